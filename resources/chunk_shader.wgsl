@@ -52,6 +52,20 @@ fn normal_to_rot(pos: vec3f, normal: u32) -> vec3f {
 	return out;
 }
 
+// Quad corners before they are turned to the face (corner 0 is the texture origin, u runs to corner 1 and v to corner 3)
+const CORNERS: array<vec3f, 4> = array<vec3f, 4>(
+	vec3f(0.0, 0.0, 0.0),
+	vec3f(0.0, 0.0, 1.0),
+	vec3f(1.0, 0.0, 1.0),
+	vec3f(1.0, 0.0, 0.0)
+);
+const CRACK_STAGES: f32 = 10.0;
+const DROPPED_SCALE: f32 = 0.25; // size of a dropped item's cube
+const PI: f32 = 3.14159265;
+
+// Brightness of the ambient occlusion levels (0 is a corner boxed in by blocks)
+const AO_BRIGHTNESS: array<f32, 4> = array<f32, 4>(0.45, 0.65, 0.82, 1.0);
+
 const NORMALS: array<vec3f, 6> = array<vec3f, 6>(
 	vec3f(-1.0, 0.0, 0.0),   // [0] Left face
 	vec3f(1.0, 0.0, 0.0),    // [1] Right face
@@ -66,29 +80,66 @@ struct VertexOutput {
 	@location(0) world_normal: vec3f,
 	@location(1) uv: vec2f,
 	@location(2) id: u32,
+	@location(3) alpha: f32,
+	@location(4) brightness: f32,
+	@location(5) crack: u32,
 };
 
+const TRANSLUCENT_ALPHA: f32 = 0.7; // the block textures have no alpha so translucent faces get a fixed one
+const MIN_BRIGHTNESS: f32 = 0.05; // so a cave is dark but not pitch black
+
+// Every light level is 80% as bright as the one above it
+fn light_to_brightness(light: u32) -> f32 {
+	let block = f32(light & 0xFu);
+	let sky = f32((light >> 4u) & 0xFu);
+	return max(pow(0.8, 15.0 - max(block, sky)), MIN_BRIGHTNESS);
+}
+
 @vertex
 fn vs_main(
 	@location(0) vertex_data: u32,
 	@location(1) instance_data: u32,
-	@builtin(vertex_index) vert_idx: u32
+	@location(2) light_data: u32,
+	@location(3) shape_data: u32
 ) -> VertexOutput {
-	// Unpack vertex position using 4-bit extractor
-	let vertex_pos = to_chunk_pos(vertex_data);
+	// Quad corner of the vertex, the other triangulation when the diagonal is flipped (bit 16)
+	let flipped = ((light_data >> 16u) & 0x1u) == 1u;
+	let corner = select(vertex_data & 0x3u, (vertex_data >> 2u) & 0x3u, flipped);
+	let vertex_pos = CORNERS[corner];
 	// Unpack instance position
 	let instance_pos = to_chunk_pos(instance_data);
 	
 	// Get normal from instance data (bits 12-15)
 	let normal_idx = (instance_data >> 12u) & 0x7u;
 
-	let model_pos = normal_to_rot(vertex_pos, normal_idx); // Combine vertex and instance positions
+	let cube_pos = normal_to_rot(vertex_pos, normal_idx); // Combine vertex and instance positions
+	// the box the face belongs to (5 bits per value, in sixteenths): min x, y, z then max x, y, z
+	let box_min = vec3f(f32(shape_data & 0x1Fu), f32((shape_data >> 5u) & 0x1Fu), f32((shape_data >> 10u) & 0x1Fu)) / 16.0;
+	let box_max = vec3f(f32((shape_data >> 15u) & 0x1Fu), f32((shape_data >> 20u) & 0x1Fu), f32((shape_data >> 25u) & 0x1Fu)) / 16.0;
+	var model_pos = box_min + cube_pos * (box_max - box_min);
+	// the texture is cut to the box instead of squeezed into it, along the directions the corner uvs run on the full cube face
+	let uv_origin = normal_to_rot(CORNERS[0], normal_idx);
+	let u_dir = normal_to_rot(CORNERS[1], normal_idx) - uv_origin;
+	let v_dir = normal_to_rot(CORNERS[3], normal_idx) - uv_origin;
+	let uv = vec2f(dot(model_pos - uv_origin, u_dir), dot(model_pos - uv_origin, v_dir));
+	// dropped items (bit 21) shrink to a small cube standing in the middle of the block's floor
+	if ((light_data >> 21u) & 0x1u) == 1u {
+		let margin = (1.0 - DROPPED_SCALE) / 2.0;
+		model_pos = model_pos * DROPPED_SCALE + vec3f(margin, 0.0, margin);
+	}
 	
 	let normal = NORMALS[normal_idx];
 	
 	var output: VertexOutput;
 
 	output.id = (instance_data >> 16u) & 0xFFFu; // (bits 16-32)
+	// translucent flag (bit 15)
+	output.alpha = select(1.0, TRANSLUCENT_ALPHA, ((instance_data >> 15u) & 0x1u) == 1u);
+	// block light in bits 0-3, sky light in bits 4-7, ambient occlusion 2 bits per corner from bit 8
+	let ao = (light_data >> (8u + corner * 2u)) & 0x3u;
+	output.brightness = light_to_brightness(light_data) * AO_BRIGHTNESS[ao];
+	// crack overlay stage (bits 17-20), 0 for every normal face
+	output.crack = (light_data >> 17u) & 0xFu;
 	
 	// Apply chunk position (as translation), then camera view_proj
 	let world_pos = to_world_pos(chunk_pos) + model_pos + instance_pos;
@@ -96,17 +147,8 @@ fn vs_main(
 	
 	output.world_normal = normal;
 	
-	// Calculate UV based on original vertex positions
-	// Since your quad is defined with positions:
-	// [0,0,0], [0,0,1], [1,0,1], [1,0,1], [1,0,0], [0,0,0]
-	// The UVs should be:
-	// [0,0], [1,0], [1,1], [1,1], [0,1], [0,0]
-	let local_idx = vert_idx % 6u;
-	// Replace the UV calculation with:
-	output.uv = vec2f(
-		f32(local_idx >= 1u && local_idx <= 3u),
-		f32(local_idx >= 2u && local_idx <= 4u)
-	);
+	// UV follows the corner so a flipped quad keeps its texture the same way around
+	output.uv = uv;
 	
 	return output;
 }
@@ -118,6 +160,25 @@ fn vs_main(
 //@group(3) @binding(0) var<uniform> data: u32;
 
 
+fn crack_hash(n: f32) -> f32 {
+	return fract(sin(n * 127.1) * 43758.5453);
+}
+
+// Jagged dark rays from the middle of the face, growing longer with every stage
+fn crack_color(uv: vec2f, stage: u32) -> vec4f {
+	let p = uv - vec2f(0.5);
+	let dist = length(p) * 2.0;
+	let rays = 7.0;
+	let sector = (atan2(p.y, p.x) / (2.0 * PI) + 0.5) * rays;
+	let ray = floor(sector);
+	let reach = f32(stage) / CRACK_STAGES * (0.6 + 0.6 * crack_hash(ray));
+	let wobble = sin(dist * 14.0 + ray * 3.0) * 0.12;
+	let offset = abs(fract(sector) - 0.5 + (crack_hash(ray + 0.5) - 0.5) * 0.4 + wobble);
+	let width = 0.06 / max(dist, 0.1);
+	let on_ray = offset < width && dist < reach;
+	return vec4f(0.0, 0.0, 0.0, select(0.0, 0.75, on_ray));
+}
+
 @fragment
 fn fs_main(vertex: VertexOutput) -> @location(0) vec4f {
 	let light_dir = normalize(vec3f(0.5, 1.0, 0.5));
@@ -129,6 +190,10 @@ fn fs_main(vertex: VertexOutput) -> @location(0) vec4f {
 	let final_light = mix(0.35 + 0.55 * directional, hemi, 0.3);
 	
 	let texture_color = textureSample(t_diffuse, s_diffuse, vertex.uv, vertex.id);
-	return vec4f(texture_color.rgb * final_light, texture_color.a);
+	// sampled before branching, texture sampling has to stay in uniform control flow
+	if vertex.crack > 0u {
+		return crack_color(vertex.uv, vertex.crack);
+	}
+	return vec4f(texture_color.rgb * final_light * vertex.brightness, texture_color.a * vertex.alpha);
 }
 
@@ -0,0 +1,35 @@
+use crate::block::main::Material;
+use crate::block::props::mat;
+use crate::game::inventory::ItemContainer;
+use crate::ui::inventory::SlotCount;
+
+/// Size of the storage a single chest holds
+pub const CHEST_SIZE: SlotCount = SlotCount::SMALL;
+
+/// Extra per-block data that does not fit into the palette (stored by the chunk, keyed by block index)
+#[derive(Clone, Debug, PartialEq)]
+pub enum BlockEntity {
+	Chest(ItemContainer),
+}
+
+impl BlockEntity {
+	/// Creates the default entity of a material, `None` if the material has no entity
+	#[inline] pub fn for_material(material: Material) -> Option<Self> {
+		match material {
+			mat::CHEST => Some(Self::Chest(ItemContainer::new(CHEST_SIZE.rows, CHEST_SIZE.columns))),
+			_ => None,
+		}
+	}
+
+	/// The storage of this entity, if it has any
+	#[inline] pub const fn container(&self) -> Option<&ItemContainer> {
+		match self {
+			Self::Chest(container) => Some(container),
+		}
+	}
+	#[inline] pub const fn container_mut(&mut self) -> Option<&mut ItemContainer> {
+		match self {
+			Self::Chest(container) => Some(container),
+		}
+	}
+}
@@ -1,9 +1,14 @@
 
 use crate::player::Player;
 use crate::ext::ptr;
-use crate::block::math::ChunkCoord;
+use crate::block::math::{Axis, ChunkCoord};
 use crate::game::player::Camera;
 use crate::block::main::Block;
+use crate::block::{farming, loot};
+use crate::game::inventory::Inventory;
+use crate::game::items::{ItemStack, ToolFlags};
+use crate::hs::math::Rand;
+use crate::physic::ray::VoxelRay;
 use crate::world::main::World;
 use glam::{Vec3, IVec3};
 
@@ -46,72 +51,27 @@ fn update_chunk_mesh(world: &mut World, chunk_coord: ChunkCoord) {
 /// Optimized raycasting function using IVec3 for block positions
 #[inline]
 pub fn raycast_to_block(camera: &Camera, player: &Player, world: &World, max_distance: f32) -> Option<(IVec3, IVec3)> {
-	let ray_origin = player.cam_pos();
-	let ray_dir = camera.forward();
-	
-	// Initialize variables for DDA algorithm
-	let step = Vec3::new(ray_dir.x.signum(), ray_dir.y.signum(), ray_dir.z.signum());
-	let step_i = IVec3::new(step.x as i32, step.y as i32, step.z as i32);
-	
-	// Use IVec3 for block position
-	let mut block_pos = IVec3::new(
-		ray_origin.x.floor() as i32,
-		ray_origin.y.floor() as i32,
-		ray_origin.z.floor() as i32,
-	);
-	
-	let t_delta = Vec3::new(
-		1.0 / ray_dir.x.abs().max(f32::MIN_POSITIVE),
-		1.0 / ray_dir.y.abs().max(f32::MIN_POSITIVE),
-		1.0 / ray_dir.z.abs().max(f32::MIN_POSITIVE),
-	);
-	
-	let mut t_max = Vec3::new(
-		if step.x > 0.0 {
-			(block_pos.x + 1) as f32 - ray_origin.x
-		} else {
-			ray_origin.x - block_pos.x as f32
-		} / ray_dir.x.abs().max(f32::MIN_POSITIVE),
-		if step.y > 0.0 {
-			(block_pos.y + 1) as f32 - ray_origin.y
-		} else {
-			ray_origin.y - block_pos.y as f32
-		} / ray_dir.y.abs().max(f32::MIN_POSITIVE),
-		if step.z > 0.0 {
-			(block_pos.z + 1) as f32 - ray_origin.z
-		} else {
-			ray_origin.z - block_pos.z as f32
-		} / ray_dir.z.abs().max(f32::MIN_POSITIVE),
-	);
-	
-	let mut normal = IVec3::ZERO;
-	let mut traveled = 0.0f32;
-	
-	while traveled < max_distance {
-		// Check current block - now using IVec3
-		if !world.get_block(block_pos).is_empty() {
-			return Some((block_pos, normal));
+	raycast(world, player.cam_pos(), camera.forward(), max_distance).map(|(block_pos, normal, _)| (block_pos, normal))
+}
+
+/// Finds the first block the ray hits: its position, the normal of the face hit and the point hit
+/// Blocks that are not full cubes only count where the ray hits one of their boxes, fluids are looked through
+pub fn raycast(world: &World, ray_origin: Vec3, ray_dir: Vec3, max_distance: f32) -> Option<(IVec3, IVec3, Vec3)> {
+	for (block_pos, normal, traveled) in VoxelRay::new(ray_origin, ray_dir).take_while(|(_, _, traveled)| *traveled < max_distance) {
+		let block = world.get_block(block_pos);
+		if block.is_empty() || block.props().fluid {
+			continue;
 		}
-		
-		// Move to next block boundary
-		if t_max.x < t_max.y && t_max.x < t_max.z {
-			normal = IVec3::new(-step_i.x, 0, 0);
-			block_pos.x += step_i.x;
-			traveled = t_max.x;
-			t_max.x += t_delta.x;
-		} else if t_max.y < t_max.z {
-			normal = IVec3::new(0, -step_i.y, 0);
-			block_pos.y += step_i.y;
-			traveled = t_max.y;
-			t_max.y += t_delta.y;
-		} else {
-			normal = IVec3::new(0, 0, -step_i.z);
-			block_pos.z += step_i.z;
-			traveled = t_max.z;
-			t_max.z += t_delta.z;
+		if block.props().shape.is_none() {
+			return Some((block_pos, normal, ray_origin + ray_dir * traveled));
+		}
+		let hit = block.shape().iter()
+			.filter_map(|shape| shape.ray_hit(block_pos, ray_origin, ray_dir))
+			.min_by(|a, b| a.0.total_cmp(&b.0));
+		if let Some((distance, normal)) = hit.filter(|hit| hit.0 <= max_distance) {
+			return Some((block_pos, normal, ray_origin + ray_dir * distance));
 		}
 	}
-	
 	None
 }
 
@@ -125,30 +85,156 @@ pub fn place_looked_block() {
 	let player = &ptr::get_gamestate().player();
 	let world = &mut ptr::get_gamestate().world_mut();
 
-	if let Some((block_pos, normal)) = raycast_to_block(player.camera(), player, world, REACH) {
+	if let Some((block_pos, normal, hit)) = raycast(world, player.cam_pos(), player.camera().forward(), REACH) {
 		let placement_pos = block_pos + normal;
-		let block_id = player.inventory()
-		    .selected_item()
-		    .and_then(|item| item.get_block_id())
-		    .map_or(1, |block_id| block_id);
+		let Some(block_id) = player.inventory().selected_item().and_then(|item| item.get_block_id()) else {
+			return; // only blocks can be placed
+		};
+		// against the bottom of a block, or the upper half of a side, goes into the upper half of the space
+		let upper = normal == IVec3::NEG_Y || (normal.y == 0 && hit.y - hit.y.floor() > 0.5);
+		let mut block = Block::new(block_id);
+		if block.props().support.is_some_and(|support| world.get_block(placement_pos - IVec3::Y).material() != support) {
+			return; // seeds only go into farmland ...
+		}
+		if let Some(rotation) = block.props().placement.rotation(horizontal_facing(player.camera().forward()), upper) {
+			block.set_rotation(rotation);
+		}
 
-		world.set_block(placement_pos, Block::new(block_id));
+		world.set_block(placement_pos, block);
 		update_chunk_mesh(world, ChunkCoord::from_world_pos(placement_pos));
+		use_up_selected_item(ptr::get_gamestate().player_mut().inventory_mut());
 	}
 }
 
-/// Removes the block the player is looking at
-pub fn remove_targeted_block() {
+/// The horizontal axis closest to the direction
+#[inline] fn horizontal_facing(direction: Vec3) -> Axis {
+	if direction.x.abs() > direction.z.abs() {
+		if direction.x > 0.0 { Axis::Xplus } else { Axis::Xminus }
+	} else if direction.z > 0.0 {
+		Axis::Zplus
+	} else {
+		Axis::Zminus
+	}
+}
+
+/// Takes one from the selected stack, the slot empties when it runs out
+pub fn use_up_selected_item(inventory: &mut Inventory) {
+	let slot = inventory.selected_slot_idx();
+	let Some(item) = inventory.selected_item_mut() else { return; };
+	item.quantity = item.quantity.saturating_sub(1);
+	if item.quantity == 0 {
+		inventory.hotbar_mut().set(slot, None);
+	}
+}
+
+/// Starts or stops mining (the mine key went down or up)
+#[inline]
+pub fn set_mining(held: bool) {
+	ptr::get_gamestate().mining_mut().held = held;
+}
+
+/// Mines the block the player is looking at while the mine key is held
+/// The break time depends on the block's hardness and the selected tool, the tool wears on every break
+/// The drops go into the inventory, or fall out of the block when they do not fit
+pub fn update_mining(delta_seconds: f32) {
 	let state = ptr::get_state();
 	if !state.is_world_running {
 		return;
 	}
-	let world = &mut ptr::get_gamestate().world_mut();
+	let game_state = ptr::get_gamestate();
+	let looked = raycast_to_block(game_state.player().camera(), game_state.player(), game_state.world(), REACH)
+		.map(|(block_pos, _)| block_pos);
+	let tool = game_state.player().inventory().selected_item().and_then(|item| item.tool());
+	let break_time = looked.and_then(|block_pos| game_state.world().get_block(block_pos).props().break_time(tool));
 
-	if let Some((block_pos, _)) = raycast_to_block(ptr::get_gamestate().player().camera(),ptr::get_gamestate().player(), world, REACH) {
-		world.set_block(block_pos, Block::None);
+	let broken = game_state.mining_mut().step(looked, break_time, delta_seconds);
+	game_state.mining_mut().make_mesh(state.device());
+	let Some(block_pos) = broken else { return; };
+
+	let world = game_state.world_mut();
+	let drops = break_block(world, block_pos, &mut Rand::from_time());
+	update_chunk_mesh(world, ChunkCoord::from_world_pos(block_pos));
+
+	let inventory = game_state.player_mut().inventory_mut();
+	let slot = inventory.selected_slot_idx();
+	if inventory.selected_item_mut().is_some_and(|item| item.damage()) {
+		inventory.hotbar_mut().set(slot, None); // worn out
+	}
+	// whatever does not fit in the inventory falls out of the block
+	for stack in drops {
+		if !game_state.player_mut().inventory_mut().add_item_anywhere(stack.clone()) {
+			game_state.world_mut().drop_item(stack, block_pos.as_vec3() + Vec3::splat(0.5));
+		}
+	}
+}
+
+/// Removes the block and returns what it drops: its loot and whatever its storage held
+pub fn break_block(world: &mut World, block_pos: IVec3, rand: &mut Rand) -> Vec<ItemStack> {
+	let mut drops = loot::roll(world.get_block(block_pos), rand);
+	if let Some(container) = world.get_block_entity(block_pos).and_then(|entity| entity.container()) {
+		drops.extend(container.iter().flatten().cloned());
+	}
+	world.set_block(block_pos, Block::None);
+	drops
+}
+
+/// Picks up the dropped items lying around the player
+pub fn pick_up_dropped_items() {
+	let player = ptr::get_gamestate().player_mut();
+	ptr::get_gamestate().world_mut().pick_up_dropped_items(player.pos(), player.inventory_mut());
+}
+
+/// Uses the looked at block (flips a lever, opens a door, tills dirt with a hoe ...)
+/// Blocks that do nothing when used get a block placed against them instead
+pub fn use_looked_block() {
+	let state = ptr::get_state();
+	if !state.is_world_running {
+		return;
+	}
+	let game_state = ptr::get_gamestate();
+	let Some((block_pos, _)) = raycast_to_block(game_state.player().camera(), game_state.player(), game_state.world(), REACH) else {
+		return;
+	};
+	let hoe = game_state.player().inventory().selected_item()
+		.and_then(|item| item.tool())
+		.is_some_and(|tool| tool.any(ToolFlags::HOE));
+	let world = game_state.world_mut();
+	if hoe && farming::till(world, block_pos) {
 		update_chunk_mesh(world, ChunkCoord::from_world_pos(block_pos));
+		let inventory = game_state.player_mut().inventory_mut();
+		let slot = inventory.selected_slot_idx();
+		if inventory.selected_item_mut().is_some_and(|item| item.damage()) {
+			inventory.hotbar_mut().set(slot, None); // worn out
+		}
+		return;
+	}
+	let Some(on_use) = world.get_block(block_pos).props().on_use else {
+		place_looked_block();
+		return;
+	};
+	on_use(world, block_pos);
+	update_chunk_mesh(world, ChunkCoord::from_world_pos(block_pos));
+}
+
+/// Remembers the looked at block entity's storage so the storage UI can show it
+/// Returns false if the player is not looking at anything with a storage
+pub fn open_looked_storage() -> bool {
+	let state = ptr::get_state();
+	if !state.is_world_running {
+		return false;
+	}
+	let game_state = ptr::get_gamestate();
+	let Some((block_pos, _)) = raycast_to_block(game_state.player().camera(), game_state.player(), game_state.world(), REACH) else {
+		return false;
+	};
+	let has_storage = game_state.world()
+		.get_block_entity(block_pos)
+		.and_then(|entity| entity.container())
+		.is_some();
+	if has_storage {
+		*game_state.open_storage() = Some(block_pos);
 	}
+	has_storage
 }
 
 
@@ -0,0 +1,126 @@
+use crate::block::light::{LightKind, MAX_LIGHT};
+use crate::block::loot;
+use crate::block::main::Block;
+use crate::block::props::mat;
+use crate::block::shape::{BlockBox, UNITS};
+use crate::hs::math::Rand;
+use crate::world::main::World;
+use glam::{IVec3, Vec3};
+
+// The state byte of a crop: its growth stage (0 just planted - `MAX_STAGE` ripe)
+// The state byte of a farmland: its moisture (0 dry - `MAX_MOISTURE` watered)
+
+/// Growth stage of a ripe crop
+pub const MAX_STAGE: u8 = 7;
+/// Moisture of a farmland next to water, it dries out one step on every random tick away from it
+pub const MAX_MOISTURE: u8 = 7;
+/// Crops do not grow in a darker place than this
+pub const MIN_GROW_LIGHT: u8 = 9;
+/// Chance of a crop growing a stage on a random tick, in full light on dry farmland
+const GROW_CHANCE: f32 = 0.25;
+/// Growth multiplier of a crop on moist farmland
+const WATER_BONUS: f32 = 2.0;
+/// Horizontal distance water keeps a farmland moist from (on its level or one above)
+const WATER_RANGE: i32 = 4;
+
+// Farmland sits a sixteenth lower than a full block, crops get taller with every stage
+static FARMLAND: [BlockBox; 1] = [BlockBox::new([0, 0, 0], [UNITS, UNITS - 1, UNITS])];
+static CROP: [[BlockBox; 1]; MAX_STAGE as usize + 1] = crop_shapes();
+
+const fn crop_shapes() -> [[BlockBox; 1]; MAX_STAGE as usize + 1] {
+	let mut shapes = [[BlockBox::FULL]; MAX_STAGE as usize + 1];
+	let mut stage = 0;
+	while stage <= MAX_STAGE as usize {
+		shapes[stage] = [BlockBox::new([2, 0, 2], [UNITS - 2, (stage as u8 + 1) * 2, UNITS - 2])];
+		stage += 1;
+	}
+	shapes
+}
+
+/// Is there water close enough to the farmland to keep it moist
+pub fn water_nearby(world: &World, pos: IVec3) -> bool {
+	for y in 0..=1 {
+		for x in -WATER_RANGE..=WATER_RANGE {
+			for z in -WATER_RANGE..=WATER_RANGE {
+				if world.get_block(pos + IVec3::new(x, y, z)).material() == mat::WATER {
+					return true;
+				}
+			}
+		}
+	}
+	false
+}
+
+/// Chance of the crop at the position growing a stage on a random tick
+/// It grows faster in brighter light (block or sky) and on moist farmland, not at all in the dark
+pub fn growth_chance(world: &World, pos: IVec3) -> f32 {
+	let light = world.get_light(pos);
+	let level = LightKind::Block.get(light).max(LightKind::Sky.get(light));
+	if level < MIN_GROW_LIGHT {
+		return 0.0;
+	}
+	let below = world.get_block(pos - IVec3::Y);
+	let moist = below.material() == mat::FARMLAND && below.state() > 0;
+	GROW_CHANCE * level as f32 / MAX_LIGHT as f32 * if moist { WATER_BONUS } else { 1.0 }
+}
+
+/// Turns dirt (or grass) under nothing into farmland, returns false if it can not be tilled
+pub fn till(world: &mut World, pos: IVec3) -> bool {
+	let material = world.get_block(pos).material();
+	if !matches!(material, mat::DIRT | mat::DIRT_GRASS | mat::GRASS) || !world.get_block(pos + IVec3::Y).is_empty() {
+		return false;
+	}
+	let moisture = if water_nearby(world, pos) { MAX_MOISTURE } else { 0 };
+	let mut farmland = Block::new(mat::FARMLAND);
+	farmland.set_state(moisture);
+	world.set_block(pos, farmland);
+	true
+}
+
+//
+// Block behaviors used by the props table
+//
+
+/// Random tick of the crops: grows a stage now and then, until it is ripe
+pub fn crop_grow(world: &mut World, pos: IVec3, rand: &mut Rand) {
+	let stage = world.get_block(pos).state();
+	if stage < MAX_STAGE && rand.next_f32() < growth_chance(world, pos) {
+		world.set_block_state(pos, stage + 1);
+	}
+}
+
+/// Scheduled tick of the blocks that have to stand on a certain block: they pop off with their loot when it goes away
+pub fn support_check(world: &mut World, pos: IVec3, rand: &mut Rand) {
+	let block = *world.get_block(pos);
+	let Some(support) = block.props().support else { return; };
+	if world.get_block(pos - IVec3::Y).material() == support {
+		return;
+	}
+	world.set_block(pos, Block::None);
+	for stack in loot::roll(&block, rand) {
+		world.drop_item(stack, pos.as_vec3() + Vec3::splat(0.5));
+	}
+}
+
+/// Random tick of the farmland: water nearby keeps it moist, away from it it dries out
+/// Dry farmland with nothing planted, or farmland under an opaque block, turns back into dirt
+pub fn farmland_tick(world: &mut World, pos: IVec3, _rand: &mut Rand) {
+	let above = *world.get_block(pos + IVec3::Y);
+	let moisture = world.get_block(pos).state();
+	if above.props().is_opaque() || (moisture == 0 && above.material() != mat::CROP) {
+		world.set_block(pos, Block::new(mat::DIRT));
+		return;
+	}
+	let moisture = if water_nearby(world, pos) { MAX_MOISTURE } else { moisture.saturating_sub(1) };
+	world.set_block_state(pos, moisture);
+}
+
+/// Farmland is a little lower than the blocks around it
+pub fn farmland_shape(_block: &Block) -> &'static [BlockBox] {
+	&FARMLAND
+}
+
+/// A crop is as tall as it is grown
+pub fn crop_shape(block: &Block) -> &'static [BlockBox] {
+	&CROP[block.state().min(MAX_STAGE) as usize]
+}
@@ -0,0 +1,103 @@
+use crate::block::main::{Block, Material};
+use crate::hs::math::Rand;
+use crate::world::main::World;
+use glam::IVec3;
+
+// The state byte of a fluid block:
+// bits 0-2: level, 0 is a source, 1-7 flowing further and further away from it
+// bit 3: falling, fed by the same fluid from above
+
+/// Ticks between two flow steps
+pub const FLOW_DELAY: u32 = 5;
+/// The last level that still spreads sideways
+pub const MAX_LEVEL: u8 = 7;
+const LEVEL_MASK: u8 = 0b0111;
+const FALLING: u8 = 0b1000;
+
+const HORIZONTAL: [IVec3; 4] = [IVec3::X, IVec3::NEG_X, IVec3::Z, IVec3::NEG_Z];
+
+#[inline] pub const fn level(state: u8) -> u8 { state & LEVEL_MASK }
+#[inline] pub const fn is_source(state: u8) -> bool { state == 0 }
+#[inline] pub const fn is_falling(state: u8) -> bool { state & FALLING != 0 }
+
+/// Can the fluid wash this block away
+#[inline] pub fn can_flow_into(block: &Block) -> bool {
+	block.is_empty()
+}
+
+/// The level a fluid block passes on to its sides (falling fluid spreads like a source where it lands)
+#[inline] const fn spread_level(state: u8) -> u8 {
+	if is_falling(state) { 1 } else { level(state) + 1 }
+}
+
+/// What a flowing (non source) block should be, `None` if nothing feeds it anymore
+fn fed_state(world: &World, pos: IVec3, material: Material) -> Option<u8> {
+	if world.get_block(pos + IVec3::Y).material() == material {
+		return Some(FALLING);
+	}
+
+	let mut sources = 0;
+	let mut fed_level: Option<u8> = None;
+	for side in HORIZONTAL {
+		let block = world.get_block(pos + side);
+		if block.material() != material {
+			continue;
+		}
+		if is_source(block.state()) {
+			sources += 1;
+		}
+		let next = spread_level(block.state());
+		fed_level = Some(fed_level.map_or(next, |current| current.min(next)));
+	}
+
+	// two sources refill the block between them, if it has something to rest on
+	let below = world.get_block(pos - IVec3::Y);
+	let supported = below.props().is_opaque() || (below.material() == material && is_source(below.state()));
+	if sources >= 2 && supported {
+		return Some(0);
+	}
+	fed_level.filter(|level| *level <= MAX_LEVEL)
+}
+
+/// Scheduled tick of fluids: updates the own level, then flows down, or sideways if it can not
+/// Every change goes through `World::set_block` which schedules the fluid around it again
+pub fn flow_tick(world: &mut World, pos: IVec3, _rand: &mut Rand) {
+	let block = *world.get_block(pos);
+	let material = block.material();
+	let mut state = block.state();
+
+	if !is_source(state) {
+		match fed_state(world, pos, material) {
+			None => {
+				world.set_block(pos, Block::None);
+				return;
+			}
+			Some(fed) if fed != state => {
+				world.set_block(pos, Block::with_state(material, fed));
+				state = fed;
+			}
+			_ => {}
+		}
+	}
+
+	let below = *world.get_block(pos - IVec3::Y);
+	if can_flow_into(&below) {
+		world.set_block(pos - IVec3::Y, Block::with_state(material, FALLING));
+		return;
+	}
+	if below.material() == material {
+		return; // already pouring into itself
+	}
+
+	let next = spread_level(state);
+	if next > MAX_LEVEL {
+		return;
+	}
+	for side in HORIZONTAL {
+		let target = pos + side;
+		let current = *world.get_block(target);
+		if can_flow_into(&current) {
+			world.set_block(target, Block::with_state(material, next));
+		}
+	}
+}
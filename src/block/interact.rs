@@ -0,0 +1,71 @@
+use crate::block::main::Block;
+use crate::block::shape::{self, BlockBox, NO_BOXES};
+use crate::block::signal;
+use crate::world::main::World;
+use glam::IVec3;
+
+// The state byte of a door or trapdoor:
+// bit 0: powered (the signal reaches it)
+// bit 1: open
+
+/// What a block does when the player uses it (world, world position of the block)
+pub type UseFn = fn(&mut World, IVec3);
+
+const POWERED: u8 = 1 << 0;
+const OPEN: u8 = 1 << 1;
+/// Thickness of a door or trapdoor panel (sixteenths)
+const PANEL: u8 = 3;
+
+#[inline] pub const fn is_open(state: u8) -> bool { state & OPEN != 0 }
+
+// Shapes of a block facing +X, turned to the other facings
+// A door stands on the far side of its block and swings to the right, a trapdoor lies on the floor and folds up against the far side
+static DOOR_CLOSED: [[BlockBox; 1]; 4] = shape::all_turns([BlockBox::new([16 - PANEL, 0, 0], [16, 16, 16])]);
+static DOOR_OPEN: [[BlockBox; 1]; 4] = shape::all_turns([BlockBox::new([0, 0, 0], [16, 16, PANEL])]);
+static TRAPDOOR_CLOSED: [[BlockBox; 1]; 4] = shape::all_turns([BlockBox::new([0, 0, 0], [16, PANEL, 16])]);
+static TRAPDOOR_CLOSED_TOP: [[BlockBox; 1]; 4] = shape::all_turns([BlockBox::new([0, 16 - PANEL, 0], [16, 16, 16])]);
+static TRAPDOOR_OPEN: [[BlockBox; 1]; 4] = shape::all_turns([BlockBox::new([16 - PANEL, 0, 0], [16, 16, 16])]);
+
+//
+// Block behaviors used by the props table
+//
+
+/// Use of the doors and trapdoors: swings them open or shut
+pub fn use_door(world: &mut World, pos: IVec3) {
+	let state = world.get_block(pos).state();
+	world.set_block_state(pos, state ^ OPEN);
+}
+
+/// Neighbor update of the doors and trapdoors: they open when the signal reaches them and close when it goes away
+/// Opened by hand they stay open until the signal changes
+pub fn door_update(world: &mut World, pos: IVec3, _source: IVec3) {
+	let state = world.get_block(pos).state();
+	let powered = signal::received(world, pos) > 0;
+	if powered == (state & POWERED != 0) {
+		return;
+	}
+	world.set_block_state(pos, if powered { POWERED | OPEN } else { 0 });
+}
+
+/// A door panel, on the far side while closed and swung to the right while open
+pub fn door_shape(block: &Block) -> &'static [BlockBox] {
+	let table = if is_open(block.state()) { &DOOR_OPEN } else { &DOOR_CLOSED };
+	&table[shape::quarters(block.facing())]
+}
+
+/// A trapdoor panel, on the floor (against the ceiling in the upper half) while closed and folded up against the far side while open
+pub fn trapdoor_shape(block: &Block) -> &'static [BlockBox] {
+	let table = if is_open(block.state()) {
+		&TRAPDOOR_OPEN
+	} else if shape::is_upper(block) {
+		&TRAPDOOR_CLOSED_TOP
+	} else {
+		&TRAPDOOR_CLOSED
+	};
+	&table[shape::quarters(block.facing())]
+}
+
+/// Open doors and trapdoors let everything through
+pub fn door_collision(block: &Block) -> &'static [BlockBox] {
+	if is_open(block.state()) { &NO_BOXES } else { block.shape() }
+}
@@ -0,0 +1,407 @@
+use crate::block::main::{Block, Chunk};
+use crate::block::signal::{self, SignalRole};
+use crate::block::math::{ADJACENT, BlockPosition, ChunkCoord};
+use crate::world::main::World;
+use glam::IVec3;
+use std::collections::{HashMap, VecDeque};
+
+// A light byte of a block:
+// bits 0-3: block light (lamps ...)
+// bits 4-7: sky light
+
+/// Brightest light level, the sky light of every block the sky sees directly
+pub const MAX_LIGHT: u8 = 15;
+
+/// The two kinds of light, spread separately
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LightKind {
+	Block, // from emissive blocks
+	Sky,   // from above, does not fade going straight down through clear blocks
+}
+
+impl LightKind {
+	pub const ALL: [Self; 2] = [Self::Block, Self::Sky];
+
+	#[inline] const fn shift(self) -> u8 {
+		match self {
+			Self::Block => 0,
+			Self::Sky => 4,
+		}
+	}
+	/// The level of this kind in a light byte
+	#[inline] pub const fn get(self, light: u8) -> u8 {
+		(light >> self.shift()) & 0xF
+	}
+	/// The light byte with this kind set to the level
+	#[inline] pub const fn with(self, light: u8, level: u8) -> u8 {
+		(light & !(0xF << self.shift())) | (level << self.shift())
+	}
+}
+
+/// Light bytes of a chunk, a single value until something differs (like `BlockStorage`)
+/// Not saved, it gets computed again when the chunk is loaded
+#[derive(Debug, Clone, PartialEq)]
+pub enum LightStorage {
+	Uniform(u8),
+	Sparse(Box<[u8; 4096]>),
+}
+
+impl LightStorage {
+	#[inline] pub const fn get(&self, index: usize) -> u8 {
+		match self {
+			LightStorage::Uniform(light) => *light,
+			LightStorage::Sparse(lights) => lights[index],
+		}
+	}
+
+	#[inline]
+	pub fn set(&mut self, index: usize, light: u8) {
+		match self {
+			LightStorage::Uniform(current) => {
+				if *current != light {
+					let mut lights = Box::new([*current; 4096]);
+					lights[index] = light;
+					*self = LightStorage::Sparse(lights);
+				}
+			}
+			LightStorage::Sparse(lights) => lights[index] = light,
+		}
+	}
+}
+
+/// Block columns in a chunk column
+const COLUMNS: usize = Chunk::SIZE * Chunk::SIZE;
+
+/// The highest block of every column the sky can't see through, kept by chunk column
+/// It starts out as the ground of the generator and follows the chunks loaded and the blocks changed,
+/// where the chunk is not loaded it is only a guess from above (never lower than what was seen)
+#[derive(Debug, Clone, Default)]
+pub struct SkyMap {
+	columns: HashMap<(i32, i32), Box<[i32; COLUMNS]>>, // chunk column -> heights, x * SIZE + z
+}
+
+impl SkyMap {
+	#[inline] pub fn new() -> Self {
+		Self::default()
+	}
+
+	#[inline] fn key(x: i32, z: i32) -> ((i32, i32), usize) {
+		let (cx, cz) = (x.div_euclid(Chunk::SIZE_I), z.div_euclid(Chunk::SIZE_I));
+		((cx, cz), (x.rem_euclid(Chunk::SIZE_I) * Chunk::SIZE_I + z.rem_euclid(Chunk::SIZE_I)) as usize)
+	}
+
+	/// The height kept for the column, None before any chunk of its chunk column was loaded
+	#[inline] pub fn get(&self, x: i32, z: i32) -> Option<i32> {
+		let (key, index) = Self::key(x, z);
+		self.columns.get(&key).map(|heights| heights[index])
+	}
+
+	/// Forgets the chunk columns farther away than the radius (in chunks), none of their chunks can be loaded
+	pub fn retain_near(&mut self, center_x: i32, center_z: i32, radius_sq: i32) {
+		self.columns.retain(|(cx, cz), _| (cx - center_x).pow(2) + (cz - center_z).pow(2) <= radius_sq);
+	}
+}
+
+/// Can light pass through the block
+#[inline] fn lets_light(block: &Block) -> bool {
+	!block.props().is_opaque()
+}
+
+/// Level of the light after stepping into the target block
+#[inline] fn spread_level(kind: LightKind, level: u8, side: IVec3, target: &Block) -> u8 {
+	let falls_clear = side == IVec3::NEG_Y && !target.props().is_translucent();
+	if kind == LightKind::Sky && level == MAX_LIGHT && falls_clear {
+		MAX_LIGHT
+	} else {
+		level.saturating_sub(1)
+	}
+}
+
+/// Does the block change how light moves around it (everything else can skip the light update)
+#[inline] fn affects_light(old: &Block, new: &Block) -> bool {
+	old.props().transparency != new.props().transparency || old.light_emission() != new.light_emission()
+}
+
+impl Block {
+	/// Block light level it shines with (a signal lamp only while powered)
+	#[inline] pub fn light_emission(&self) -> u8 {
+		let props = self.props();
+		if props.signal == Some(SignalRole::Consumer) && !signal::is_on(self.state()) {
+			return 0;
+		}
+		props.light_emission
+	}
+}
+
+impl Chunk {
+	/// The light byte at the given index
+	#[inline] pub fn get_light(&self, index: usize) -> u8 {
+		self.light.get(index)
+	}
+}
+
+impl World {
+	/// The light byte of the block, 0 if its chunk is not loaded
+	#[inline] pub fn get_light(&self, world_pos: IVec3) -> u8 {
+		self.light_at(world_pos).unwrap_or(0)
+	}
+
+	#[inline] fn light_at(&self, world_pos: IVec3) -> Option<u8> {
+		let index: usize = BlockPosition::from(world_pos).into();
+		self.get_chunk(ChunkCoord::from_world_pos(world_pos)).map(|chunk| chunk.get_light(index))
+	}
+
+	/// Sets one kind of light, the chunks showing the block have to remesh
+	fn set_light(&mut self, world_pos: IVec3, kind: LightKind, level: u8) {
+		let chunk_coord = ChunkCoord::from_world_pos(world_pos);
+		let local_pos: BlockPosition = world_pos.into();
+		let Some(chunk) = self.get_chunk_mut(chunk_coord) else { return; };
+		let index: usize = local_pos.into();
+		chunk.light.set(index, kind.with(chunk.get_light(index), level));
+		chunk.dirty = true;
+		if !chunk.is_border_block(local_pos.into()) {
+			return;
+		}
+		for side in ADJACENT {
+			let coord = ChunkCoord::from_world_pos(world_pos + side);
+			if coord == chunk_coord {
+				continue;
+			}
+			if let Some(neighbor_chunk) = self.get_chunk_mut(coord) {
+				neighbor_chunk.final_mesh = false;
+			}
+		}
+	}
+
+	/// The highest block of the column the sky can't see through, as far as the world knows
+	pub fn sky_height(&self, x: i32, z: i32) -> i32 {
+		self.sky.get(x, z)
+			.or_else(|| self.generator.surface_height(x, z))
+			.unwrap_or(i32::MIN)
+	}
+
+	/// The heights of the chunk column, taken from the generator the first time
+	fn sky_heights_mut(&mut self, chunk_x: i32, chunk_z: i32) -> &mut [i32; COLUMNS] {
+		let generator = &self.generator;
+		self.sky.columns.entry((chunk_x, chunk_z)).or_insert_with(|| {
+			let mut heights = Box::new([i32::MIN; COLUMNS]);
+			for (index, height) in heights.iter_mut().enumerate() {
+				let (x, z) = ((index / Chunk::SIZE) as i32, (index % Chunk::SIZE) as i32);
+				*height = generator.surface_height(chunk_x * Chunk::SIZE_I + x, chunk_z * Chunk::SIZE_I + z).unwrap_or(i32::MIN);
+			}
+			heights
+		})
+	}
+
+	#[inline] fn set_sky_height(&mut self, x: i32, z: i32, height: i32) {
+		let ((cx, cz), index) = SkyMap::key(x, z);
+		self.sky_heights_mut(cx, cz)[index] = height;
+	}
+
+	/// Goes down the column from `y` to the first block the sky can't see through,
+	/// stopping at the top of a chunk that isn't loaded (what is in there isn't known)
+	fn settle_sky_height(&self, x: i32, mut y: i32, z: i32) -> i32 {
+		loop {
+			let pos = IVec3::new(x, y, z);
+			if self.get_chunk(ChunkCoord::from_world_pos(pos)).is_none() || !lets_light(self.get_block(pos)) {
+				return y;
+			}
+			y -= 1;
+		}
+	}
+
+	/// Fits the sky heights of the columns the chunk covers to its blocks
+	fn track_sky_chunk(&mut self, chunk_coord: ChunkCoord) {
+		let (x, y, z) = chunk_coord.unpack_to_worldpos();
+		let (bottom, top) = (y, y + Chunk::SIZE_I - 1);
+		self.sky_heights_mut(chunk_coord.x(), chunk_coord.z());
+		for dx in 0..Chunk::SIZE_I {
+			for dz in 0..Chunk::SIZE_I {
+				let (x, z) = (x + dx, z + dz);
+				let height = self.sky_height(x, z);
+				if height > top {
+					continue;
+				}
+				let covering = ((height + 1).max(bottom)..=top).rev().find(|&y| !lets_light(self.get_block(IVec3::new(x, y, z))));
+				match covering {
+					Some(y) => self.set_sky_height(x, z, y),
+					None if height >= bottom => self.set_sky_height(x, z, self.settle_sky_height(x, height, z)),
+					None => {}
+				}
+			}
+		}
+	}
+
+	/// Follows a block changing from `old` to `new` in the sky heights
+	fn track_sky_block(&mut self, world_pos: IVec3, old: &Block, new: &Block) {
+		let height = self.sky_height(world_pos.x, world_pos.z);
+		if !lets_light(new) && world_pos.y > height {
+			self.set_sky_height(world_pos.x, world_pos.z, world_pos.y);
+		} else if !lets_light(old) && lets_light(new) && world_pos.y == height {
+			let settled = self.settle_sky_height(world_pos.x, world_pos.y, world_pos.z);
+			self.set_sky_height(world_pos.x, world_pos.z, settled);
+		}
+	}
+
+	/// The sky shines straight into the block: nothing above it blocks the sky, and the block above isn't loaded
+	/// (where it is, the sky light comes down from there)
+	#[inline] fn sky_enters(&self, world_pos: IVec3) -> bool {
+		world_pos.y > self.sky_height(world_pos.x, world_pos.z) && self.light_at(world_pos + IVec3::Y).is_none()
+	}
+
+	/// Computes the light of a freshly loaded chunk, pulling in the light of its loaded neighbors
+	pub fn light_chunk(&mut self, chunk_coord: ChunkCoord) {
+		if self.get_chunk(chunk_coord).is_none() {
+			return;
+		}
+		self.track_sky_chunk(chunk_coord);
+		let Some(chunk) = self.get_chunk_mut(chunk_coord) else { return; };
+		chunk.light = LightStorage::Uniform(0);
+
+		let (x, y, z) = chunk_coord.unpack_to_worldpos();
+		let origin = IVec3::new(x, y, z);
+		let mut block_queue = VecDeque::new();
+		let mut sky_queue = VecDeque::new();
+		for index in 0..Chunk::VOLUME {
+			let local: IVec3 = BlockPosition::from(index).into();
+			let pos = origin + local;
+			let block = *self.get_block(pos);
+
+			let emission = block.light_emission();
+			if emission > 0 {
+				self.set_light(pos, LightKind::Block, emission);
+				block_queue.push_back(pos);
+			}
+			if local.y == Chunk::SIZE_I - 1 && lets_light(&block) && self.sky_enters(pos) {
+				self.set_light(pos, LightKind::Sky, MAX_LIGHT);
+				sky_queue.push_back(pos);
+			}
+			// the loaded neighbors shine in through the border
+			if local.min_element() > 0 && local.max_element() < Chunk::SIZE_I - 1 {
+				continue;
+			}
+			for side in ADJACENT {
+				let outside = pos + side;
+				if ChunkCoord::from_world_pos(outside) != chunk_coord && self.light_at(outside).is_some_and(|light| light != 0) {
+					block_queue.push_back(outside);
+					sky_queue.push_back(outside);
+				}
+			}
+		}
+		self.spread_light(LightKind::Block, block_queue);
+		self.spread_light(LightKind::Sky, sky_queue);
+
+		// the chunk below thought it saw the sky, the columns this one covers have to go dark
+		if self.get_chunk(chunk_coord.offset(0, -1, 0)).is_none() {
+			return;
+		}
+		for x in 0..Chunk::SIZE_I {
+			for z in 0..Chunk::SIZE_I {
+				let bottom = origin + IVec3::new(x, 0, z);
+				let below = bottom - IVec3::Y;
+				if LightKind::Sky.get(self.get_light(below)) == MAX_LIGHT
+					&& LightKind::Sky.get(self.get_light(bottom)) != MAX_LIGHT
+				{
+					let refill = self.remove_light(LightKind::Sky, below);
+					self.spread_light(LightKind::Sky, refill);
+				}
+			}
+		}
+	}
+
+	/// Lights every chunk of the world from scratch (after loading a save)
+	pub fn light_all_chunks(&mut self) {
+		let mut coords: Vec<ChunkCoord> = self.chunks.keys().copied().collect();
+		// top down, so the sky does not light a column that gets covered later
+		coords.sort_by_key(|coord| std::cmp::Reverse(coord.y()));
+		for coord in coords {
+			self.light_chunk(coord);
+		}
+	}
+
+	/// Updates the light around a block that changed from `old` to `new`
+	pub fn update_light(&mut self, world_pos: IVec3, old: &Block, new: &Block) {
+		if !affects_light(old, new) {
+			return;
+		}
+		self.track_sky_block(world_pos, old, new);
+		for kind in LightKind::ALL {
+			let mut refill = self.remove_light(kind, world_pos);
+			// the light around flows back in
+			refill.extend(ADJACENT.map(|side| world_pos + side));
+			match kind {
+				LightKind::Block if new.light_emission() > 0 => {
+					self.set_light(world_pos, kind, new.light_emission());
+					refill.push_back(world_pos);
+				}
+				LightKind::Sky if lets_light(new) && self.sky_enters(world_pos) => {
+					self.set_light(world_pos, kind, MAX_LIGHT);
+					refill.push_back(world_pos);
+				}
+				_ => {}
+			}
+			self.spread_light(kind, refill);
+		}
+	}
+
+	/// Flood fills the light outwards from the queued blocks, only ever brightening
+	fn spread_light(&mut self, kind: LightKind, mut queue: VecDeque<IVec3>) {
+		while let Some(pos) = queue.pop_front() {
+			let level = kind.get(self.get_light(pos));
+			if level <= 1 {
+				continue;
+			}
+			for side in ADJACENT {
+				let target = pos + side;
+				let Some(light) = self.light_at(target) else { continue; };
+				let block = self.get_block(target);
+				if !lets_light(block) {
+					continue;
+				}
+				let next = spread_level(kind, level, side, block);
+				if kind.get(light) < next {
+					self.set_light(target, kind, next);
+					queue.push_back(target);
+				}
+			}
+		}
+	}
+
+	/// Darkens every block that got its light through the start block
+	/// Returns the blocks still lit from elsewhere, spreading from them fills the hole back up
+	fn remove_light(&mut self, kind: LightKind, start: IVec3) -> VecDeque<IVec3> {
+		let mut refill = VecDeque::new();
+		let level = kind.get(self.get_light(start));
+		if level == 0 {
+			return refill;
+		}
+		self.set_light(start, kind, 0);
+
+		let mut queue = VecDeque::from([(start, level)]);
+		while let Some((pos, level)) = queue.pop_front() {
+			for side in ADJACENT {
+				let target = pos + side;
+				let Some(light) = self.light_at(target) else { continue; };
+				let current = kind.get(light);
+				if current == 0 {
+					continue;
+				}
+				let fed_by_pos = current < level
+					|| (kind == LightKind::Sky && side == IVec3::NEG_Y && level == MAX_LIGHT && current == MAX_LIGHT);
+				if !fed_by_pos {
+					refill.push_back(target);
+					continue;
+				}
+				self.set_light(target, kind, 0);
+				queue.push_back((target, current));
+				// a lamp in the dark area still shines
+				let emission = self.get_block(target).light_emission();
+				if kind == LightKind::Block && emission > 0 {
+					self.set_light(target, kind, emission);
+					refill.push_back(target);
+				}
+			}
+		}
+		refill
+	}
+}
@@ -0,0 +1,67 @@
+use crate::block::main::{Block, Material};
+use crate::block::props;
+use crate::game::items::{Item, ItemStack};
+use crate::hs::math::Rand;
+
+/// One possible drop of a broken block
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LootEntry {
+	pub item: Item,
+	pub min: u8, // quantity range (inclusive)
+	pub max: u8,
+	pub chance: f32, // 0-1, rolled on its own for every entry
+	pub min_state: u8, // only drops from blocks with at least this state (ripe crops ...)
+}
+
+impl LootEntry {
+	/// Always drops exactly `quantity` of the block
+	#[inline] pub const fn block(material: Material, quantity: u8) -> Self {
+		Self { item: Item::Block(material), min: quantity, max: quantity, chance: 1.0, min_state: 0 }
+	}
+	#[inline] pub const fn range(mut self, min: u8, max: u8) -> Self { self.min = min; self.max = max; self }
+	#[inline] pub const fn chance(mut self, chance: f32) -> Self { self.chance = chance; self }
+	#[inline] pub const fn from_state(mut self, state: u8) -> Self { self.min_state = state; self }
+}
+
+/// Block loot tables referenced from the props table
+pub mod tables {
+	use super::LootEntry;
+	use crate::block::farming::MAX_STAGE;
+	use crate::block::props::mat;
+
+	pub static NOTHING: [LootEntry; 0] = [];
+	pub static DIRT: [LootEntry; 1] = [LootEntry::block(mat::DIRT, 1)];
+	pub static ROCK: [LootEntry; 1] = [LootEntry::block(mat::ROCK, 1)];
+	pub static GRAVEL: [LootEntry; 2] = [
+		LootEntry::block(mat::GRAVEL, 1).chance(0.9),
+		LootEntry::block(mat::SAND, 1).chance(0.1),
+	];
+	pub static RUBY: [LootEntry; 2] = [
+		LootEntry::block(mat::ROCK_RUBY, 1),
+		LootEntry::block(mat::ROCK, 1).range(1, 2).chance(0.5),
+	];
+	// the seed comes back at any stage, a ripe crop gives more seeds and the wheat
+	pub static CROP: [LootEntry; 3] = [
+		LootEntry::block(mat::CROP, 1),
+		LootEntry::block(mat::CROP, 1).range(0, 2).from_state(MAX_STAGE),
+		LootEntry::block(mat::WHEAT, 1).range(1, 2).from_state(MAX_STAGE),
+	];
+}
+
+/// Rolls the drops of a broken block (a block without a loot table drops itself)
+pub fn roll(block: &Block, rand: &mut Rand) -> Vec<ItemStack> {
+	let material: Material = block.material();
+	let Some(table) = props::get(material).loot else {
+		return vec![ItemStack::new_block(material, 1)];
+	};
+	table.iter()
+		.filter_map(|entry| {
+			if block.state() < entry.min_state || (entry.chance < 1.0 && rand.next_f32() >= entry.chance) {
+				return None;
+			}
+			let spread = entry.max.saturating_sub(entry.min) as u32 + 1;
+			let quantity = entry.min + (rand.next_u32() % spread) as u8;
+			(quantity > 0).then_some(ItemStack { item: entry.item, quantity, data: None })
+		})
+		.collect()
+}
@@ -1,12 +1,15 @@
 
-use crate::block::math::{self, ChunkCoord, BlockPosition, BlockRotation};
-use crate::hs::math::{Noise};
-use crate::render::meshing::GeometryBuffer;
+use crate::block::math::{self, BlockPosition, BlockRotation};
+use crate::block::props::{self, BlockProps};
+use crate::block::entity::BlockEntity;
+use crate::block::light::LightStorage;
+use crate::render::meshing::{GeometryBuffer, TranslucentMesh};
 #[allow(unused_imports)]
 use crate::ext::stopwatch;
 use glam::IVec3;
+use std::collections::HashMap;
 
-type Material = u16;
+pub type Material = u16;
 
 /// Represents a block in the world with optimized storage
 #[derive(Clone, Copy, Debug, PartialEq)]
@@ -14,6 +17,7 @@ type Material = u16;
 pub enum Block {
 	None = 0,
 	Simple(Material, BlockRotation), // material, rotation
+	Stateful(Material, BlockRotation, u8), // material, rotation, state (fluid level, growth stage ...)
 }
 
 #[allow(dead_code)]
@@ -31,15 +35,24 @@ impl Block {
 		Self::Simple(0, BlockRotation::XplusYplus)
 	}
 
-	/// Creates a new simple block with default material
-	#[inline] pub const fn new(material: Material) -> Self {
-		Self::Simple(material, BlockRotation::XplusYplus)
+	/// Creates a new block of the material (with state 0 if the material keeps a state)
+	#[inline] pub fn new(material: Material) -> Self {
+		if props::get(material).stateful {
+			Self::Stateful(material, BlockRotation::XplusYplus, 0)
+		} else {
+			Self::Simple(material, BlockRotation::XplusYplus)
+		}
+	}
+
+	/// Creates a new block with the given state
+	#[inline] pub const fn with_state(material: Material, state: u8) -> Self {
+		Self::Stateful(material, BlockRotation::XplusYplus, state)
 	}
 
 	/// Extracts rotation
 	#[inline] pub const fn get_rotation(&self) -> Option<BlockRotation> {
 		match self {
-			Block::Simple(_, rot) => Some(*rot),
+			Block::Simple(_, rot) | Block::Stateful(_, rot, _) => Some(*rot),
 			_ => None,
 		}
 	}
@@ -47,29 +60,52 @@ impl Block {
 	/// Rotates the block around an axis by N 90° steps
 	#[inline]
 	pub fn rotate(&mut self, axis: math::AxisBasic, steps: u8) {
-		if let Block::Simple(_, rotation) = self {
+		if let Block::Simple(_, rotation) | Block::Stateful(_, rotation, _) = self {
 			*rotation = rotation.rotate(axis, steps);
 		}
 	}
 
 	#[inline] pub const fn is_empty(&self) -> bool {
 		match self {
-			Block::Simple(material, _) => *material == 0,
+			Block::Simple(material, _) | Block::Stateful(material, _, _) => *material == 0,
 			Block::None => true,
 		}
 	}
 
 	#[inline] pub const fn material(&self) -> Material {
 		match self {
-			Block::Simple(material, _) => *material,
+			Block::Simple(material, _) | Block::Stateful(material, _, _) => *material,
 			Block::None => 0,
 		}
 	}
 
+	/// The state byte of the block (0 for blocks without a state)
+	#[inline] pub const fn state(&self) -> u8 {
+		match self {
+			Block::Stateful(_, _, state) => *state,
+			_ => 0,
+		}
+	}
+
+	/// Sets the state, blocks without a state become stateful
+	#[inline]
+	pub fn set_state(&mut self, new_state: u8) {
+		match self {
+			Block::Simple(material, rotation) => *self = Block::Stateful(*material, *rotation, new_state),
+			Block::Stateful(_, _, state) => *state = new_state,
+			Block::None => {}
+		}
+	}
+
+	/// Static properties of this block's material
+	#[inline] pub fn props(&self) -> &'static BlockProps {
+		props::get(self.material())
+	}
+
 	#[inline]
 	pub fn set_material(&mut self, material: Material) {
 		match self {
-			Block::Simple(mat, _) => *mat = material,
+			Block::Simple(mat, _) | Block::Stateful(mat, _, _) => *mat = material,
 			Block::None => {}
 		}
 	}
@@ -77,7 +113,7 @@ impl Block {
 	/// Sets all rotation axes at once
 	#[inline]
 	pub fn set_rotation(&mut self, rotaio: BlockRotation) {
-		if let Block::Simple(_, rotation) = self {
+		if let Block::Simple(_, rotation) | Block::Stateful(_, rotation, _) = self {
 			*rotation = rotaio;
 		}
 	}
@@ -89,9 +125,12 @@ impl Block {
 pub struct Chunk {
 	pub palette: Vec<Block>, // Max 256 entries (index 0 = air, indices 1-255 = blocks)
 	pub storage: BlockStorage, // Palette indices for each block position
+	pub entities: HashMap<u16, BlockEntity>, // block index -> extra data (chests ...)
+	pub light: LightStorage, // block and sky light of every block, filled in by the world
 	pub dirty: bool,
 	pub final_mesh: bool,
 	pub mesh: Option<GeometryBuffer>,
+	pub translucent_mesh: Option<TranslucentMesh>, // blended faces, drawn after every opaque mesh
 	pub bind_group: Option<wgpu::BindGroup>,
 }
 
@@ -165,9 +204,12 @@ impl Chunk {
 		Self {
 			palette: vec![Block::None],  // Index 0 is always air
 			storage: BlockStorage::Uniform(0u8), // All blocks point to air
+			entities: HashMap::new(),
+			light: LightStorage::Uniform(0),
 			dirty: false,
 			final_mesh: false,
 			mesh: None,
+			translucent_mesh: None,
 			bind_group: None,
 		}
 	}
@@ -266,43 +308,6 @@ impl Chunk {
 		self.storage.try_optimize();
 	}
 
-	pub fn generate(coord: ChunkCoord, seed: u32) -> Option<Self> {
-		if coord.y() > 8i32 { return Some(Self::empty()); }
-		if coord.y() <= -2i32 { return Some(Self::new(1u16)); }
-		//let mut stopwatch = stopwatch::RunningAverage::new();
-		
-		let noise_gen = Noise::new(seed);
-		let (world_x, world_y, world_z) = coord.unpack_to_worldpos();
-		let mut chunk = Self::empty();
-		let block = Block::new(1u16);
-		
-		// Pre-calculate all noise values for this chunk's XZ plane
-		for x in 0..Self::SIZE {
-			for z in 0..Self::SIZE {
-				let pos_x:i32 = world_x + x as i32;
-				let pos_z:i32 = world_z + z as i32;
-				
-				// Get noise value and scale it to a reasonable height range
-				let noise:f32 = noise_gen.terrain_noise_2d(pos_x, pos_z);
-				//stopwatch.add(noise as f64);
-				let final_noise = noise * (8 * Chunk::SIZE) as f32;
-				
-				for y in 0..Self::SIZE {
-					let pos_y = world_y + y as i32;
-					// If this block is under or in terrain height, make it solid
-					if pos_y <= final_noise as i32 {
-						// Correct block indexing : BlockPosition
-						let idx: BlockPosition = (x,y,z).into();
-						chunk.set_block(idx.into(), block); // Set to solid
-					}
-					// Else leave as air
-				}
-			}
-		}
-		//println!("stopwatch: {:?}", stopwatch);
-		Some(chunk)
-	}
-
 	#[inline]
 	pub fn get_block(&self, index: usize) -> &Block {
 		let palette_idx = self.storage.get(index);
@@ -334,6 +339,13 @@ impl Chunk {
 
 	/// Sets a block at the given index
 	pub fn set_block(&mut self, index: usize, block: Block) {
+		// A new material means the old block entity is gone and the new one (if any) starts fresh
+		if self.get_block(index).material() != block.material() {
+			self.entities.remove(&(index as u16));
+			if let Some(entity) = BlockEntity::for_material(block.material()) {
+				self.entities.insert(index as u16, entity);
+			}
+		}
 		let palette_idx = self.palette_add(block);
 		self.storage.set(index, palette_idx);
 		self.dirty = true;
@@ -344,6 +356,20 @@ impl Chunk {
 		}
 	}
 
+	/// Puts the blocks set in the other chunk (the non-empty ones, with their block entities) over the ones of this one
+	pub fn overlay(&mut self, other: &Chunk) {
+		for index in 0..Self::VOLUME {
+			let block = *other.get_block(index);
+			if block.is_empty() {
+				continue;
+			}
+			self.set_block(index, block);
+			if let Some(entity) = other.entities.get(&(index as u16)) {
+				self.entities.insert(index as u16, entity.clone());
+			}
+		}
+	}
+
 	/// Checks if a block position is empty or outside the chunk
 	#[inline]
 	pub fn is_block_cull(&self, pos: IVec3) -> bool {
@@ -376,11 +402,24 @@ impl Chunk {
 		false
 	}
 
+	/// Returns the block entity at the given index if there is one
+	#[inline] pub fn get_entity(&self, index: usize) -> Option<&BlockEntity> {
+		self.entities.get(&(index as u16))
+	}
+	#[inline] pub fn get_entity_mut(&mut self, index: usize) -> Option<&mut BlockEntity> {
+		self.entities.get_mut(&(index as u16))
+	}
+
 	/// Returns a reference to the mesh if it exists
 	#[inline] pub const fn mesh(&self) -> Option<&GeometryBuffer> {
 		self.mesh.as_ref()
 	}
 
+	/// Returns a reference to the translucent mesh if it exists
+	#[inline] pub fn translucent_mesh(&self) -> Option<&GeometryBuffer> {
+		self.translucent_mesh.as_ref().map(|mesh| &mesh.buffer)
+	}
+
 	/// Returns a reference to the bind group if it exists
 	#[inline] pub const fn bind_group(&self) -> Option<&wgpu::BindGroup> {
 		self.bind_group.as_ref()
@@ -2,6 +2,9 @@
 use glam::{Vec3, IVec3};
 use crate::block::main::Chunk;
 
+/// The six directly adjacent directions (no diagonals): -X, +X, -Z, +Z, +Y, -Y
+pub const ADJACENT: [IVec3; 6] = [IVec3::NEG_X, IVec3::X, IVec3::NEG_Z, IVec3::Z, IVec3::Y, IVec3::NEG_Y];
+
 /// Compact chunk coordinate representation (64 bits)
 /// Format: [X:26 (signed), Y:12 (signed), Z:26 (signed)]
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -114,14 +117,14 @@ impl ChunkCoord {
 
 	/// Returns the 6 directly adjacent chunk coordinates (no diagonals)
 	#[inline] pub const fn get_adjacent(&self) -> [ChunkCoord; 6] {
-		[
-			self.offset(-1, 0, 0), // -X
-			self.offset(1, 0, 0),  // +X
-			self.offset(0, 0, -1), // -Z
-			self.offset(0, 0, 1),  // +Z
-			self.offset(0, 1, 0),  // +Y
-			self.offset(0, -1, 0), // -Y
-		]
+		let mut adjacent = [*self; 6];
+		let mut i = 0;
+		while i < 6 {
+			let side = ADJACENT[i];
+			adjacent[i] = self.offset(side.x, side.y, side.z);
+			i += 1;
+		}
+		adjacent
 	}
 
 	/// Checks if this chunk is adjacent to another chunk (direct neighbors)
@@ -201,14 +204,14 @@ impl BlockPosition {
 
 	/// Returns the 6 directly adjacent block positions within the chunk (no diagonals)
 	#[inline] pub const fn get_adjacent(&self) -> [BlockPosition; 6] {
-		[
-			self.offset(-1, 0, 0), // -X
-			self.offset(1, 0, 0),  // +X
-			self.offset(0, 0, -1), // -Z
-			self.offset(0, 0, 1),  // +Z
-			self.offset(0, 1, 0),  // +Y
-			self.offset(0, -1, 0), // -Y
-		]
+		let mut adjacent = [*self; 6];
+		let mut i = 0;
+		while i < 6 {
+			let side = ADJACENT[i];
+			adjacent[i] = self.offset(side.x as i8, side.y as i8, side.z as i8);
+			i += 1;
+		}
+		adjacent
 	}
 
 	/// Checks if this position is adjacent to another position
@@ -372,6 +375,54 @@ pub enum BlockRotation {
 
 impl BlockRotation {
 
+	/// A rotation with the given primary axis (the way the block faces)
+	#[inline] pub const fn facing(axis: Axis) -> Self {
+		match axis {
+			Axis::Xplus => BlockRotation::XplusYplus,
+			Axis::Xminus => BlockRotation::XminusYplus,
+			Axis::Yplus => BlockRotation::YplusXplus,
+			Axis::Yminus => BlockRotation::YminusXplus,
+			Axis::Zplus => BlockRotation::ZplusYplus,
+			Axis::Zminus => BlockRotation::ZminusYplus,
+		}
+	}
+
+	/// The rotation with the given primary and secondary axis (None if they are not perpendicular)
+	pub const fn from_axes(primary: Axis, secondary: Axis) -> Option<Self> {
+		match (primary, secondary) {
+			(Axis::Xplus, Axis::Yplus) => Some(BlockRotation::XplusYplus),
+			(Axis::Xplus, Axis::Yminus) => Some(BlockRotation::XplusYminus),
+			(Axis::Xplus, Axis::Zplus) => Some(BlockRotation::XplusZplus),
+			(Axis::Xplus, Axis::Zminus) => Some(BlockRotation::XplusZminus),
+			
+			(Axis::Xminus, Axis::Yplus) => Some(BlockRotation::XminusYplus),
+			(Axis::Xminus, Axis::Yminus) => Some(BlockRotation::XminusYminus),
+			(Axis::Xminus, Axis::Zplus) => Some(BlockRotation::XminusZplus),
+			(Axis::Xminus, Axis::Zminus) => Some(BlockRotation::XminusZminus),
+			
+			(Axis::Yplus, Axis::Xplus) => Some(BlockRotation::YplusXplus),
+			(Axis::Yplus, Axis::Xminus) => Some(BlockRotation::YplusXminus),
+			(Axis::Yplus, Axis::Zplus) => Some(BlockRotation::YplusZplus),
+			(Axis::Yplus, Axis::Zminus) => Some(BlockRotation::YplusZminus),
+			
+			(Axis::Yminus, Axis::Xplus) => Some(BlockRotation::YminusXplus),
+			(Axis::Yminus, Axis::Xminus) => Some(BlockRotation::YminusXminus),
+			(Axis::Yminus, Axis::Zplus) => Some(BlockRotation::YminusZplus),
+			(Axis::Yminus, Axis::Zminus) => Some(BlockRotation::YminusZminus),
+			
+			(Axis::Zplus, Axis::Xplus) => Some(BlockRotation::ZplusXplus),
+			(Axis::Zplus, Axis::Xminus) => Some(BlockRotation::ZplusXminus),
+			(Axis::Zplus, Axis::Yplus) => Some(BlockRotation::ZplusYplus),
+			(Axis::Zplus, Axis::Yminus) => Some(BlockRotation::ZplusYminus),
+			
+			(Axis::Zminus, Axis::Xplus) => Some(BlockRotation::ZminusXplus),
+			(Axis::Zminus, Axis::Xminus) => Some(BlockRotation::ZminusXminus),
+			(Axis::Zminus, Axis::Yplus) => Some(BlockRotation::ZminusYplus),
+			(Axis::Zminus, Axis::Yminus) => Some(BlockRotation::ZminusYminus),
+			_ => None,
+		}
+	}
+
 	/// Returns the primary axis of this rotation
 	#[inline] pub const fn primary_axis(self) -> Axis {
 		match self {
@@ -398,22 +449,23 @@ impl BlockRotation {
 	/// Returns the secondary axis of this rotation
 	#[inline] pub const fn secondary_axis(self) -> Axis {
 		match self {
-			BlockRotation::XplusYplus | BlockRotation::XminusYplus |
-			BlockRotation::YplusXplus | BlockRotation::YminusXplus  => Axis::Xplus,
+			BlockRotation::YplusXplus | BlockRotation::YminusXplus |
+			BlockRotation::ZplusXplus | BlockRotation::ZminusXplus => Axis::Xplus,
 			
 			BlockRotation::YplusXminus | BlockRotation::YminusXminus |
 			BlockRotation::ZplusXminus | BlockRotation::ZminusXminus => Axis::Xminus,
 			
+			BlockRotation::XplusYplus | BlockRotation::XminusYplus |
+			BlockRotation::ZplusYplus | BlockRotation::ZminusYplus => Axis::Yplus,
+			
+			BlockRotation::XplusYminus | BlockRotation::XminusYminus |
+			BlockRotation::ZplusYminus | BlockRotation::ZminusYminus => Axis::Yminus,
+			
 			BlockRotation::XplusZplus | BlockRotation::XminusZplus |
-			BlockRotation::YplusZplus | BlockRotation::YminusZplus  => Axis::Zplus,
+			BlockRotation::YplusZplus | BlockRotation::YminusZplus => Axis::Zplus,
 			
 			BlockRotation::XplusZminus | BlockRotation::XminusZminus |
-			BlockRotation::YplusZminus | BlockRotation::YminusZminus  => Axis::Zminus,
-			
-			BlockRotation::XplusYminus | BlockRotation::XminusYminus |
-			BlockRotation::ZplusYminus | BlockRotation::ZminusYminus => Axis:: Yminus,
-
-			_ => Axis::Zplus, // Remaining cases are Z variants
+			BlockRotation::YplusZminus | BlockRotation::YminusZminus => Axis::Zminus,
 		}
 	}
 
@@ -464,39 +516,7 @@ impl BlockRotation {
 		};
 
 		// Reconstruct the new rotation based on primary and secondary axes
-		match (primary, secondary) {
-			(Axis::Xplus, Axis::Yplus) => BlockRotation::XplusYplus,
-			(Axis::Xplus, Axis::Yminus) => BlockRotation::XplusYminus,
-			(Axis::Xplus, Axis::Zplus) => BlockRotation::XplusZplus,
-			(Axis::Xplus, Axis::Zminus) => BlockRotation::XplusZminus,
-			
-			(Axis::Xminus, Axis::Yplus) => BlockRotation::XminusYplus,
-			(Axis::Xminus, Axis::Yminus) => BlockRotation::XminusYminus,
-			(Axis::Xminus, Axis::Zplus) => BlockRotation::XminusZplus,
-			(Axis::Xminus, Axis::Zminus) => BlockRotation::XminusZminus,
-			
-			(Axis::Yplus, Axis::Xplus) => BlockRotation::YplusXplus,
-			(Axis::Yplus, Axis::Xminus) => BlockRotation::YplusXminus,
-			(Axis::Yplus, Axis::Zplus) => BlockRotation::YplusZplus,
-			(Axis::Yplus, Axis::Zminus) => BlockRotation::YplusZminus,
-			
-			(Axis::Yminus, Axis::Xplus) => BlockRotation::YminusXplus,
-			(Axis::Yminus, Axis::Xminus) => BlockRotation::YminusXminus,
-			(Axis::Yminus, Axis::Zplus) => BlockRotation::YminusZplus,
-			(Axis::Yminus, Axis::Zminus) => BlockRotation::YminusZminus,
-			
-			(Axis::Zplus, Axis::Xplus) => BlockRotation::ZplusXplus,
-			(Axis::Zplus, Axis::Xminus) => BlockRotation::ZplusXminus,
-			(Axis::Zplus, Axis::Yplus) => BlockRotation::ZplusYplus,
-			(Axis::Zplus, Axis::Yminus) => BlockRotation::ZplusYminus,
-			
-			(Axis::Zminus, Axis::Xplus) => BlockRotation::ZminusXplus,
-			(Axis::Zminus, Axis::Xminus) => BlockRotation::ZminusXminus,
-			(Axis::Zminus, Axis::Yplus) => BlockRotation::ZminusYplus,
-			(Axis::Zminus, Axis::Yminus) => BlockRotation::ZminusYminus,
-
-			_ => panic!("The rotation is incorrect"),
-		}
+		Self::from_axes(primary, secondary).expect("The rotation is incorrect")
 	}
 
 }
\ No newline at end of file
@@ -0,0 +1,335 @@
+use crate::block::main::Material;
+use crate::block::farming;
+use crate::block::math::{Axis, BlockRotation};
+use crate::world::tick::{self, TickFn};
+use crate::world::neighbor::NeighborFn;
+use crate::world::falling;
+use crate::world::explosion;
+use crate::block::fluid;
+use crate::block::light;
+use crate::block::loot::{self, LootEntry};
+use crate::block::signal::{self, SignalRole};
+use crate::block::shape::{self, ShapeFn};
+use crate::block::interact::{self, UseFn};
+use crate::game::items::ToolFlags;
+
+/// How a block lets light (and the view) through, decides culling and the render pass
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Transparency {
+	Opaque,      // hides every face behind it, drawn in the main pass
+	Cutout,      // fully see-through pixels, still drawn in the main pass
+	Translucent, // blended, drawn in the sorted second pass
+}
+
+/// How a placed block gets turned
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Placement {
+	Fixed,  // always the same way
+	Facing, // away from the player, upside down in the upper half of the space (doors, stairs ...)
+	Half,   // into the lower or the upper half of the space (slabs)
+}
+
+impl Placement {
+	/// The rotation of a block placed by a player looking along `facing` (None keeps the default)
+	pub fn rotation(self, facing: Axis, upper: bool) -> Option<BlockRotation> {
+		match self {
+			Placement::Fixed => None,
+			Placement::Facing => BlockRotation::from_axes(facing, if upper { Axis::Yminus } else { Axis::Yplus }),
+			Placement::Half => Some(BlockRotation::facing(if upper { Axis::Yplus } else { Axis::Yminus })),
+		}
+	}
+}
+
+/// Static data shared by every block of the same material
+#[derive(Clone, Copy, Debug)]
+pub struct BlockProps {
+	pub name: &'static str,
+	pub transparency: Transparency,
+	pub texture: Option<Material>, // borrow another material's texture (blocks without their own file)
+	pub random_tick: Option<TickFn>, // runs when the block gets picked by the random ticks
+	pub scheduled_tick: Option<TickFn>, // runs when a tick scheduled for the block's position is due
+	pub tick_delay: u32, // delay of the scheduled tick when the block or a neighbor changes
+	pub neighbor_update: Option<NeighborFn>, // runs when a block next to it changes
+	pub falls: bool, // turns into a falling block when there is nothing below it (sand, gravel)
+	pub fluid: bool, // flows, its level is kept in the block state
+	pub stateful: bool, // new blocks are `Block::Stateful`
+	pub light_emission: u8, // block light level it shines with (0-15)
+	pub hardness: f32, // how long it takes to mine (negative can not be mined)
+	pub tool: u8, // `ToolFlags` of the tools that mine it fast (NONE if the hand is just as good)
+	pub loot: Option<&'static [LootEntry]>, // drops when broken (None drops the block itself)
+	pub signal: Option<SignalRole>, // part of the signal circuits, its signal state is kept in the block state
+	pub shape: Option<ShapeFn>, // the boxes it is drawn with (None is a full cube)
+	pub collision: Option<ShapeFn>, // the boxes things bump into (None is the same as the shape)
+	pub on_use: Option<UseFn>, // runs when the player uses it (None places a block against it instead)
+	pub placement: Placement, // how it gets turned when placed
+	pub support: Option<Material>, // the block it has to stand on, it pops off without it (None stands anywhere)
+	pub resistance: Option<f32>, // how much of an explosion it soaks up (None follows the hardness)
+	pub on_blast: Option<UseFn>, // runs when an explosion reaches it instead of breaking it (None breaks)
+}
+
+impl BlockProps {
+	#[inline] pub const fn new(name: &'static str) -> Self {
+		Self {
+			name,
+			transparency: Transparency::Opaque,
+			texture: None,
+			random_tick: None,
+			scheduled_tick: None,
+			tick_delay: 1,
+			neighbor_update: None,
+			falls: false,
+			fluid: false,
+			stateful: false,
+			light_emission: 0,
+			hardness: 1.0,
+			tool: ToolFlags::NONE,
+			loot: None,
+			signal: None,
+			shape: None,
+			collision: None,
+			on_use: None,
+			placement: Placement::Fixed,
+			support: None,
+			resistance: None,
+			on_blast: None,
+		}
+	}
+	#[inline] pub const fn cutout(mut self) -> Self { self.transparency = Transparency::Cutout; self }
+	#[inline] pub const fn translucent(mut self) -> Self { self.transparency = Transparency::Translucent; self }
+	#[inline] pub const fn texture(mut self, material: Material) -> Self { self.texture = Some(material); self }
+	#[inline] pub const fn random_tick(mut self, on_tick: TickFn) -> Self { self.random_tick = Some(on_tick); self }
+	#[inline] pub const fn scheduled_tick(mut self, on_tick: TickFn) -> Self { self.scheduled_tick = Some(on_tick); self }
+	#[inline] pub const fn tick_delay(mut self, delay: u32) -> Self { self.tick_delay = delay; self }
+	#[inline] pub const fn neighbor_update(mut self, on_update: NeighborFn) -> Self { self.neighbor_update = Some(on_update); self }
+	#[inline] pub const fn stateful(mut self) -> Self { self.stateful = true; self }
+	#[inline] pub const fn emits(mut self, level: u8) -> Self { self.light_emission = level; self }
+	#[inline] pub const fn hardness(mut self, hardness: f32) -> Self { self.hardness = hardness; self }
+	#[inline] pub const fn tool(mut self, tool: u8) -> Self { self.tool = tool; self }
+	#[inline] pub const fn loot(mut self, table: &'static [LootEntry]) -> Self { self.loot = Some(table); self }
+	#[inline] pub const fn pickaxe(self, hardness: f32) -> Self { self.hardness(hardness).tool(ToolFlags::PICKAXE) }
+	#[inline] pub const fn axe(self, hardness: f32) -> Self { self.hardness(hardness).tool(ToolFlags::AXE) }
+	#[inline] pub const fn shovel(self, hardness: f32) -> Self { self.hardness(hardness).tool(ToolFlags::SHOVEL) }
+	#[inline] pub const fn falls(mut self) -> Self {
+		self.falls = true;
+		self.scheduled_tick(falling::fall_check).tick_delay(falling::FALL_DELAY).neighbor_update(tick::schedule_on_update)
+	}
+	#[inline] pub const fn shape(mut self, shape: ShapeFn) -> Self { self.shape = Some(shape); self }
+	#[inline] pub const fn collision(mut self, collision: ShapeFn) -> Self { self.collision = Some(collision); self }
+	#[inline] pub const fn on_use(mut self, on_use: UseFn) -> Self { self.on_use = Some(on_use); self }
+	#[inline] pub const fn placement(mut self, placement: Placement) -> Self { self.placement = placement; self }
+	#[inline] pub const fn resistance(mut self, resistance: f32) -> Self { self.resistance = Some(resistance); self }
+	#[inline] pub const fn on_blast(mut self, on_blast: UseFn) -> Self { self.on_blast = Some(on_blast); self }
+	#[inline] pub const fn support(mut self, material: Material) -> Self {
+		self.support = Some(material);
+		self.scheduled_tick(farming::support_check).neighbor_update(tick::schedule_on_update)
+	}
+	#[inline] pub const fn crop(self) -> Self {
+		self.cutout().stateful().shape(farming::crop_shape).collision(shape::no_collision)
+			.random_tick(farming::crop_grow).support(mat::FARMLAND).loot(&loot::tables::CROP)
+	}
+	#[inline] pub const fn door(self, shape: ShapeFn) -> Self {
+		self.cutout().placement(Placement::Facing).shape(shape).collision(interact::door_collision).on_use(interact::use_door)
+			.signal_consumer().neighbor_update(interact::door_update)
+	}
+	#[inline] pub const fn signal_source(mut self) -> Self {
+		self.signal = Some(SignalRole::Source);
+		self.stateful()
+	}
+	#[inline] pub const fn wire(mut self) -> Self {
+		self.signal = Some(SignalRole::Wire);
+		self.stateful().neighbor_update(signal::wire_update)
+	}
+	#[inline] pub const fn signal_consumer(mut self) -> Self {
+		self.signal = Some(SignalRole::Consumer);
+		self.stateful().neighbor_update(signal::consumer_update)
+	}
+	#[inline] pub const fn fluid(mut self) -> Self {
+		self.fluid = true;
+		self.stateful().scheduled_tick(fluid::flow_tick).tick_delay(fluid::FLOW_DELAY).neighbor_update(tick::schedule_on_update)
+	}
+
+	#[inline] pub const fn is_opaque(&self) -> bool {
+		matches!(self.transparency, Transparency::Opaque)
+	}
+	#[inline] pub const fn is_translucent(&self) -> bool {
+		matches!(self.transparency, Transparency::Translucent)
+	}
+
+	/// How much strength an explosion ray loses going through the block (blocks that can not be mined stop it)
+	#[inline] pub fn blast_resistance(&self) -> f32 {
+		match self.resistance {
+			Some(resistance) => resistance,
+			None if self.hardness < 0.0 => f32::INFINITY,
+			None => self.hardness,
+		}
+	}
+
+	/// Seconds it takes to mine the block with the tool (None if it can not be mined)
+	pub fn break_time(&self, tool: Option<ToolFlags>) -> Option<f32> {
+		if self.hardness < 0.0 {
+			return None;
+		}
+		let right_tool = tool.is_some_and(|tool| tool.any(self.tool));
+		let multiplier = if right_tool {
+			BREAK_TIME_TOOL
+		} else if self.tool == ToolFlags::NONE {
+			BREAK_TIME_HAND
+		} else {
+			BREAK_TIME_WRONG_TOOL
+		};
+		Some(self.hardness * multiplier)
+	}
+}
+
+/// Hardness to seconds multipliers
+const BREAK_TIME_TOOL: f32 = 0.4;
+const BREAK_TIME_HAND: f32 = 1.5;
+const BREAK_TIME_WRONG_TOOL: f32 = 3.0;
+
+/// Material ids, they follow the sorted file names inside "resources/blocks" (0 is air)
+#[allow(dead_code)]
+pub mod mat {
+	use crate::block::main::Material;
+
+	pub const AIR: Material = 0;
+	pub const BRICK_GREY: Material = 1;
+	pub const BRICK_RED: Material = 2;
+	pub const DIRT: Material = 3;
+	pub const DIRT_DRY: Material = 4;
+	pub const DIRT_GRASS: Material = 5;
+	pub const DIRT_SNOW: Material = 6;
+	pub const GRASS: Material = 7;
+	pub const GRAVEL: Material = 8;
+	pub const METAL_BLUE: Material = 9;
+	pub const PLANK: Material = 10;
+	pub const ROCK: Material = 11;
+	pub const ROCK_RUBY: Material = 12;
+	pub const ROCK_RUBY_ALT: Material = 13;
+	pub const SAND: Material = 14;
+	pub const SNOW: Material = 15;
+	pub const STONE: Material = 16;
+	pub const STONE_BROWNIRON: Material = 17;
+	pub const STONE_BROWNIRON_ALT: Material = 18;
+	pub const STONE_COAL: Material = 19;
+	pub const STONE_COAL_ALT: Material = 20;
+	pub const STONE_DIAMOND: Material = 21;
+	pub const STONE_DIAMOND_ALT: Material = 22;
+	pub const STONE_DIRT: Material = 23;
+	pub const STONE_GOLD: Material = 24;
+	pub const STONE_GOLD_ALT: Material = 25;
+	pub const STONE_GRASS: Material = 26;
+	pub const STONE_IRON: Material = 27;
+	pub const STONE_IRON_ALT: Material = 28;
+	pub const STONE_SAND: Material = 29;
+	pub const STONE_SILVER: Material = 30;
+	pub const STONE_SILVER_ALT: Material = 31;
+	pub const STONE_SNOW: Material = 32;
+	pub const WATER: Material = 33;
+	pub const WOOD_SIDE: Material = 34;
+	pub const WOOD_TOP: Material = 35;
+	pub const WOOD_WHITE_SIDE: Material = 36;
+	pub const WOOD_WHITE_TOP: Material = 37;
+	// blocks below have no texture file of their own
+	pub const CHEST: Material = 38;
+	pub const LAMP: Material = 39;
+	pub const LEVER: Material = 40;
+	pub const BUTTON: Material = 41;
+	pub const WIRE: Material = 42;
+	pub const SIGNAL_LAMP: Material = 43;
+	pub const DOOR: Material = 44;
+	pub const TRAPDOOR: Material = 45;
+	pub const STONE_SLAB: Material = 46;
+	pub const PLANK_STAIRS: Material = 47;
+	pub const FENCE: Material = 48;
+	pub const FARMLAND: Material = 49;
+	pub const CROP: Material = 50;
+	pub const WHEAT: Material = 51;
+	pub const TNT: Material = 52;
+	pub const FIRE: Material = 53;
+	pub const LEAVES: Material = 54;
+}
+
+/// Fallback for materials that are not in the table (old saves, mods ...)
+static UNKNOWN: BlockProps = BlockProps::new("unknown");
+
+/// Indexed by material id
+static PROPS: [BlockProps; 55] = [
+	BlockProps::new("air").cutout().hardness(-1.0).loot(&loot::tables::NOTHING),
+	BlockProps::new("brick_grey").pickaxe(2.0),
+	BlockProps::new("brick_red").pickaxe(2.0),
+	BlockProps::new("dirt").shovel(0.5),
+	BlockProps::new("dirt_dry").shovel(0.6),
+	BlockProps::new("dirt_grass").shovel(0.6).loot(&loot::tables::DIRT).random_tick(tick::grass_spread),
+	BlockProps::new("dirt_snow").shovel(0.6).loot(&loot::tables::DIRT).random_tick(tick::snow_thaw),
+	BlockProps::new("grass").shovel(0.6).loot(&loot::tables::DIRT),
+	BlockProps::new("gravel").shovel(0.6).loot(&loot::tables::GRAVEL).falls(),
+	BlockProps::new("metal_blue").pickaxe(5.0).resistance(50.0),
+	BlockProps::new("plank").axe(2.0),
+	BlockProps::new("rock").pickaxe(1.5),
+	BlockProps::new("rock_ruby").pickaxe(3.0).loot(&loot::tables::RUBY),
+	BlockProps::new("rock_ruby_alt").pickaxe(3.0).loot(&loot::tables::RUBY),
+	BlockProps::new("sand").shovel(0.5).falls(),
+	BlockProps::new("snow").shovel(0.2).random_tick(tick::snow_melt),
+	BlockProps::new("stone").pickaxe(1.5).loot(&loot::tables::ROCK),
+	BlockProps::new("stone_browniron").pickaxe(3.0),
+	BlockProps::new("stone_browniron_alt").pickaxe(3.0),
+	BlockProps::new("stone_coal").pickaxe(3.0),
+	BlockProps::new("stone_coal_alt").pickaxe(3.0),
+	BlockProps::new("stone_diamond").pickaxe(3.0),
+	BlockProps::new("stone_diamond_alt").pickaxe(3.0),
+	BlockProps::new("stone_dirt").pickaxe(1.0),
+	BlockProps::new("stone_gold").pickaxe(3.0),
+	BlockProps::new("stone_gold_alt").pickaxe(3.0),
+	BlockProps::new("stone_grass").pickaxe(1.0),
+	BlockProps::new("stone_iron").pickaxe(3.0),
+	BlockProps::new("stone_iron_alt").pickaxe(3.0),
+	BlockProps::new("stone_sand").pickaxe(0.8),
+	BlockProps::new("stone_silver").pickaxe(3.0),
+	BlockProps::new("stone_silver_alt").pickaxe(3.0),
+	BlockProps::new("stone_snow").pickaxe(1.0),
+	BlockProps::new("water").translucent().fluid().hardness(-1.0).loot(&loot::tables::NOTHING),
+	BlockProps::new("wood_side").axe(2.0),
+	BlockProps::new("wood_top").axe(2.0),
+	BlockProps::new("wood_white_side").axe(2.0),
+	BlockProps::new("wood_white_top").axe(2.0),
+	BlockProps::new("chest").texture(mat::PLANK).axe(2.5),
+	BlockProps::new("lamp").texture(mat::METAL_BLUE).emits(light::MAX_LIGHT).hardness(0.3),
+	BlockProps::new("lever").texture(mat::WOOD_TOP).signal_source().on_use(signal::toggle_lever).hardness(0.3),
+	BlockProps::new("button").texture(mat::STONE).signal_source().on_use(signal::press_button).hardness(0.3)
+		.scheduled_tick(signal::button_release).tick_delay(signal::BUTTON_TICKS),
+	BlockProps::new("wire").texture(mat::BRICK_RED).wire().hardness(0.0),
+	BlockProps::new("signal_lamp").texture(mat::STONE_GOLD).signal_consumer().emits(light::MAX_LIGHT).hardness(0.3),
+	BlockProps::new("door").texture(mat::WOOD_WHITE_SIDE).door(interact::door_shape).axe(2.0),
+	BlockProps::new("trapdoor").texture(mat::PLANK).door(interact::trapdoor_shape).axe(2.0),
+	BlockProps::new("stone_slab").texture(mat::STONE).cutout().shape(shape::slab_shape).placement(Placement::Half).pickaxe(1.5),
+	BlockProps::new("plank_stairs").texture(mat::PLANK).cutout().shape(shape::stairs_shape).placement(Placement::Facing).axe(2.0),
+	BlockProps::new("fence").texture(mat::PLANK).cutout().stateful().shape(shape::fence_shape).neighbor_update(shape::fence_update).axe(2.0),
+	BlockProps::new("farmland").texture(mat::DIRT).cutout().stateful().shape(farming::farmland_shape).random_tick(farming::farmland_tick)
+		.shovel(0.6).loot(&loot::tables::DIRT),
+	BlockProps::new("crop").texture(mat::GRASS).crop().hardness(0.0),
+	BlockProps::new("wheat").texture(mat::SAND).hardness(0.5),
+	BlockProps::new("tnt").texture(mat::BRICK_RED).stateful().hardness(0.0).on_use(explosion::ignite).on_blast(explosion::chain_ignite)
+		.neighbor_update(explosion::tnt_update).scheduled_tick(explosion::tnt_fuse).tick_delay(explosion::FUSE_TICKS),
+	BlockProps::new("fire").texture(mat::BRICK_RED).cutout().shape(explosion::fire_shape).collision(shape::no_collision)
+		.emits(light::MAX_LIGHT).hardness(0.0).loot(&loot::tables::NOTHING).scheduled_tick(explosion::burn_out).tick_delay(explosion::BURN_TICKS),
+	BlockProps::new("leaves").texture(mat::GRASS).cutout().hardness(0.2),
+];
+
+/// Texture layer material of a material (itself unless it borrows one)
+#[inline] pub fn texture_of(material: Material) -> Material {
+	get(material).texture.unwrap_or(material)
+}
+
+/// Looks up the properties of a material
+#[inline] pub fn get(material: Material) -> &'static BlockProps {
+	if (material as usize) < PROPS.len() {
+		&PROPS[material as usize]
+	} else {
+		&UNKNOWN
+	}
+}
+
+/// Looks up a material by the name of its properties
+pub fn by_name(name: &str) -> Option<Material> {
+	PROPS.iter().position(|props| props.name == name).map(|idx| idx as Material)
+}
@@ -0,0 +1,245 @@
+use crate::block::main::Block;
+use crate::block::math::Axis;
+use crate::physic::aabb::AABB;
+use crate::world::main::World;
+use glam::{IVec3, Vec3};
+
+/// Sixteenths of a block, the unit of the block boxes
+pub const UNITS: u8 = 16;
+
+/// A box inside a block, in sixteenths of the block
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BlockBox {
+	pub min: [u8; 3],
+	pub max: [u8; 3],
+}
+
+/// The boxes a block is made of, picked by its state and facing
+pub type ShapeFn = fn(&Block) -> &'static [BlockBox];
+
+/// Shape of every ordinary block
+pub static FULL_CUBE: [BlockBox; 1] = [BlockBox::FULL];
+/// Shape of the blocks nothing bumps into
+pub static NO_BOXES: [BlockBox; 0] = [];
+
+impl BlockBox {
+	pub const FULL: Self = Self::new([0, 0, 0], [UNITS, UNITS, UNITS]);
+
+	#[inline] pub const fn new(min: [u8; 3], max: [u8; 3]) -> Self {
+		Self { min, max }
+	}
+
+	#[inline] pub const fn is_full(&self) -> bool {
+		self.min[0] == 0 && self.min[1] == 0 && self.min[2] == 0
+			&& self.max[0] == UNITS && self.max[1] == UNITS && self.max[2] == UNITS
+	}
+
+	/// The box turned around the vertical axis a quarter turn at a time (+X turns towards +Z)
+	pub const fn turned(self, quarters: u8) -> Self {
+		let mut turned = self;
+		let mut i = 0;
+		while i < quarters % 4 {
+			turned = Self::new(
+				[UNITS - turned.max[2], turned.min[1], turned.min[0]],
+				[UNITS - turned.min[2], turned.max[1], turned.max[0]],
+			);
+			i += 1;
+		}
+		turned
+	}
+
+	/// Does the box reach the side of the block the normal points to
+	#[inline] pub fn touches_side(&self, normal: IVec3) -> bool {
+		(0..3).all(|axis| match normal[axis] {
+			1 => self.max[axis] == UNITS,
+			-1 => self.min[axis] == 0,
+			_ => true,
+		})
+	}
+
+	/// How much of the side of the block the normal points to the box covers (square sixteenths, 0 if it does not reach it)
+	#[inline] pub fn side_area(&self, normal: IVec3) -> u32 {
+		if !self.touches_side(normal) {
+			return 0;
+		}
+		(0..3).filter(|axis| normal[*axis] == 0)
+			.map(|axis| (self.max[axis] - self.min[axis]) as u32)
+			.product()
+	}
+
+	/// Where the ray enters the box of the block at `pos`: distance along the ray and the normal of the side it enters
+	pub fn ray_hit(&self, pos: IVec3, origin: Vec3, dir: Vec3) -> Option<(f32, IVec3)> {
+		let aabb = self.aabb(pos);
+		let (mut near, mut far) = (f32::NEG_INFINITY, f32::INFINITY);
+		let mut normal = IVec3::ZERO;
+		for axis in 0..3 {
+			if dir[axis] == 0.0 {
+				if origin[axis] < aabb.min[axis] || origin[axis] > aabb.max[axis] {
+					return None;
+				}
+				continue;
+			}
+			let (a, b) = ((aabb.min[axis] - origin[axis]) / dir[axis], (aabb.max[axis] - origin[axis]) / dir[axis]);
+			let (enter, exit) = if a < b { (a, b) } else { (b, a) };
+			if enter > near {
+				near = enter;
+				normal = IVec3::ZERO;
+				normal[axis] = if dir[axis] > 0.0 { -1 } else { 1 };
+			}
+			far = far.min(exit);
+		}
+		(near <= far && far >= 0.0).then_some((near.max(0.0), normal))
+	}
+
+	/// The box packed for the shader, 5 bits per value: min x, y, z then max x, y, z
+	#[inline] pub const fn packed(&self) -> u32 {
+		self.min[0] as u32 | (self.min[1] as u32) << 5 | (self.min[2] as u32) << 10
+			| (self.max[0] as u32) << 15 | (self.max[1] as u32) << 20 | (self.max[2] as u32) << 25
+	}
+
+	/// The box of the block at the world position, in world space
+	#[inline] pub fn aabb(&self, pos: IVec3) -> AABB {
+		let unit = |values: [u8; 3]| Vec3::new(values[0] as f32, values[1] as f32, values[2] as f32) / UNITS as f32;
+		let origin = pos.as_vec3();
+		AABB::new(origin + unit(self.min), origin + unit(self.max))
+	}
+}
+
+/// The boxes in all 4 horizontal directions, indexed by `quarters`
+pub const fn all_turns<const N: usize>(boxes: [BlockBox; N]) -> [[BlockBox; N]; 4] {
+	let mut turns = [boxes; 4];
+	let mut quarter = 1;
+	while quarter < 4 {
+		let mut i = 0;
+		while i < N {
+			turns[quarter][i] = boxes[i].turned(quarter as u8);
+			i += 1;
+		}
+		quarter += 1;
+	}
+	turns
+}
+
+/// Quarter turns of a horizontal facing, counted from +X towards +Z (vertical ones count as +X)
+#[inline] pub const fn quarters(facing: Axis) -> usize {
+	match facing {
+		Axis::Zplus => 1,
+		Axis::Xminus => 2,
+		Axis::Zminus => 3,
+		_ => 0,
+	}
+}
+
+impl Block {
+	/// The way the block faces (the primary axis of its rotation)
+	#[inline] pub fn facing(&self) -> Axis {
+		self.get_rotation().map_or(Axis::Xplus, |rotation| rotation.primary_axis())
+	}
+
+	/// The boxes the block is drawn with
+	#[inline] pub fn shape(&self) -> &'static [BlockBox] {
+		match self.props().shape {
+			Some(shape) => shape(self),
+			None => &FULL_CUBE,
+		}
+	}
+
+	/// Does the block hide the whole side the normal points to (a full cube, the bottom of a slab, the back of stairs ...)
+	/// The boxes of a shape never overlap, so together they cover the side when their areas on it add up to all of it
+	#[inline] pub fn covers_side(&self, normal: IVec3) -> bool {
+		let props = self.props();
+		if props.shape.is_none() {
+			return props.is_opaque();
+		}
+		let area: u32 = self.shape().iter().map(|shape| shape.side_area(normal)).sum();
+		!props.is_translucent() && area == UNITS as u32 * UNITS as u32
+	}
+
+	/// The boxes things bump into (nothing for air and fluids)
+	#[inline] pub fn collision(&self) -> &'static [BlockBox] {
+		let props = self.props();
+		if self.is_empty() || props.fluid {
+			return &NO_BOXES;
+		}
+		match props.collision {
+			Some(collision) => collision(self),
+			None => self.shape(),
+		}
+	}
+}
+
+//
+// Shapes used by the props table
+//
+
+/// Collision of the blocks everything passes through (crops ...)
+pub fn no_collision(_block: &Block) -> &'static [BlockBox] {
+	&NO_BOXES
+}
+
+// The state byte of a fence: bits 0-3 connected towards +X, +Z, -X, -Z (the `quarters` order)
+
+const HALF: u8 = UNITS / 2;
+static BOTTOM_SLAB: [BlockBox; 1] = [BlockBox::new([0, 0, 0], [UNITS, HALF, UNITS])];
+static TOP_SLAB: [BlockBox; 1] = [BlockBox::new([0, HALF, 0], [UNITS, UNITS, UNITS])];
+// Stairs facing +X climb towards +X, turned to the other facings
+static STAIRS: [[BlockBox; 2]; 4] = all_turns([BOTTOM_SLAB[0], BlockBox::new([HALF, HALF, 0], [UNITS, UNITS, UNITS])]);
+static STAIRS_UPSIDE_DOWN: [[BlockBox; 2]; 4] = all_turns([TOP_SLAB[0], BlockBox::new([HALF, 0, 0], [UNITS, HALF, UNITS])]);
+static FENCE: [([BlockBox; 9], usize); 16] = fence_shapes();
+
+/// Is the block in the upper half of its space (a top slab, upside down stairs ...)
+#[inline] pub fn is_upper(block: &Block) -> bool {
+	block.get_rotation().is_some_and(|rotation| rotation.primary_axis() == Axis::Yplus || rotation.secondary_axis() == Axis::Yminus)
+}
+
+/// A fence post with two rails towards every connected side
+const fn fence_shapes() -> [([BlockBox; 9], usize); 16] {
+	let post = BlockBox::new([6, 0, 6], [10, UNITS, 10]);
+	let low_rail = BlockBox::new([10, 6, 7], [UNITS, 9, 9]);
+	let high_rail = BlockBox::new([10, 12, 7], [UNITS, 15, 9]);
+	let mut shapes = [([post; 9], 1); 16];
+	let mut connections = 0;
+	while connections < 16 {
+		let (boxes, count) = &mut shapes[connections];
+		let mut side = 0;
+		while side < 4 {
+			if connections & 1 << side != 0 {
+				boxes[*count] = low_rail.turned(side as u8);
+				boxes[*count + 1] = high_rail.turned(side as u8);
+				*count += 2;
+			}
+			side += 1;
+		}
+		connections += 1;
+	}
+	shapes
+}
+
+/// A slab fills the lower half, or the upper one when placed against the top of the space
+pub fn slab_shape(block: &Block) -> &'static [BlockBox] {
+	if is_upper(block) { &TOP_SLAB } else { &BOTTOM_SLAB }
+}
+
+/// Stairs are a slab with a step on top towards their facing (upside down they hang from the top)
+pub fn stairs_shape(block: &Block) -> &'static [BlockBox] {
+	let table = if is_upper(block) { &STAIRS_UPSIDE_DOWN } else { &STAIRS };
+	&table[quarters(block.facing())]
+}
+
+/// A fence is a post, with rails towards the sides it is connected to
+pub fn fence_shape(block: &Block) -> &'static [BlockBox] {
+	let (boxes, count) = &FENCE[(block.state() & 0xF) as usize];
+	&boxes[..*count]
+}
+
+/// Neighbor update of the fences: they connect to other fences and to the full sides of blocks
+pub fn fence_update(world: &mut World, pos: IVec3, _source: IVec3) {
+	let mut connections = 0;
+	for (side, dir) in [IVec3::X, IVec3::Z, IVec3::NEG_X, IVec3::NEG_Z].into_iter().enumerate() {
+		let neighbor = world.get_block(pos + dir);
+		if neighbor.material() == world.get_block(pos).material() || neighbor.covers_side(-dir) {
+			connections |= 1 << side;
+		}
+	}
+	world.set_block_state(pos, connections);
+}
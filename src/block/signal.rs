@@ -0,0 +1,91 @@
+use crate::block::main::Block;
+use crate::hs::math::Rand;
+use crate::world::main::World;
+use crate::block::math::ADJACENT;
+use glam::IVec3;
+
+// The state byte of a signal block:
+// wire: bits 0-3 signal strength
+// lever, button: bit 0 switched on
+// lamp, door: bit 0 powered
+
+/// Strength a source gives out, a wire carries it one block less far with every step
+pub const MAX_SIGNAL: u8 = 15;
+/// Ticks a pressed button stays on
+pub const BUTTON_TICKS: u32 = 20;
+
+const ON: u8 = 1 << 0;
+
+/// What a block does with the signal
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SignalRole {
+	Source,   // gives out the full strength while switched on (lever, button)
+	Wire,     // carries the strongest signal next to it, one weaker
+	Consumer, // powered while any signal reaches it (lamp, door)
+}
+
+#[inline] pub const fn is_on(state: u8) -> bool { state & ON != 0 }
+#[inline] pub const fn strength(state: u8) -> u8 { state & 0xF }
+
+/// The signal strength a block gives to the blocks next to it
+pub fn output(block: &Block) -> u8 {
+	match block.props().signal {
+		Some(SignalRole::Source) if is_on(block.state()) => MAX_SIGNAL,
+		Some(SignalRole::Wire) => strength(block.state()),
+		_ => 0,
+	}
+}
+
+/// The strongest signal reaching the position from the blocks next to it
+pub fn received(world: &World, pos: IVec3) -> u8 {
+	ADJACENT.iter()
+		.map(|side| output(world.get_block(pos + *side)))
+		.max()
+		.unwrap_or(0)
+}
+
+/// The strength a wire at the position carries: full next to a source, one weaker than the strongest wire otherwise
+pub fn wire_strength(world: &World, pos: IVec3) -> u8 {
+	ADJACENT.iter()
+		.map(|side| {
+			let block = world.get_block(pos + *side);
+			match block.props().signal {
+				Some(SignalRole::Wire) => output(block).saturating_sub(1),
+				_ => output(block),
+			}
+		})
+		.max()
+		.unwrap_or(0)
+}
+
+/// Switches a lever on or off
+pub fn toggle_lever(world: &mut World, pos: IVec3) {
+	let state = world.get_block(pos).state();
+	world.set_block_state(pos, state ^ ON);
+}
+
+/// Switches a button on, its scheduled tick switches it back off
+pub fn press_button(world: &mut World, pos: IVec3) {
+	world.set_block_state(pos, ON);
+}
+
+//
+// Block behaviors used by the props table
+//
+
+/// Scheduled tick of the button: lets go after `BUTTON_TICKS`
+pub fn button_release(world: &mut World, pos: IVec3, _rand: &mut Rand) {
+	world.set_block_state(pos, 0);
+}
+
+/// Neighbor update of the wires: picks up the signal around them
+pub fn wire_update(world: &mut World, pos: IVec3, _source: IVec3) {
+	let level = wire_strength(world, pos);
+	world.set_block_state(pos, level);
+}
+
+/// Neighbor update of the consumers: powered while any signal reaches them
+pub fn consumer_update(world: &mut World, pos: IVec3, _source: IVec3) {
+	let powered = if received(world, pos) > 0 { ON } else { 0 };
+	world.set_block_state(pos, powered);
+}
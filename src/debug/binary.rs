@@ -11,7 +11,13 @@ use crate::block::math::{BlockRotation, ChunkCoord};
 #[cfg(test)]
 use crate::block::main::{Block, Chunk, BlockStorage};
 #[cfg(test)]
-use crate::ext::config;
+use crate::block::{entity::BlockEntity, props::mat};
+#[cfg(test)]
+use crate::game::items::{ItemData, ItemStack, ToolFlags};
+#[cfg(test)]
+use crate::world::manager::get_save_path;
+#[cfg(test)]
+use crate::ext::ptr;
 #[cfg(test)]
 use std::io::{Read, Write};
 #[cfg(test)]
@@ -56,6 +62,7 @@ fn block_serialization() {
 		Block::None,
 		Block::Simple(42, BlockRotation::XplusYplus),
 		Block::Simple(65535, BlockRotation::ZminusYminus),
+		Block::Stateful(33, BlockRotation::XplusYplus, 0b1011),
 	];
 	
 	for block in test_blocks {
@@ -91,6 +98,7 @@ fn block_invalid_data() {
 fn block_binary_size() {
 	assert_eq!(Block::None.binary_size(), 1);
 	assert_eq!(Block::Simple(0, BlockRotation::XplusYplus).binary_size(), 4);
+	assert_eq!(Block::Stateful(0, BlockRotation::XplusYplus, 0).binary_size(), 5);
 }
 
 #[test]
@@ -124,7 +132,7 @@ fn sparse_chunk_serialization() {
 	
 	let mut indices = Box::new([0; 4096]);
 	for i in 0..4096 {
-		indices[i] = (i % 3) as u8;
+		indices[i] = (i % 2) as u8; // only indices into the palette are valid
 	}
 	chunk.storage = BlockStorage::Sparse(indices);
 	
@@ -159,6 +167,60 @@ fn chunk_missing_data() {
 	assert!(Chunk::from_binary(&data).is_none()); // Missing indices
 }
 
+#[test]
+fn chest_chunk_serialization() {
+	let mut chunk = Chunk::empty();
+	chunk.set_block(7, Block::new(mat::CHEST));
+	let mut tool = ItemStack::new_item(3, 1);
+	tool.data = Some(Box::new(ItemData { durability: Some(250), tool: Some(ToolFlags(1)), hunger: None, armor: Some(-2), effects: Some(vec![1, 70000]) }));
+	let container = chunk.get_entity_mut(7).and_then(|entity| entity.container_mut()).expect("chest should have storage");
+	container.set(0, Some(ItemStack::new_block(5, 64)));
+	container.set(4, Some(tool));
+	
+	let binary = chunk.to_binary();
+	assert_eq!(chunk.binary_size(), binary.len());
+	let restored = Chunk::from_binary(&binary).unwrap();
+	assert_eq!(chunk.entities, restored.entities);
+}
+#[test]
+fn chunk_without_entity_section() {
+	// Chunks saved before block entities end right after the block storage
+	let mut chunk = Chunk::empty();
+	chunk.set_block(7, Block::new(mat::PLANK));
+	let mut binary = chunk.to_binary();
+	binary.truncate(binary.len() - 2);
+	let restored = Chunk::from_binary(&binary).unwrap();
+	assert_eq!(chunk.palette, restored.palette);
+	assert_eq!(chunk.storage, restored.storage);
+	assert!(restored.entities.is_empty());
+}
+#[test]
+fn world_without_entity_sections() {
+	// Worlds saved before block entities start with the chunk count and have none after any chunk
+	let mut chunk = Chunk::empty();
+	chunk.set_block(7, Block::new(mat::PLANK));
+	let mut data = 2u32.to_le_bytes().to_vec();
+	for coord in [ChunkCoord::new(0, 0, 0), ChunkCoord::new(1, 2, 3)] {
+		let binary = chunk.to_binary();
+		data.extend_from_slice(&coord.to_bytes());
+		data.extend_from_slice(&binary[..binary.len() - 2]);
+	}
+	let restored = World::from_binary(&data).unwrap();
+	assert_eq!(restored.chunks.len(), 2);
+	for restored_chunk in restored.chunks.values() {
+		assert_eq!(chunk.storage, restored_chunk.storage);
+		assert!(restored_chunk.entities.is_empty());
+	}
+}
+#[test]
+fn chest_entity_follows_block() {
+	let mut chunk = Chunk::empty();
+	chunk.set_block(7, Block::new(mat::CHEST));
+	assert!(matches!(chunk.get_entity(7), Some(BlockEntity::Chest(_))));
+	chunk.set_block(7, Block::new(mat::PLANK));
+	assert!(chunk.get_entity(7).is_none());
+}
+
 #[test]
 fn chunk_binary_size() {
 	let mut chunk = Chunk::new(1u16);
@@ -250,20 +312,20 @@ fn save_load_nonexistent_chunk() {
 	assert!(world.save_chunk(ChunkCoord::new(1, 2, 3)).is_none());
 }
 
-#[test]
+#[test] #[ignore] // starts a world, needs the window and the GPU
 fn save_load_entire_world() {
-	let temp_dir = config::get_save_path().join("world_test");
+	let temp_dir = get_save_path().join("world_test");
 	
 	// Create a test world
 	let world = create_dummy_world();
 
-	state::start_world("some_test_world");
+	state::start_world("some_test_world").unwrap();
 	
 	// Set the game state
-	config::get_gamestate().world_change(world.clone());
+	*ptr::get_gamestate().world_mut() = world.clone();
 
 	{
-	   let test_load = config::get_gamestate().world();
+	   let test_load = ptr::get_gamestate().world();
 	   // Verify
 	   assert_eq!(world.chunks.len(), test_load.chunks.len());
 	   
@@ -287,7 +349,7 @@ fn save_load_entire_world() {
 	load_entire_world(&temp_dir).unwrap();
 
 	{
-		let restored = config::get_gamestate().world();
+		let restored = ptr::get_gamestate().world();
 		
 		// Verify
 		assert_eq!(world.chunks.len(), restored.chunks.len());
@@ -337,7 +399,7 @@ fn world_serialization_roundtrip() {
 
 #[test]
 fn load_invalid_world() {
-	let temp_dir = config::get_save_path().join("world_test_invalid");
+	let temp_dir = get_save_path().join("world_test_invalid");
 		
 	// Try to load - should fail
 	assert!(load_entire_world(&temp_dir).is_err());
@@ -354,7 +416,7 @@ fn load_invalid_world() {
 #[test]
 fn world_serialization_to_disc() {
 
-	let path = config::get_save_path().join("test");
+	let path = get_save_path().join("test");
 
 	let world = create_dummy_world();
 
@@ -0,0 +1,117 @@
+#[cfg(test)]
+use crate::block::main::Block;
+#[cfg(test)]
+use crate::block::props::mat;
+#[cfg(test)]
+use crate::block::signal;
+#[cfg(test)]
+use crate::game::items::ItemStack;
+#[cfg(test)]
+use crate::hs::math::Rand;
+#[cfg(test)]
+use crate::world::explosion::{self, Explosion, FUSE_TICKS};
+#[cfg(test)]
+use crate::world::main::World;
+#[cfg(test)]
+use crate::physic::aabb::{AABB, PhysicsBody};
+#[cfg(test)]
+use glam::{IVec3, Vec3};
+
+#[cfg(test)]
+fn fill(world: &mut World, min: IVec3, max: IVec3, material: u16) {
+	for x in min.x..=max.x {
+		for y in min.y..=max.y {
+			for z in min.z..=max.z {
+				world.set_block(IVec3::new(x, y, z), Block::new(material));
+			}
+		}
+	}
+}
+
+#[test]
+fn blast_digs_a_crater_that_strong_blocks_stop() {
+	let mut world = World::empty();
+	fill(&mut world, IVec3::splat(-8), IVec3::splat(8), mat::DIRT);
+	world.set_block(IVec3::new(2, 0, 0), Block::new(mat::METAL_BLUE));
+	world.set_block(IVec3::new(3, 0, 0), Block::new(mat::DIRT));
+
+	let mut rand = Rand::new(9);
+	let removed = world.explode(&Explosion::new(Vec3::splat(0.5), 4.0), &mut rand);
+	assert!(removed.contains(&IVec3::ZERO) && removed.contains(&IVec3::new(1, 0, 0)));
+	assert!(world.get_block(IVec3::ZERO).is_empty());
+	// the metal holds and shields the dirt right behind it
+	assert_eq!(world.get_block(IVec3::new(2, 0, 0)).material(), mat::METAL_BLUE);
+	assert_eq!(world.get_block(IVec3::new(3, 0, 0)).material(), mat::DIRT);
+	// the rays run out of strength long before the edge
+	assert!(removed.iter().all(|pos| pos.abs().max_element() < 8));
+	assert!(removed.iter().all(|pos| world.get_block(*pos).is_empty()));
+}
+
+#[test]
+fn blast_drops_loot_and_throws_items_away() {
+	let mut world = World::empty();
+	fill(&mut world, IVec3::new(-3, -1, -3), IVec3::new(3, -1, 3), mat::STONE);
+	world.set_block(IVec3::new(0, 0, 1), Block::new(mat::WIRE));
+	world.drop_item(ItemStack::new_block(mat::SAND, 1), Vec3::new(1.5, 0.5, 0.5));
+
+	let mut rand = Rand::new(4);
+	world.explode(&Explosion::new(Vec3::new(0.5, 0.5, 0.9), 1.0), &mut rand); // power 1 drops everything it breaks
+	assert!(world.get_block(IVec3::new(0, 0, 1)).is_empty());
+	assert!(world.dropped.iter().any(|item| item.stack == ItemStack::new_block(mat::WIRE, 1)));
+	let sand = world.dropped.iter().find(|item| item.stack.item == ItemStack::new_block(mat::SAND, 1).item).unwrap();
+	assert!(sand.body.velocity.x > 0.0 && sand.body.velocity.y > 0.0);
+	// the bodies outside of the world (the player) are pushed by whoever takes the explosion
+	let taken = world.take_explosions();
+	assert_eq!(taken.len(), 1);
+	assert!(world.take_explosions().is_empty());
+	let mut player = PhysicsBody::new(AABB::from_center(Vec3::new(2.0, 0.9, 0.5), Vec3::new(0.4, 0.9, 0.4)));
+	taken[0].push(&mut player);
+	assert!(player.velocity.x > 0.0 && player.velocity.y > 0.0);
+
+	let blast = Explosion::new(Vec3::ZERO, 2.0);
+	assert_eq!(blast.knockback(Vec3::new(10.0, 0.0, 0.0)), Vec3::ZERO); // out of reach
+	assert!(blast.knockback(Vec3::new(1.0, 0.0, 0.0)).length() > blast.knockback(Vec3::new(3.0, 0.0, 0.0)).length());
+}
+
+#[test]
+fn blast_with_fire_lights_up_the_crater_floor() {
+	let mut world = World::empty();
+	fill(&mut world, IVec3::new(-6, -6, -6), IVec3::new(6, -1, 6), mat::STONE);
+	fill(&mut world, IVec3::new(-6, 0, -6), IVec3::new(6, 1, 6), mat::DIRT);
+
+	let mut rand = Rand::new(2);
+	let removed = world.explode(&Explosion::new(Vec3::new(0.5, 0.5, 0.5), 4.0).with_fire(), &mut rand);
+	let fires: Vec<&IVec3> = removed.iter().filter(|pos| world.get_block(**pos).material() == mat::FIRE).collect();
+	assert!(!fires.is_empty());
+	assert!(fires.iter().all(|pos| world.get_block(**pos - IVec3::Y).covers_side(IVec3::Y)));
+}
+
+#[test]
+fn lever_lights_a_tnt_that_sets_off_the_next_one() {
+	let mut world = World::empty();
+	fill(&mut world, IVec3::new(-4, -1, -4), IVec3::new(12, -1, 4), mat::STONE);
+	let lever = IVec3::new(-1, 0, 0);
+	let first = IVec3::ZERO;
+	let second = IVec3::new(3, 0, 0);
+	world.set_block(lever, Block::new(mat::LEVER));
+	world.set_block(first, Block::new(mat::TNT));
+	world.set_block(second, Block::new(mat::TNT));
+	for _ in 0..=FUSE_TICKS {
+		world.tick(0); // the fuse ticks scheduled by placing them do nothing while unlit
+	}
+	assert_eq!(world.get_block(first).material(), mat::TNT);
+
+	signal::toggle_lever(&mut world, lever);
+	assert!(explosion::is_lit(world.get_block(first).state()));
+	assert!(!explosion::is_lit(world.get_block(second).state()));
+	for _ in 0..FUSE_TICKS {
+		world.tick(0);
+	}
+	assert!(world.get_block(first).is_empty());
+	assert!(explosion::is_lit(world.get_block(second).state())); // caught in the blast, it got a short fuse
+	for _ in 0..FUSE_TICKS / 2 {
+		world.tick(0);
+	}
+	assert!(world.get_block(second).is_empty());
+	assert!(world.get_block(second - IVec3::Y).is_empty());
+}
@@ -0,0 +1,109 @@
+#[cfg(test)]
+use crate::block::farming::{self, MAX_MOISTURE, MAX_STAGE};
+#[cfg(test)]
+use crate::block::loot;
+#[cfg(test)]
+use crate::block::main::Block;
+#[cfg(test)]
+use crate::block::props::mat;
+#[cfg(test)]
+use crate::game::items::{Item, ItemStack};
+#[cfg(test)]
+use crate::hs::math::Rand;
+#[cfg(test)]
+use crate::world::main::World;
+#[cfg(test)]
+use glam::IVec3;
+
+#[cfg(test)]
+fn crop_at(stage: u8) -> Block {
+	let mut crop = Block::new(mat::CROP);
+	crop.set_state(stage);
+	crop
+}
+
+#[test]
+fn hoe_tills_uncovered_dirt() {
+	let mut world = World::empty();
+	let dry = IVec3::new(0, 0, 0);
+	let wet = IVec3::new(20, 0, 0);
+	world.set_block(dry, Block::new(mat::DIRT_GRASS));
+	world.set_block(wet, Block::new(mat::DIRT));
+	world.set_block(wet + IVec3::new(3, 0, 0), Block::new(mat::WATER));
+	world.set_block(IVec3::new(0, 0, 5), Block::new(mat::STONE));
+	world.set_block(IVec3::new(0, 0, 10), Block::new(mat::DIRT));
+	world.set_block(IVec3::new(0, 1, 10), Block::new(mat::STONE));
+
+	assert!(farming::till(&mut world, dry));
+	assert!(farming::till(&mut world, wet));
+	assert!(!farming::till(&mut world, IVec3::new(0, 0, 5))); // not dirt
+	assert!(!farming::till(&mut world, IVec3::new(0, 0, 10))); // covered
+	assert_eq!(world.get_block(dry).material(), mat::FARMLAND);
+	assert_eq!(world.get_block(dry).state(), 0);
+	assert_eq!(world.get_block(wet).state(), MAX_MOISTURE);
+
+	// dry farmland with nothing planted goes back to dirt, next to water it stays
+	let mut rand = Rand::new(3);
+	farming::farmland_tick(&mut world, dry, &mut rand);
+	farming::farmland_tick(&mut world, wet, &mut rand);
+	assert_eq!(world.get_block(dry).material(), mat::DIRT);
+	assert_eq!(world.get_block(wet).material(), mat::FARMLAND);
+}
+
+#[test]
+fn crops_grow_faster_in_light_and_next_to_water() {
+	let mut world = World::empty();
+	let dry = IVec3::new(0, 1, 0);
+	let wet = IVec3::new(20, 1, 0);
+	for farmland in [dry, wet] {
+		world.set_block(farmland - IVec3::Y, Block::new(mat::STONE));
+		world.set_block(farmland, Block::new(mat::FARMLAND));
+		world.set_block(farmland + IVec3::Y, crop_at(0));
+	}
+	world.set_block_state(wet, MAX_MOISTURE);
+	let dry_chance = farming::growth_chance(&world, dry + IVec3::Y);
+	let wet_chance = farming::growth_chance(&world, wet + IVec3::Y);
+	assert!(dry_chance > 0.0);
+	assert_eq!(wet_chance, dry_chance * 2.0);
+
+	let mut rand = Rand::new(11);
+	let mut ticks = 0;
+	while world.get_block(wet + IVec3::Y).state() < MAX_STAGE {
+		farming::crop_grow(&mut world, wet + IVec3::Y, &mut rand);
+		ticks += 1;
+		assert!(ticks < 1000);
+	}
+	farming::crop_grow(&mut world, wet + IVec3::Y, &mut rand);
+	assert_eq!(world.get_block(wet + IVec3::Y).state(), MAX_STAGE); // ripe, it stops there
+
+	// boxed in with stone, not a bit of light reaches it
+	let crop = dry + IVec3::Y;
+	for side in [IVec3::X, IVec3::NEG_X, IVec3::Z, IVec3::NEG_Z] {
+		world.set_block(dry + side, Block::new(mat::STONE));
+		world.set_block(crop + side, Block::new(mat::STONE));
+	}
+	world.set_block(crop + IVec3::Y, Block::new(mat::STONE));
+	assert_eq!(farming::growth_chance(&world, crop), 0.0);
+}
+
+#[test]
+fn ripe_crops_drop_more_and_crops_pop_off_without_farmland() {
+	let mut rand = Rand::new(5);
+	assert_eq!(loot::roll(&crop_at(MAX_STAGE - 1), &mut rand), vec![ItemStack::new_block(mat::CROP, 1)]);
+	let ripe: Vec<ItemStack> = (0..20).flat_map(|_| loot::roll(&crop_at(MAX_STAGE), &mut rand)).collect();
+	assert!(ripe.iter().all(|stack| stack.item == Item::Block(mat::WHEAT) || stack.item == Item::Block(mat::CROP)));
+	assert!(ripe.iter().any(|stack| stack.item == Item::Block(mat::WHEAT)));
+
+	let mut world = World::empty();
+	let farmland = IVec3::new(0, 0, 0);
+	world.set_block(farmland, Block::new(mat::FARMLAND));
+	world.set_block(farmland + IVec3::Y, crop_at(2));
+	world.tick(0);
+	assert_eq!(world.get_block(farmland + IVec3::Y).material(), mat::CROP);
+
+	world.set_block(farmland, Block::new(mat::DIRT));
+	world.tick(0);
+	assert!(world.get_block(farmland + IVec3::Y).is_empty());
+	assert_eq!(world.dropped.len(), 1);
+	assert_eq!(world.dropped[0].stack, ItemStack::new_block(mat::CROP, 1));
+}
@@ -0,0 +1,73 @@
+#[cfg(test)]
+use crate::block::interact;
+#[cfg(test)]
+use crate::block::main::Block;
+#[cfg(test)]
+use crate::block::math::{Axis, BlockRotation};
+#[cfg(test)]
+use crate::block::props::mat;
+#[cfg(test)]
+use crate::block::shape::{BlockBox, UNITS};
+#[cfg(test)]
+use crate::block::signal;
+#[cfg(test)]
+use crate::game::items::ItemStack;
+#[cfg(test)]
+use crate::world::main::World;
+#[cfg(test)]
+use glam::{IVec3, Vec3};
+
+#[test]
+fn boxes_turn_around_the_vertical_axis() {
+	let east = BlockBox::new([13, 0, 0], [16, 16, 16]);
+	assert_eq!(east.turned(1), BlockBox::new([0, 0, 13], [16, 16, 16])); // +X turns to +Z
+	assert_eq!(east.turned(4), east);
+	assert!(east.touches_side(IVec3::X) && east.touches_side(IVec3::Y));
+	assert!(!east.touches_side(IVec3::NEG_X));
+	assert_eq!(BlockBox::FULL.packed() >> 15, UNITS as u32 | (UNITS as u32) << 5 | (UNITS as u32) << 10);
+}
+
+#[test]
+fn doors_open_by_hand_and_by_signal() {
+	let mut world = World::empty();
+	let door = IVec3::new(0, 0, 0);
+	let mut block = Block::new(mat::DOOR);
+	block.set_rotation(BlockRotation::facing(Axis::Zplus));
+	world.set_block(door, block);
+	let closed = world.get_block(door).shape()[0];
+	assert_eq!(closed, BlockBox::new([0, 0, 13], [16, 16, 16]));
+	assert_eq!(world.get_block(door).collision(), &[closed]);
+
+	interact::use_door(&mut world, door);
+	assert!(interact::is_open(world.get_block(door).state()));
+	assert_ne!(world.get_block(door).shape()[0], closed);
+	assert!(world.get_block(door).collision().is_empty());
+	interact::use_door(&mut world, door);
+	assert!(!interact::is_open(world.get_block(door).state()));
+
+	let lever = door + IVec3::NEG_X;
+	world.set_block(lever, Block::new(mat::LEVER));
+	signal::toggle_lever(&mut world, lever);
+	assert!(interact::is_open(world.get_block(door).state()));
+	signal::toggle_lever(&mut world, lever);
+	assert!(!interact::is_open(world.get_block(door).state()));
+}
+
+#[test]
+fn dropped_items_fall_through_open_trapdoors() {
+	let mut world = World::empty();
+	world.set_block(IVec3::new(0, 0, 0), Block::new(mat::STONE));
+	let trapdoor = IVec3::new(0, 3, 0);
+	world.set_block(trapdoor, Block::new(mat::TRAPDOOR));
+	world.drop_item(ItemStack::new_block(mat::DIRT, 1), Vec3::new(0.5, 6.5, 0.5));
+	for _ in 0..40 {
+		world.update_dropped_items(1.0 / 20.0);
+	}
+	assert_eq!(world.dropped[0].body.aabb.min.y, 3.0 + 3.0 / UNITS as f32); // on the closed trapdoor
+
+	interact::use_door(&mut world, trapdoor);
+	for _ in 0..40 {
+		world.update_dropped_items(1.0 / 20.0);
+	}
+	assert_eq!(world.dropped[0].body.aabb.min.y, 1.0);
+}
@@ -0,0 +1,139 @@
+#[cfg(test)]
+use crate::block::light::{LightKind, MAX_LIGHT};
+#[cfg(test)]
+use crate::world::main::World;
+#[cfg(test)]
+use crate::block::main::{Block, Chunk};
+#[cfg(test)]
+use crate::block::math::{BlockPosition, ChunkCoord};
+#[cfg(test)]
+use crate::render::meshing::{self, ChunkMeshBuilder};
+#[cfg(test)]
+use crate::render::world::NeighboringChunks;
+#[cfg(test)]
+use crate::block::props::mat;
+#[cfg(test)]
+use crate::world::generator::{self, FlatGen, WorldGenerator};
+#[cfg(test)]
+use crate::world::terrain::TerrainGen;
+#[cfg(test)]
+use glam::{IVec3, Vec3};
+#[cfg(test)]
+use std::sync::Arc;
+
+#[cfg(test)]
+fn sky(world: &World, pos: IVec3) -> u8 {
+	LightKind::Sky.get(world.get_light(pos))
+}
+#[cfg(test)]
+fn block_light(world: &World, pos: IVec3) -> u8 {
+	LightKind::Block.get(world.get_light(pos))
+}
+
+#[test]
+fn sky_light_shines_down_and_around_a_roof() {
+	let mut world = World::empty();
+	world.set_block(IVec3::new(0, 20, 0), Block::None); // loads the chunk above first
+	world.set_block(IVec3::new(0, 0, 0), Block::None);
+	assert_eq!(sky(&world, IVec3::new(0, 0, 0)), MAX_LIGHT);
+
+	world.set_block(IVec3::new(0, 10, 0), Block::new(mat::STONE));
+	assert_eq!(sky(&world, IVec3::new(0, 10, 0)), 0);
+	assert_eq!(sky(&world, IVec3::new(0, 9, 0)), MAX_LIGHT - 1); // lit from the side
+	assert_eq!(sky(&world, IVec3::new(0, 0, 0)), MAX_LIGHT - 1);
+
+	world.set_block(IVec3::new(0, 10, 0), Block::None);
+	assert_eq!(sky(&world, IVec3::new(0, 0, 0)), MAX_LIGHT);
+}
+
+#[test]
+fn lamp_light_crosses_chunks_and_goes_out() {
+	let mut world = World::empty();
+	for x in -20..=20 {
+		world.set_block(IVec3::new(x, 40, 0), Block::new(mat::STONE));
+	}
+	let lamp = IVec3::new(14, 40, 0);
+	world.set_block(lamp, Block::new(mat::LAMP));
+	assert_eq!(block_light(&world, lamp), MAX_LIGHT);
+	assert_eq!(block_light(&world, lamp + IVec3::Y), MAX_LIGHT - 1);
+	assert_eq!(block_light(&world, IVec3::new(17, 41, 0)), MAX_LIGHT - 4); // next chunk over
+	assert_eq!(block_light(&world, IVec3::new(15, 40, 0)), 0); // stone stays dark
+
+	world.set_block(lamp, Block::new(mat::STONE));
+	assert_eq!(block_light(&world, lamp + IVec3::Y), 0);
+	assert_eq!(block_light(&world, IVec3::new(17, 41, 0)), 0);
+}
+
+#[test]
+fn covering_chunk_darkens_the_one_below() {
+	let mut world = World::empty();
+	world.set_block(IVec3::new(0, 0, 0), Block::None);
+	assert_eq!(sky(&world, IVec3::new(3, 0, 3)), MAX_LIGHT); // nothing above, sees the sky
+
+	world.set_chunk(ChunkCoord::new(0, 1, 0), Chunk::new(mat::STONE));
+	assert_eq!(sky(&world, IVec3::new(3, 0, 3)), 0);
+}
+
+#[test]
+fn buried_chunks_stay_dark() {
+	// the topmost loaded chunk deep under the ground doesn't see the sky, even with nothing loaded above it
+	let mut world = World::empty();
+	world.generator = Arc::new(TerrainGen::new(7));
+	world.set_chunk(ChunkCoord::new(0, -10, 0), Chunk::empty()); // a cave
+	assert_eq!(sky(&world, IVec3::new(3, -150, 3)), 0);
+	// the same chunk in the void is open to the sky
+	let mut world = World::empty();
+	world.set_chunk(ChunkCoord::new(0, -10, 0), Chunk::empty());
+	assert_eq!(sky(&world, IVec3::new(3, -150, 3)), MAX_LIGHT);
+}
+
+#[test]
+fn digging_through_the_ground_lets_the_sky_in() {
+	let mut world = World::empty();
+	world.generator = Arc::new(FlatGen::new(generator::parse_layers(generator::DEFAULT_LAYERS).unwrap()));
+	let coord = ChunkCoord::new(0, -1, 0);
+	world.set_chunk(coord, world.generator.generate(coord));
+	let below = IVec3::new(3, -10, 3);
+	assert_eq!(sky(&world, below), 0);
+	assert_eq!(world.sky_height(3, 3), generator::FLAT_TOP);
+	for y in (-6..=-1).rev() {
+		world.set_block(IVec3::new(3, y, 3), Block::None);
+	}
+	assert_eq!(sky(&world, below), MAX_LIGHT);
+}
+
+#[test]
+fn corners_next_to_a_wall_get_darker() {
+	let mut chunk = Chunk::empty();
+	chunk.set_block(BlockPosition::from((5u8, 5u8, 5u8)).into(), Block::new(mat::STONE));
+	chunk.set_block(BlockPosition::from((4u8, 6u8, 5u8)).into(), Block::new(mat::STONE)); // wall next to the top face
+
+	let mut builder = ChunkMeshBuilder::new();
+	builder.add_cube(IVec3::new(5, 5, 5), Block::new(mat::STONE), &chunk, &NeighboringChunks::new([None; 27]));
+	let top = builder.instances.iter().find(|face| (face.packed_data >> 12) & 0x7 == 4).unwrap();
+	let ao: Vec<u32> = (0..4).map(|corner| (top.light >> (8 + corner * 2)) & 0x3).collect();
+	assert_eq!(ao, vec![2, 2, 3, 3]); // the corners on the wall side (x = 0)
+}
+
+#[test]
+fn translucent_faces_sort_back_to_front() {
+	let mut chunk = Chunk::empty();
+	let (near, far) = (IVec3::new(8, 5, 5), IVec3::new(2, 5, 5));
+	chunk.set_block(BlockPosition::from(near).into(), Block::new(mat::WATER));
+	chunk.set_block(BlockPosition::from(far).into(), Block::new(mat::WATER));
+	let mut builder = ChunkMeshBuilder::new();
+	for pos in [near, far] {
+		builder.add_cube(pos, Block::new(mat::WATER), &chunk, &NeighboringChunks::new([None; 27]));
+	}
+	let side = |face: &meshing::InstanceRaw| ((face.packed_data & 0xF) as i32, (face.packed_data >> 12) & 0x7);
+	assert!(builder.translucent.len() >= 2);
+
+	// looking from +X, the -X face of the far block goes first and the +X face of the near one last
+	meshing::sort_back_to_front(&mut builder.translucent, Vec3::new(20.0, 5.5, 5.5));
+	assert_eq!(side(&builder.translucent[0]), (far.x, 0));
+	assert_eq!(side(builder.translucent.last().unwrap()), (near.x, 1));
+	// turned around, the order turns too
+	meshing::sort_back_to_front(&mut builder.translucent, Vec3::new(-10.0, 5.5, 5.5));
+	assert_eq!(side(&builder.translucent[0]), (near.x, 1));
+	assert_eq!(side(builder.translucent.last().unwrap()), (far.x, 0));
+}
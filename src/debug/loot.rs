@@ -0,0 +1,76 @@
+#[cfg(test)]
+use crate::block::{extra, loot};
+#[cfg(test)]
+use crate::block::main::Block;
+#[cfg(test)]
+use crate::block::props::mat;
+#[cfg(test)]
+use crate::game::inventory::{Inventory, ItemContainer};
+#[cfg(test)]
+use crate::game::items::{Item, ItemStack, MAX_STACK};
+#[cfg(test)]
+use crate::hs::math::Rand;
+#[cfg(test)]
+use crate::world::main::World;
+#[cfg(test)]
+use glam::{IVec3, Vec3};
+
+#[test]
+fn blocks_drop_their_loot() {
+	let mut rand = Rand::new(7);
+	assert_eq!(loot::roll(&Block::new(mat::STONE), &mut rand), vec![ItemStack::new_block(mat::ROCK, 1)]);
+	assert_eq!(loot::roll(&Block::new(mat::DIRT_GRASS), &mut rand), vec![ItemStack::new_block(mat::DIRT, 1)]);
+	assert_eq!(loot::roll(&Block::new(mat::PLANK), &mut rand), vec![ItemStack::new_block(mat::PLANK, 1)]); // no table drops itself
+	assert!(loot::roll(&Block::new(mat::WATER), &mut rand).is_empty());
+
+	// every gravel drop is one of its two entries, both show up sooner or later
+	let drops: Vec<Item> = (0..200).flat_map(|_| loot::roll(&Block::new(mat::GRAVEL), &mut rand)).map(|stack| stack.item).collect();
+	assert!(drops.iter().all(|item| *item == Item::Block(mat::GRAVEL) || *item == Item::Block(mat::SAND)));
+	assert!(drops.contains(&Item::Block(mat::GRAVEL)) && drops.contains(&Item::Block(mat::SAND)));
+}
+
+#[test]
+fn adding_items_tops_up_stacks_or_changes_nothing() {
+	let mut container = ItemContainer::new_linear(2);
+	assert!(container.add_item(ItemStack::new_block(mat::DIRT, 40)));
+	assert!(container.add_item(ItemStack::new_block(mat::DIRT, 40)));
+	assert_eq!(container.get(0).map(|stack| stack.quantity), Some(MAX_STACK));
+	assert_eq!(container.get(1).map(|stack| stack.quantity), Some(80 - MAX_STACK));
+
+	let before = container.clone();
+	assert!(!container.add_item(ItemStack::new_block(mat::DIRT, 60))); // only 48 fit
+	assert!(!container.add_item(ItemStack::new_block(mat::STONE, 1)));
+	assert_eq!(container, before);
+}
+
+#[test]
+fn broken_chest_drops_its_contents() {
+	let mut world = World::empty();
+	let pos = IVec3::new(2, 3, 4);
+	world.set_block(pos, Block::new(mat::CHEST));
+	let chest = world.get_block_entity_mut(pos).and_then(|entity| entity.container_mut()).unwrap();
+	chest.set(3, Some(ItemStack::new_block(mat::SAND, 5)));
+
+	let drops = extra::break_block(&mut world, pos, &mut Rand::new(1));
+	assert!(world.get_block(pos).is_empty());
+	assert_eq!(drops, vec![ItemStack::new_block(mat::CHEST, 1), ItemStack::new_block(mat::SAND, 5)]);
+}
+
+#[test]
+fn dropped_items_land_and_get_picked_up() {
+	let mut world = World::empty();
+	world.set_block(IVec3::new(0, 0, 0), Block::new(mat::STONE));
+	world.drop_item(ItemStack::new_block(mat::ROCK, 3), Vec3::new(0.5, 4.5, 0.5));
+	for _ in 0..40 {
+		world.update_dropped_items(1.0 / 20.0);
+	}
+	assert_eq!(world.dropped[0].block_pos(), IVec3::new(0, 1, 0));
+	assert_eq!(world.dropped[0].body.aabb.min.y, 1.0);
+
+	let mut inventory = Inventory::default();
+	world.pick_up_dropped_items(Vec3::new(10.0, 1.0, 0.5), &mut inventory); // too far
+	assert_eq!(world.dropped.len(), 1);
+	world.pick_up_dropped_items(Vec3::new(1.0, 1.0, 0.5), &mut inventory);
+	assert!(world.dropped.is_empty());
+	assert_eq!(inventory.get_hotbar(0), Some(&ItemStack::new_block(mat::ROCK, 3)));
+}
@@ -1,12 +1,12 @@
 
 #[cfg(test)]
-use crate::world::manager::{WorldData, load_world_data, save_world_data, update_world_data};
-#[cfg(test)]
-use crate::ext::config;
+use crate::world::manager::{WorldData, get_save_path, load_world_data, save_world_data, update_world_data};
 #[cfg(test)]
 use std::io::{self};
 #[cfg(test)]
 use crate::hs::time::Time;
+#[cfg(test)]
+use crate::world::generator::{self, GeneratorPreset, GeneratorSettings};
 
 // Test 1: Simple roundtrip serialization/deserialization with perfect data
 #[test]
@@ -15,6 +15,8 @@ fn roundtrip_serialization() {
 		version: "1.0.0".to_string(),
 		creation_date: Time::now(),
 		last_opened_date: Time::now(),
+		generator: GeneratorSettings::new(GeneratorPreset::Superflat, 1234)
+			.with_layers(generator::parse_layers("stone*10,sand*2").unwrap()),
 	};
 
 	let bytes = original.to_bytes();
@@ -23,12 +25,25 @@ fn roundtrip_serialization() {
 	assert_eq!(original.version, deserialized.version);
 	assert_eq!(original.creation_date, deserialized.creation_date);
 	assert_eq!(original.last_opened_date, deserialized.last_opened_date);
+	assert_eq!(original.generator, deserialized.generator);
+}
+
+// Test 1b: Data saved before the generator settings existed loads with the default generator
+#[test]
+fn data_without_generator() {
+	let data = WorldData::new();
+	let bytes = data.to_bytes();
+	let old_len = bytes.len() - data.generator.to_bytes().len();
+	let loaded = WorldData::from_bytes(&bytes[..old_len]).unwrap();
+	assert_eq!(loaded.generator, GeneratorSettings::default());
+	// cut off halfway is broken data though
+	assert!(WorldData::from_bytes(&bytes[..old_len + 3]).is_err());
 }
 
 // Test 2: File operations with correct data
 #[test]
 fn file_operations() -> io::Result<()> {
-	let temp_dir = config::get_save_path().join("test");
+	let temp_dir = get_save_path().join("test");
 	let path = temp_dir.as_path();
 
 	// Test creating new data when file doesn't exist
@@ -0,0 +1,89 @@
+#[cfg(test)]
+use crate::game::mining::{Mining, CRACK_STAGES};
+#[cfg(test)]
+use crate::game::items::{ItemData, ItemStack, ToolFlags};
+#[cfg(test)]
+use crate::block::props::{self, mat};
+#[cfg(test)]
+use crate::block::{extra, main::Block};
+#[cfg(test)]
+use crate::world::main::World;
+#[cfg(test)]
+use crate::hs::math::Rand;
+#[cfg(test)]
+use glam::{IVec3, Vec3};
+
+#[test]
+fn right_tool_mines_faster() {
+	let stone = props::get(mat::STONE);
+	let hand = stone.break_time(None).unwrap();
+	let pickaxe = stone.break_time(Some(ToolFlags(ToolFlags::PICKAXE))).unwrap();
+	let shovel = stone.break_time(Some(ToolFlags(ToolFlags::SHOVEL))).unwrap();
+	assert!(pickaxe < hand);
+	assert_eq!(shovel, hand); // the wrong tool is no better than the hand
+	assert!(props::get(mat::WATER).break_time(None).is_none());
+}
+
+#[test]
+fn mining_breaks_after_break_time_and_resets_on_look_away() {
+	let mut mining = Mining::new();
+	let block = IVec3::new(1, 2, 3);
+	assert_eq!(mining.step(Some(block), Some(1.0), 0.5), None); // not held
+
+	mining.held = true;
+	assert_eq!(mining.step(Some(block), Some(1.0), 0.5), None);
+	assert_eq!(mining.stage(), Some(CRACK_STAGES / 2 + 1));
+
+	// looking at something else starts over
+	assert_eq!(mining.step(Some(IVec3::ZERO), Some(1.0), 0.5), None);
+	assert_eq!(mining.target(), Some(IVec3::ZERO));
+	assert_eq!(mining.progress(), 0.5);
+	assert_eq!(mining.step(Some(block), Some(1.0), 0.6), None);
+	assert_eq!(mining.step(Some(block), Some(1.0), 0.6), Some(block));
+	assert_eq!(mining.stage(), None);
+
+	mining.held = false;
+	mining.step(Some(block), Some(1.0), 0.6);
+	assert_eq!(mining.target(), None);
+}
+
+#[test]
+fn tools_wear_out() {
+	let mut pickaxe = ItemStack::new_item(1, 1);
+	pickaxe.data = Some(Box::new(ItemData {
+		durability: Some(2),
+		tool: Some(ToolFlags(ToolFlags::PICKAXE)),
+		hunger: None,
+		armor: None,
+		effects: None,
+	}));
+	assert!(!pickaxe.damage());
+	assert!(pickaxe.damage());
+	assert!(!ItemStack::new_block(mat::STONE, 1).damage()); // nothing to wear out
+}
+
+#[test]
+fn blocks_under_water_can_be_mined() {
+	let mut world = World::empty();
+	let floor = IVec3::new(0, 0, 0);
+	world.set_block(floor, Block::new(mat::STONE));
+	for y in 1..4 {
+		world.set_block(IVec3::new(0, y, 0), Block::new(mat::WATER));
+	}
+
+	// looking down through the water finds the floor
+	let (looked, normal, _) = extra::raycast(&world, Vec3::new(0.5, 4.5, 0.5), Vec3::NEG_Y, 6.0).unwrap();
+	assert_eq!((looked, normal), (floor, IVec3::Y));
+
+	let mut mining = Mining::new();
+	mining.held = true;
+	let break_time = world.get_block(looked).props().break_time(None);
+	assert!(break_time.is_some());
+	let mut broken = None;
+	for _ in 0..100 {
+		broken = broken.or(mining.step(Some(looked), break_time, 0.5));
+	}
+	assert_eq!(broken, Some(floor));
+	extra::break_block(&mut world, floor, &mut Rand::new(1));
+	assert_ne!(world.get_block(floor).material(), mat::STONE);
+}
@@ -69,7 +69,7 @@ pub fn network_test() {
 pub fn network_host_test() {
 	println!("=== STARTING HOST TEST ===");
 
-	state::start_world("test_world");
+	state::start_world("test_world").unwrap();
 	
 	// Start as host
 	match api::begin_online_giveaway() {
@@ -1,7 +1,5 @@
 #[cfg(test)]
-use crate::physic::aabb::{self, AABB};
-#[cfg(test)]
-use crate::physic::body::{self, PhysicsBody};
+use crate::physic::aabb::{AABB, PhysicsBody};
 
 #[cfg(test)]
 mod tests {
@@ -25,7 +23,7 @@ mod tests {
 		let penetration = a.penetration_vector(&b).unwrap();
 		assert!(penetration.x < 0.0 && penetration.y == 0.0 && penetration.z == 0.0);
 		
-		let c = AABB::new(vec3(8.0, -2.0, 8.0), vec3(18.0, 8.0, 18.0));
+		let c = AABB::new(vec3(2.0, -2.0, 2.0), vec3(8.0, 2.0, 8.0)); // sticks out of the bottom, least deep on y
 		let penetration = a.penetration_vector(&c).unwrap();
 		assert!(penetration.x == 0.0 && penetration.y > 0.0 && penetration.z == 0.0);
 	}
@@ -33,11 +31,11 @@ mod tests {
 	#[test]
 	fn test_physics_update() {
 		let mut body = PhysicsBody {
-			aabb: AABB::new(vec3(0.0, 0.0, 0.0), vec3(1.0, 1.0, 1.0)),
 			velocity: vec3(1.0, 0.0, 0.0),
 			acceleration: vec3(0.0, -9.8, 0.0),
 			restitution: 0.5,
 			friction: 0.2,
+			..PhysicsBody::new(AABB::new(vec3(0.0, 0.0, 0.0), vec3(1.0, 1.0, 1.0)))
 		};
 		
 		body.update(1.0, vec3(0.0, -9.8, 0.0));
@@ -0,0 +1,117 @@
+#[cfg(test)]
+use crate::block::extra;
+#[cfg(test)]
+use crate::block::main::Block;
+#[cfg(test)]
+use crate::block::math::{Axis, BlockRotation};
+#[cfg(test)]
+use crate::block::props::{mat, Placement};
+#[cfg(test)]
+use crate::block::shape::BlockBox;
+#[cfg(test)]
+use crate::world::main::World;
+#[cfg(test)]
+use crate::game::items::ItemStack;
+#[cfg(test)]
+use glam::{IVec3, Vec3};
+
+#[test]
+fn rotations_round_trip_through_their_axes() {
+	let axes = [Axis::Xplus, Axis::Xminus, Axis::Yplus, Axis::Yminus, Axis::Zplus, Axis::Zminus];
+	let mut count = 0;
+	for primary in axes {
+		for secondary in axes {
+			let Some(rotation) = BlockRotation::from_axes(primary, secondary) else {
+				continue;
+			};
+			assert_eq!((rotation.primary_axis(), rotation.secondary_axis()), (primary, secondary));
+			count += 1;
+		}
+	}
+	assert_eq!(count, 24);
+}
+
+#[test]
+fn slabs_and_stairs_follow_their_rotation() {
+	let placed = |material, placement: Placement, facing, upper| {
+		let mut block = Block::new(material);
+		block.set_rotation(placement.rotation(facing, upper).unwrap());
+		block
+	};
+	let bottom = placed(mat::STONE_SLAB, Placement::Half, Axis::Xplus, false);
+	let top = placed(mat::STONE_SLAB, Placement::Half, Axis::Xplus, true);
+	assert!(bottom.covers_side(IVec3::NEG_Y) && !bottom.covers_side(IVec3::Y) && !bottom.covers_side(IVec3::X));
+	assert!(top.covers_side(IVec3::Y) && !top.covers_side(IVec3::NEG_Y));
+
+	let stairs = placed(mat::PLANK_STAIRS, Placement::Facing, Axis::Zplus, false);
+	assert!(stairs.shape().contains(&BlockBox::new([0, 8, 8], [16, 16, 16])));
+	assert!(stairs.covers_side(IVec3::Z) && stairs.covers_side(IVec3::NEG_Y) && !stairs.covers_side(IVec3::NEG_Z));
+	let upside_down = placed(mat::PLANK_STAIRS, Placement::Facing, Axis::Zplus, true);
+	assert!(upside_down.covers_side(IVec3::Y) && upside_down.covers_side(IVec3::Z) && !upside_down.covers_side(IVec3::NEG_Y));
+}
+
+#[test]
+fn fences_connect_to_fences_and_full_sides() {
+	let mut world = World::empty();
+	let fence = IVec3::ZERO;
+	world.set_block(IVec3::NEG_Z, Block::new(mat::STONE));
+	world.set_block(IVec3::Z, Block::new(mat::STONE_SLAB)); // only half a side, nothing to connect to
+	world.set_block(fence, Block::new(mat::FENCE));
+	assert_eq!(world.get_block(fence).state(), 0b1000);
+
+	world.set_block(IVec3::X, Block::new(mat::FENCE));
+	assert_eq!(world.get_block(fence).state(), 0b1001);
+	assert_eq!(world.get_block(IVec3::X).state(), 0b0100);
+	assert_eq!(world.get_block(fence).shape().len(), 5); // the post and two rails each way
+
+	world.set_block(IVec3::NEG_Z, Block::None);
+	assert_eq!(world.get_block(fence).state(), 0b0001);
+}
+
+#[test]
+fn rays_pass_over_the_empty_half_of_a_slab() {
+	let mut world = World::empty();
+	world.set_block(IVec3::ZERO, Block::new(mat::STONE_SLAB));
+	world.set_block(IVec3::new(3, 0, 0), Block::new(mat::STONE));
+
+	let (pos, normal, _) = extra::raycast(&world, Vec3::new(-2.0, 0.75, 0.5), Vec3::X, 10.0).unwrap();
+	assert_eq!((pos, normal), (IVec3::new(3, 0, 0), IVec3::NEG_X));
+	let (pos, normal, _) = extra::raycast(&world, Vec3::new(-2.0, 0.25, 0.5), Vec3::X, 10.0).unwrap();
+	assert_eq!((pos, normal), (IVec3::ZERO, IVec3::NEG_X));
+	let (pos, normal, hit) = extra::raycast(&world, Vec3::new(0.5, 3.0, 0.5), Vec3::NEG_Y, 10.0).unwrap();
+	assert_eq!((pos, normal), (IVec3::ZERO, IVec3::Y));
+	assert!((hit.y - 0.5).abs() < 1e-5);
+}
+
+#[test]
+fn bodies_rest_on_slabs_and_stairs() {
+	let mut world = World::empty();
+	let slab = IVec3::ZERO;
+	let stairs = IVec3::new(3, 0, 0);
+	world.set_block(slab, Block::new(mat::STONE_SLAB));
+	let mut block = Block::new(mat::PLANK_STAIRS);
+	block.set_rotation(Placement::Facing.rotation(Axis::Zplus, false).unwrap()); // the high step at +Z
+	world.set_block(stairs, block);
+
+	// items lie on the boxes, not on the block grid
+	world.drop_item(ItemStack::new_block(mat::DIRT, 1), Vec3::new(0.5, 4.5, 0.5));
+	world.drop_item(ItemStack::new_block(mat::DIRT, 1), Vec3::new(3.5, 4.5, 0.25));
+	world.drop_item(ItemStack::new_block(mat::DIRT, 1), Vec3::new(3.5, 4.5, 0.75));
+	for _ in 0..40 {
+		world.update_dropped_items(1.0 / 20.0);
+	}
+	let rests: Vec<f32> = world.dropped.iter().map(|item| item.body.aabb.min.y).collect();
+	assert_eq!(rests, vec![0.5, 0.5, 1.0]);
+
+	// a falling block stops on the top of the slab and the step, then takes the place above them
+	world.set_block(slab + IVec3::Y * 4, Block::new(mat::SAND));
+	world.set_block(stairs + IVec3::Y * 4, Block::new(mat::SAND));
+	for _ in 0..100 {
+		world.tick(0);
+		world.update_falling_blocks(1.0 / 20.0);
+	}
+	assert!(world.falling.is_empty());
+	assert_eq!(world.get_block(slab + IVec3::Y).material(), mat::SAND);
+	assert_eq!(world.get_block(stairs + IVec3::Y).material(), mat::SAND);
+	assert_eq!(world.get_block(slab).material(), mat::STONE_SLAB);
+}
@@ -0,0 +1,77 @@
+#[cfg(test)]
+use crate::block::signal::{self, BUTTON_TICKS, MAX_SIGNAL};
+#[cfg(test)]
+use crate::block::light::{LightKind, MAX_LIGHT};
+#[cfg(test)]
+use crate::block::main::Block;
+#[cfg(test)]
+use crate::block::props::mat;
+#[cfg(test)]
+use crate::world::main::World;
+#[cfg(test)]
+use glam::IVec3;
+
+#[test]
+fn lever_powers_a_wire_line_and_a_lamp() {
+	let mut world = World::empty();
+	let lever = IVec3::new(0, 0, 0);
+	let lamp = IVec3::new(6, 0, 0);
+	world.set_block(lever, Block::new(mat::LEVER));
+	for x in 1..6 {
+		world.set_block(IVec3::new(x, 0, 0), Block::new(mat::WIRE));
+	}
+	world.set_block(lamp, Block::new(mat::SIGNAL_LAMP));
+	assert_eq!(LightKind::Block.get(world.get_light(lamp)), 0);
+
+	signal::toggle_lever(&mut world, lever);
+	for x in 1..6 {
+		let wire = world.get_block(IVec3::new(x, 0, 0));
+		assert_eq!(signal::strength(wire.state()), MAX_SIGNAL + 1 - x as u8);
+	}
+	assert!(signal::is_on(world.get_block(lamp).state()));
+	assert_eq!(LightKind::Block.get(world.get_light(lamp)), MAX_LIGHT);
+
+	signal::toggle_lever(&mut world, lever);
+	for x in 1..6 {
+		assert_eq!(world.get_block(IVec3::new(x, 0, 0)).state(), 0);
+	}
+	assert!(!signal::is_on(world.get_block(lamp).state()));
+	assert_eq!(LightKind::Block.get(world.get_light(lamp)), 0);
+}
+
+#[test]
+fn signal_dies_out_along_a_long_wire() {
+	let mut world = World::empty();
+	let length = MAX_SIGNAL as i32 + 3;
+	world.set_block(IVec3::ZERO, Block::new(mat::LEVER));
+	for x in 1..=length {
+		world.set_block(IVec3::new(x, 0, 0), Block::new(mat::WIRE));
+	}
+	let door = IVec3::new(length + 1, 0, 0);
+	world.set_block(door, Block::new(mat::DOOR));
+
+	signal::toggle_lever(&mut world, IVec3::ZERO);
+	assert_eq!(signal::strength(world.get_block(IVec3::new(MAX_SIGNAL as i32, 0, 0)).state()), 1);
+	assert_eq!(world.get_block(IVec3::new(MAX_SIGNAL as i32 + 1, 0, 0)).state(), 0);
+	assert!(!signal::is_on(world.get_block(door).state()));
+}
+
+#[test]
+fn button_lets_go_after_a_while() {
+	let mut world = World::empty();
+	let button = IVec3::new(0, 0, 0);
+	let door = IVec3::new(0, 0, 1);
+	world.set_block(button, Block::new(mat::BUTTON));
+	world.set_block(door, Block::new(mat::DOOR));
+	for _ in 0..=BUTTON_TICKS {
+		world.tick(0); // the tick scheduled by placing the button does nothing
+	}
+
+	signal::press_button(&mut world, button);
+	assert!(signal::is_on(world.get_block(door).state()));
+	for _ in 0..BUTTON_TICKS {
+		world.tick(0);
+	}
+	assert!(!signal::is_on(world.get_block(button).state()));
+	assert!(!signal::is_on(world.get_block(door).state()));
+}
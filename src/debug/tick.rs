@@ -0,0 +1,212 @@
+#[cfg(test)]
+use crate::world::tick::{self, TickScheduler};
+#[cfg(test)]
+use crate::world::main::World;
+#[cfg(test)]
+use crate::world::neighbor::MAX_NEIGHBOR_UPDATES;
+#[cfg(test)]
+use crate::block::main::Block;
+#[cfg(test)]
+use crate::block::props::mat;
+#[cfg(test)]
+use crate::block::fluid;
+#[cfg(test)]
+use crate::hs::math::Rand;
+#[cfg(test)]
+use crate::block::math::{ADJACENT, BlockPosition, ChunkCoord};
+#[cfg(test)]
+use crate::world::falling::FALL_DELAY;
+#[cfg(test)]
+use glam::IVec3;
+
+#[test]
+fn scheduled_ticks_come_due_in_order() {
+	let mut ticks = TickScheduler::new();
+	ticks.schedule(IVec3::new(1, 0, 0), 2);
+	ticks.schedule(IVec3::new(2, 0, 0), 1);
+	ticks.schedule(IVec3::new(2, 0, 0), 1); // same position twice only ticks once
+	ticks.schedule(IVec3::new(3, 0, 0), 0); // never runs in the same tick
+	assert_eq!(ticks.pending(), 3);
+
+	let first = ticks.advance();
+	assert_eq!(first.len(), 2);
+	assert!(first.contains(&IVec3::new(2, 0, 0)) && first.contains(&IVec3::new(3, 0, 0)));
+	assert_eq!(ticks.advance(), vec![IVec3::new(1, 0, 0)]);
+	assert!(ticks.advance().is_empty());
+	assert_eq!(ticks.current(), 3);
+}
+
+#[test]
+fn grass_spreads_to_uncovered_dirt() {
+	let mut world = World::empty();
+	let grass = IVec3::new(0, 0, 0);
+	let dirt = IVec3::new(1, 0, 0);
+	world.set_block(grass, Block::new(mat::DIRT_GRASS));
+	world.set_block(dirt, Block::new(mat::DIRT));
+
+	let mut rand = Rand::new(12345);
+	for _ in 0..500 {
+		tick::grass_spread(&mut world, grass, &mut rand);
+	}
+	assert_eq!(world.get_block(dirt).material(), mat::DIRT_GRASS);
+}
+
+#[test]
+fn grass_dies_when_covered() {
+	let mut world = World::empty();
+	let grass = IVec3::new(0, 0, 0);
+	world.set_block(grass, Block::new(mat::DIRT_GRASS));
+	world.set_block(grass + IVec3::Y, Block::new(mat::STONE));
+
+	tick::grass_spread(&mut world, grass, &mut Rand::new(1));
+	assert_eq!(world.get_block(grass).material(), mat::DIRT);
+}
+
+#[test]
+fn snow_melts_next_to_water() {
+	let mut world = World::empty();
+	let snow = IVec3::new(0, 0, 0);
+	world.set_block(snow, Block::new(mat::SNOW));
+	world.set_block(IVec3::new(5, 0, 0), Block::new(mat::WATER));
+
+	tick::snow_melt(&mut world, snow, &mut Rand::new(1));
+	assert_eq!(world.get_block(snow).material(), mat::SNOW);
+
+	world.set_block(snow + IVec3::NEG_Z, Block::new(mat::WATER));
+	tick::snow_melt(&mut world, snow, &mut Rand::new(1));
+	assert_eq!(world.get_block(snow).material(), mat::WATER);
+}
+
+#[test]
+fn sand_stack_falls_and_lands() {
+	let mut world = World::empty();
+	world.set_block(IVec3::new(0, 0, 0), Block::new(mat::STONE));
+	world.set_block(IVec3::new(0, 4, 0), Block::new(mat::SAND));
+	world.set_block(IVec3::new(0, 5, 0), Block::new(mat::GRAVEL));
+
+	for _ in 0..200 {
+		world.tick(0);
+		world.update_falling_blocks(1.0 / 20.0);
+	}
+	assert!(world.falling.is_empty());
+	assert_eq!(world.get_block(IVec3::new(0, 1, 0)).material(), mat::SAND);
+	assert_eq!(world.get_block(IVec3::new(0, 2, 0)).material(), mat::GRAVEL);
+	assert!(world.get_block(IVec3::new(0, 4, 0)).is_empty());
+	assert!(world.get_block(IVec3::new(0, 5, 0)).is_empty());
+
+	// taking out the bottom makes the rest follow
+	world.set_block(IVec3::new(0, 0, 0), Block::None);
+	world.set_block(IVec3::new(0, -3, 0), Block::new(mat::STONE));
+	for _ in 0..200 {
+		world.tick(0);
+		world.update_falling_blocks(1.0 / 20.0);
+	}
+	assert_eq!(world.get_block(IVec3::new(0, -2, 0)).material(), mat::SAND);
+	assert_eq!(world.get_block(IVec3::new(0, -1, 0)).material(), mat::GRAVEL);
+}
+
+#[test]
+fn falling_blocks_are_placed_back_when_saved_or_unloaded() {
+	let mut world = World::empty();
+	world.set_block(IVec3::new(0, 0, 0), Block::new(mat::STONE));
+	world.set_block(IVec3::new(0, 10, 0), Block::new(mat::SAND));
+	world.set_block(IVec3::new(40, 10, 0), Block::new(mat::GRAVEL));
+	for _ in 0..(FALL_DELAY + 3) {
+		world.tick(0);
+		world.update_falling_blocks(1.0 / 20.0);
+	}
+	assert_eq!(world.falling.len(), 2);
+
+	// unloading a chunk only settles the blocks falling inside it
+	let far = ChunkCoord::from_world_pos(IVec3::new(40, 8, 0));
+	world.unload_chunk(far);
+	assert_eq!(world.falling.len(), 1);
+	assert_eq!(world.falling[0].block.material(), mat::SAND);
+
+	// saving puts the rest back where they are, they go on falling from there
+	let pos = world.falling[0].block_pos();
+	world.settle_falling_blocks();
+	assert!(world.falling.is_empty());
+	let loaded = World::from_binary(&world.to_binary()).unwrap();
+	assert_eq!(loaded.get_block(pos).material(), mat::SAND);
+	for _ in 0..200 {
+		world.tick(0);
+		world.update_falling_blocks(1.0 / 20.0);
+	}
+	assert_eq!(world.get_block(IVec3::new(0, 1, 0)).material(), mat::SAND);
+}
+
+#[test]
+fn water_spreads_and_dries_up() {
+	let mut world = World::empty();
+	for x in -10..=10 {
+		for z in -10..=10 {
+			world.set_block(IVec3::new(x, 0, z), Block::new(mat::STONE));
+		}
+	}
+	world.set_block(IVec3::new(4, 0, 0), Block::None); // a hole to pour into
+	world.set_block(IVec3::new(4, -1, 0), Block::new(mat::STONE));
+	let source = IVec3::new(0, 1, 0);
+	world.set_block(source, Block::new(mat::WATER));
+	assert!(fluid::is_source(world.get_block(source).state()));
+
+	for _ in 0..400 {
+		world.tick(0);
+	}
+	assert_eq!(fluid::level(world.get_block(IVec3::new(1, 1, 0)).state()), 1);
+	assert_eq!(fluid::level(world.get_block(IVec3::new(-3, 1, 0)).state()), 3);
+	assert_eq!(fluid::level(world.get_block(IVec3::new(-7, 1, 0)).state()), 7);
+	assert!(world.get_block(IVec3::new(-8, 1, 0)).is_empty());
+	let poured = world.get_block(IVec3::new(4, 0, 0));
+	assert_eq!(poured.material(), mat::WATER);
+	assert!(fluid::is_falling(poured.state()));
+
+	world.set_block(source, Block::None);
+	for _ in 0..400 {
+		world.tick(0);
+	}
+	assert!(world.get_block(IVec3::new(1, 1, 0)).is_empty());
+	assert!(world.get_block(IVec3::new(4, 0, 0)).is_empty());
+}
+
+#[test]
+fn removing_support_tells_the_block_above() {
+	let mut world = World::empty();
+	let stone = IVec3::new(0, 0, 0);
+	world.set_block(stone, Block::new(mat::STONE));
+	world.set_block(stone + IVec3::Y, Block::new(mat::SAND));
+	for _ in 0..10 {
+		world.tick(0);
+	}
+	assert_eq!(world.ticks.pending(), 0);
+
+	world.set_block(stone, Block::None);
+	assert_eq!(world.ticks.pending(), 1); // the sand heard about it and looks around
+}
+
+#[test]
+fn neighbor_updates_wait_once_and_carry_over() {
+	let mut world = World::empty();
+	let source = IVec3::ZERO;
+	for x in 0..MAX_NEIGHBOR_UPDATES as i32 + 10 {
+		world.neighbor_updates.push(IVec3::new(x, 1, 0), source);
+	}
+	world.neighbor_updates.push(IVec3::new(0, 1, 0), source); // already waiting
+	assert_eq!(world.neighbor_updates.pending(), MAX_NEIGHBOR_UPDATES + 10);
+
+	world.flush_neighbor_updates();
+	assert_eq!(world.neighbor_updates.pending(), 10);
+	world.tick(0);
+	assert_eq!(world.neighbor_updates.pending(), 0);
+}
+
+#[test]
+fn adjacent_helpers_share_the_directions() {
+	let chunk = ChunkCoord::new(3, -2, 7);
+	let local = BlockPosition::new(0, 8, 15);
+	for (i, side) in ADJACENT.iter().enumerate() {
+		assert_eq!(chunk.get_adjacent()[i], chunk.offset(side.x, side.y, side.z));
+		assert_eq!(local.get_adjacent()[i], local.offset(side.x as i8, side.y as i8, side.z as i8));
+	}
+	assert_eq!(local.get_adjacent()[0], BlockPosition::new(15, 8, 15)); // wraps within the chunk
+}
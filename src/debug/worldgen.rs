@@ -0,0 +1,483 @@
+#[cfg(test)]
+use crate::block::main::{Block, Chunk};
+#[cfg(test)]
+use crate::block::math::{BlockPosition, ChunkCoord};
+#[cfg(test)]
+use crate::block::props::mat;
+#[cfg(test)]
+use glam::IVec3;
+#[cfg(test)]
+use std::collections::HashSet;
+#[cfg(test)]
+use crate::hs::math::Noise;
+#[cfg(test)]
+use crate::world::biome::{Biome, BiomeBlend, Climate};
+#[cfg(test)]
+use crate::world::generator::{self, FlatGen, GeneratorPreset, GeneratorSettings, IslandsGen, WorldGenerator, FLAT_TOP};
+#[cfg(test)]
+use crate::world::heightmap::{ColumnCache, ColumnMap, CACHE_CAPACITY};
+#[cfg(test)]
+use crate::world::import::{self, HeightImage, HeightmapGen, HeightmapSettings, HEIGHTMAP_BASE};
+#[cfg(test)]
+use crate::world::main::World;
+#[cfg(test)]
+use crate::world::ore::{self, ORES};
+#[cfg(test)]
+use crate::world::structure::{self, FeatureKind, Placing};
+#[cfg(test)]
+use crate::world::terrain::{Column, TerrainGen, BEACH_HEIGHT, GRAVEL_DEPTH, SEA_LEVEL, SNOW_HEIGHT};
+#[cfg(test)]
+use crate::world::workers::ChunkWorkers;
+#[cfg(test)]
+use std::sync::Arc;
+#[cfg(test)]
+use std::time::{Duration, Instant};
+
+#[test]
+fn biomes_blend_by_climate() {
+	for biome in Biome::ALL {
+		let props = biome.props();
+		let blend = BiomeBlend::from_climate(Climate { temperature: props.temperature, humidity: props.humidity });
+		assert_eq!(blend.dominant(), biome);
+		assert!((blend.weights.iter().sum::<f32>() - 1.0).abs() < 1e-5);
+	}
+	// right between the desert and the plains both weigh in
+	let (desert, plains) = (Biome::Desert.props(), Biome::Plains.props());
+	let between = BiomeBlend::from_climate(Climate {
+		temperature: (desert.temperature + plains.temperature) / 2.0,
+		humidity: (desert.humidity + plains.humidity) / 2.0,
+	});
+	assert!(between.weights[Biome::Desert as usize] > 0.3 && between.weights[Biome::Plains as usize] > 0.3);
+}
+
+#[test]
+fn biome_borders_have_no_cliffs() {
+	let terrain = TerrainGen::new(12345);
+	let mut seen = [false; Biome::COUNT];
+	for i in 0..96 {
+		for j in 0..96 {
+			let (x, z) = (i * 40 - 1920, j * 40 - 1920);
+			let column = terrain.column(x, z);
+			seen[column.biome as usize] = true;
+			for (dx, dz) in [(1, 0), (0, 1)] {
+				assert!((column.height - terrain.column(x + dx, z + dz).height).abs() <= 8, "cliff at {x} {z}");
+			}
+		}
+	}
+	assert!(seen.iter().all(|seen| *seen), "every biome shows up somewhere");
+}
+
+#[test]
+fn chunks_are_covered_by_their_biome() {
+	let terrain = TerrainGen::new(777);
+	let mut checked = 0;
+	for i in 0..64 {
+		let (x, z) = (i * 97, i * -61);
+		let column = terrain.column(x, z);
+		if column.height >= terrain.snow_line() || column.height <= column.water + BEACH_HEIGHT || terrain.is_cave(x, column.height, z, 0) {
+			continue;
+		}
+		let coord = ChunkCoord::new(x.div_euclid(Chunk::SIZE_I), column.height.div_euclid(Chunk::SIZE_I), z.div_euclid(Chunk::SIZE_I));
+		let chunk = terrain.generate(coord);
+		let top: usize = BlockPosition::new(x.rem_euclid(16) as u8, column.height.rem_euclid(16) as u8, z.rem_euclid(16) as u8).into();
+		assert_eq!(chunk.get_block(top).material(), column.biome.props().surface);
+		if column.height.rem_euclid(16) < 15 {
+			assert!(chunk.get_block(top + 16).is_empty()); // one up is air (y is the middle index)
+		}
+		checked += 1;
+	}
+	assert!(checked > 32);
+}
+
+#[test]
+fn columns_are_layered_by_depth_and_altitude() {
+	let snow_line = SEA_LEVEL + SNOW_HEIGHT;
+	let plains = Column { height: 20, biome: Biome::Plains, water: SEA_LEVEL };
+	assert_eq!(plains.material_at(21, snow_line), None);
+	assert_eq!(plains.material_at(20, snow_line), Some(mat::DIRT_GRASS));
+	assert_eq!(plains.material_at(18, snow_line), Some(mat::DIRT));
+	assert_eq!(plains.material_at(17, snow_line), Some(mat::DIRT));
+	assert_eq!(plains.material_at(16, snow_line), Some(mat::STONE));
+	assert_eq!(plains.material_at(-100, snow_line), Some(mat::STONE));
+
+	let beach = Column { height: SEA_LEVEL + BEACH_HEIGHT, biome: Biome::Plains, water: SEA_LEVEL };
+	assert_eq!(beach.material_at(beach.height, snow_line), Some(mat::SAND));
+	assert_eq!(beach.material_at(beach.height - 1, snow_line), Some(mat::SAND));
+	assert_eq!(beach.material_at(beach.height - 10, snow_line), Some(mat::STONE));
+
+	let peak = Column { height: snow_line + 5, biome: Biome::Mountains, water: SEA_LEVEL };
+	assert_eq!(peak.material_at(peak.height, snow_line), Some(mat::STONE_SNOW));
+	assert_eq!(peak.material_at(peak.height - 1, snow_line), Some(mat::STONE));
+	let slope = Column { height: snow_line - 1, biome: Biome::Mountains, water: SEA_LEVEL };
+	assert_eq!(slope.material_at(slope.height, snow_line), Some(mat::STONE_GRASS));
+	// the snow line goes with the sea level of the world
+	assert_eq!(TerrainGen::new(1).with_sea_level(40).snow_line(), 40 + SNOW_HEIGHT);
+	assert_eq!(peak.material_at(peak.height, TerrainGen::new(1).with_sea_level(40).snow_line()), Some(mat::STONE_GRASS));
+
+	// water over the ground up to its surface, sand under shallow water and gravel under deep water
+	let shallow = Column { height: SEA_LEVEL - 3, biome: Biome::Snowy, water: SEA_LEVEL };
+	assert_eq!(shallow.material_at(SEA_LEVEL + 1, snow_line), None);
+	assert_eq!(shallow.material_at(SEA_LEVEL, snow_line), Some(mat::WATER));
+	assert_eq!(shallow.material_at(shallow.height + 1, snow_line), Some(mat::WATER));
+	assert_eq!(shallow.material_at(shallow.height, snow_line), Some(mat::SAND));
+	let deep = Column { height: SEA_LEVEL - GRAVEL_DEPTH - 5, biome: Biome::Plains, water: SEA_LEVEL };
+	assert_eq!(deep.material_at(deep.height, snow_line), Some(mat::GRAVEL));
+	assert_eq!(deep.material_at(deep.height - 10, snow_line), Some(mat::STONE));
+}
+
+#[test]
+fn noise_3d_is_seeded_and_smooth() {
+	let (noise, other) = (Noise::new(5), Noise::new(6));
+	let mut differs = false;
+	for i in 0..1000 {
+		let (x, y, z) = (i as f32 * 0.37, i as f32 * -0.21, i as f32 * 0.13);
+		let value = noise.noise_3d(x, y, z);
+		assert!((-1.0..=1.0).contains(&value));
+		assert_eq!(value, Noise::new(5).noise_3d(x, y, z));
+		assert!((value - noise.noise_3d(x + 0.01, y, z)).abs() < 0.1);
+		assert!((-1.0..=1.0).contains(&noise.fractal_noise_3d(x, y, z, 3)));
+		differs |= value != other.noise_3d(x, y, z);
+	}
+	assert!(differs);
+}
+
+#[test]
+fn caves_hollow_out_the_underground() {
+	let terrain = TerrainGen::new(4242);
+	let (mut air, mut total) = (0, 0);
+	for i in 0..8 {
+		let chunk = terrain.generate(ChunkCoord::new(i * 3, -4 - i, i * -2));
+		for idx in 0..Chunk::VOLUME {
+			let block = chunk.get_block(idx);
+			let is_ore = ORES.iter().any(|rule| block.material() == rule.ore || block.material() == rule.alt);
+			assert!(block.is_empty() || block.material() == mat::STONE || is_ore);
+			air += block.is_empty() as u32;
+			total += 1;
+		}
+	}
+	// deep down is mostly stone, but not all of it any more
+	assert!(air > total / 50 && air < total / 3, "{air} of {total} hollow");
+	// the caves line up across chunk borders
+	let (below, above) = (terrain.generate(ChunkCoord::new(0, -5, 0)), terrain.generate(ChunkCoord::new(0, -4, 0)));
+	for x in 0..16 {
+		for z in 0..16 {
+			let (top, bottom): (usize, usize) = (BlockPosition::new(x, 15, z).into(), BlockPosition::new(x, 0, z).into());
+			assert_eq!(below.get_block(top).is_empty(), terrain.is_cave(x as i32, -65, z as i32, 1000));
+			assert_eq!(above.get_block(bottom).is_empty(), terrain.is_cave(x as i32, -64, z as i32, 1000));
+		}
+	}
+}
+
+#[test]
+fn ore_veins_follow_their_rules() {
+	let count = |chunk: &Chunk, material: u16| (0..Chunk::VOLUME).filter(|idx| chunk.get_block(*idx).material() == material).count();
+	for rule in &ORES {
+		let (mut ores, mut alts) = (0, 0);
+		for i in 0..16 {
+			let coord = ChunkCoord::new(i, rule.min_y.div_euclid(Chunk::SIZE_I) + i % 2, -i);
+			let mut chunk = Chunk::new(rule.host);
+			ore::place_ores(&mut chunk, coord, 99);
+			let mut again = Chunk::new(rule.host);
+			ore::place_ores(&mut again, coord, 99);
+			assert!((0..Chunk::VOLUME).all(|idx| chunk.get_block(idx) == again.get_block(idx)), "the seed decides");
+			ores += count(&chunk, rule.ore);
+			alts += count(&chunk, rule.alt);
+		}
+		assert!(ores > 0 && alts > 0, "{} shows up in both looks", rule.ore);
+
+		// out of its height range or in the wrong rock it never does
+		let above = ChunkCoord::new(0, rule.max_y.div_euclid(Chunk::SIZE_I) + 1, 0);
+		let mut chunk = Chunk::new(rule.host);
+		ore::place_ores(&mut chunk, above, 99);
+		assert_eq!(count(&chunk, rule.ore) + count(&chunk, rule.alt), 0);
+		let mut chunk = Chunk::new(mat::DIRT);
+		ore::place_ores(&mut chunk, ChunkCoord::new(0, rule.min_y.div_euclid(Chunk::SIZE_I), 0), 99);
+		assert_eq!(count(&chunk, rule.ore) + count(&chunk, rule.alt), 0);
+	}
+}
+
+#[test]
+fn features_cross_chunk_borders() {
+	let terrain = TerrainGen::new(31337);
+	let mut kinds = HashSet::new();
+	let (mut crossing, mut matching, mut total) = (0, 0, 0);
+	for cell_x in -40..40 {
+		for cell_z in -40..40 {
+			let Some(feature) = structure::feature_in_cell(&terrain, cell_x, cell_z) else { continue; };
+			assert_eq!(Some(feature), structure::feature_in_cell(&terrain, cell_x, cell_z));
+			kinds.insert(feature.kind);
+			let blocks = feature.blocks();
+			let chunks: HashSet<ChunkCoord> = blocks.iter().map(|(pos, _, _)| ChunkCoord::from_world_pos(*pos)).collect();
+			if chunks.len() < 2 || crossing >= 4 {
+				continue;
+			}
+			crossing += 1;
+			// every chunk it reaches into has its solid blocks, each chunk generated on its own
+			let generated: Vec<(ChunkCoord, Chunk)> = chunks.iter().map(|coord| (*coord, terrain.generate(*coord))).collect();
+			for (pos, material, placing) in blocks {
+				if placing != Placing::Replace {
+					continue;
+				}
+				let (_, chunk) = generated.iter().find(|(coord, _)| *coord == ChunkCoord::from_world_pos(pos)).unwrap();
+				let idx: usize = BlockPosition::from(pos.rem_euclid(IVec3::splat(Chunk::SIZE_I))).into();
+				// a feature of a later cell may stand in the way, but never air
+				assert!(!chunk.get_block(idx).is_empty(), "{:?} at {pos}", feature.kind);
+				matching += (chunk.get_block(idx).material() == material) as u32;
+				total += 1;
+			}
+		}
+	}
+	assert!(crossing > 0 && matching * 10 >= total * 9);
+	assert!(kinds.contains(&FeatureKind::Tree) && kinds.contains(&FeatureKind::Boulder));
+}
+
+#[test]
+fn presets_make_their_worlds() {
+	// superflat: the layers bottom up, their top just under the spawn
+	let layers = generator::parse_layers("stone*3, dirt*2, dirt_grass").unwrap();
+	assert_eq!(generator::layers_to_string(&layers), "stone*3,dirt*2,dirt_grass");
+	assert!(generator::parse_layers("stone*3,cheese").is_none());
+	let flat = FlatGen::new(layers.clone());
+	assert_eq!(flat.material_at(FLAT_TOP), Some(mat::DIRT_GRASS));
+	assert_eq!(flat.material_at(FLAT_TOP - 2), Some(mat::DIRT));
+	assert_eq!(flat.material_at(FLAT_TOP - 5), Some(mat::STONE));
+	assert_eq!(flat.material_at(FLAT_TOP - 6), None);
+	assert_eq!(flat.material_at(FLAT_TOP + 1), None);
+	let chunk = GeneratorSettings::new(GeneratorPreset::Superflat, 0).with_layers(layers).build().unwrap().generate(ChunkCoord::new(5, -1, -3));
+	assert_eq!(chunk.get_block(BlockPosition::new(7, 15, 2).into()).material(), mat::DIRT_GRASS);
+
+	// void: only the platform under the spawn
+	let void = GeneratorSettings::new(GeneratorPreset::Void, 0).build().unwrap();
+	assert_eq!(void.generate(ChunkCoord::new(0, -1, 0)).get_block(BlockPosition::new(0, 15, 0).into()).material(), mat::STONE);
+	assert!(void.generate(ChunkCoord::new(0, 0, 0)).get_block(0).is_empty());
+	assert!(void.generate(ChunkCoord::new(1, -1, 0)).get_block(BlockPosition::new(0, 15, 0).into()).is_empty());
+
+	// amplified: the same ground, twice as far from the sea (where no river or ocean digs into it)
+	let (normal, amplified) = (TerrainGen::new(8), TerrainGen::new(8).amplified(generator::AMPLIFIED));
+	let mut compared = 0;
+	for i in 0..128 {
+		let (a, b) = (normal.column(i * 50, i * 30).height, amplified.column(i * 50, i * 30).height);
+		if a > SEA_LEVEL + 40 {
+			assert!((b - a * 2).abs() <= 1);
+			compared += 1;
+		}
+	}
+	assert!(compared > 0);
+
+	// islands: floating land with grass on top, nothing far below
+	let islands = IslandsGen::new(3);
+	let mut solid = 0;
+	for x in 0..64 {
+		for y in 8..88 {
+			solid += islands.is_solid(x * 3, y, x * -2) as u32;
+		}
+	}
+	assert!(solid > 0 && solid < 64 * 80 / 2, "{solid} solid");
+	let islands = GeneratorSettings::new(GeneratorPreset::Islands, 3).build().unwrap();
+	assert!((0..Chunk::VOLUME).all(|idx| islands.generate(ChunkCoord::new(0, -2, 0)).get_block(idx).is_empty()));
+}
+
+#[test]
+fn water_fills_oceans_and_lakes() {
+	let terrain = TerrainGen::new(12345);
+	let block_at = |x: i32, y: i32, z: i32| {
+		let chunk = terrain.generate(ChunkCoord::from_world_pos(IVec3::new(x, y, z)));
+		chunk.get_block(BlockPosition::from(IVec3::new(x, y, z).rem_euclid(IVec3::splat(Chunk::SIZE_I))).into()).material()
+	};
+	let (mut ocean, mut lake) = (None, None);
+	for i in 0..250 {
+		for j in 0..250 {
+			let (x, z) = (i * 8 - 1000, j * 8 - 1000);
+			let column = terrain.column(x, z);
+			if column.water == SEA_LEVEL && column.height < SEA_LEVEL - GRAVEL_DEPTH {
+				ocean = Some((x, z, column));
+			} else if column.water > SEA_LEVEL && column.is_underwater() {
+				lake = Some((x, z, column));
+			}
+		}
+	}
+	// the sea stands over the ocean floor, the floor holds under it
+	let (x, z, column) = ocean.expect("an ocean");
+	assert_eq!(block_at(x, SEA_LEVEL, z), mat::WATER);
+	assert_eq!(block_at(x, column.height + 1, z), mat::WATER);
+	assert_eq!(block_at(x, column.height, z), mat::GRAVEL);
+	assert!(block_at(x, SEA_LEVEL + 1, z) != mat::WATER);
+
+	// a lake is above the sea and its water doesn't spill over its rim
+	let (x, z, column) = lake.expect("a lake");
+	assert_eq!(block_at(x, column.water, z), mat::WATER);
+	assert!(block_at(x, column.height, z) != mat::WATER);
+	for dx in -16..=16 {
+		for dz in -16..=16 {
+			let (here, next) = (terrain.column(x + dx, z + dz), terrain.column(x + dx + 1, z + dz));
+			for (high, low) in [(here, next), (next, here)] {
+				assert!(!high.is_underwater() || low.water >= high.water || low.height >= high.water, "spill at {dx} {dz}");
+			}
+		}
+	}
+
+	// a higher sea floods more of the land
+	let flooded = TerrainGen::new(12345).with_sea_level(SEA_LEVEL + 20);
+	let count = |terrain: &TerrainGen| (0..400).filter(|i| terrain.column(i * 37 - 7000, i * 23 - 4000).is_underwater()).count();
+	assert!(count(&flooded) > count(&terrain));
+	let settings = GeneratorSettings::new(GeneratorPreset::Default, 12345).with_sea_level(SEA_LEVEL + 20);
+	let bytes = settings.to_bytes();
+	assert_eq!(GeneratorSettings::from_bytes(&bytes).unwrap(), settings);
+	assert!(GeneratorSettings::from_bytes(&bytes[..bytes.len() - 5]).is_err()); // no sea level
+}
+
+#[test]
+fn workers_generate_in_the_background() {
+	let generator: Arc<dyn WorldGenerator> = Arc::new(TerrainGen::new(4321));
+	let mut workers = ChunkWorkers::new();
+	let coords: Vec<ChunkCoord> = (0..6).map(|i| ChunkCoord::new(i, -1, -i)).collect();
+	for coord in &coords {
+		workers.request(*coord, &generator);
+	}
+	workers.request(coords[0], &generator); // already on its way
+	// asked for again after being cancelled, only the new request counts
+	workers.cancel(coords[1]);
+	assert!(!workers.is_pending(coords[1]));
+	workers.request(coords[1], &generator);
+	// the far one falls out of range when the center moves, it never comes back
+	let far = ChunkCoord::new(40, 0, 0);
+	workers.request(far, &generator);
+	assert_eq!(workers.pending_count(), coords.len() + 1);
+	workers.retarget(ChunkCoord::new(1, 0, 0), 100);
+	assert!(!workers.is_pending(far));
+
+	let mut received = Vec::new();
+	let deadline = Instant::now() + Duration::from_secs(20);
+	while workers.pending_count() > 0 && Instant::now() < deadline {
+		let finished = workers.finished(2);
+		assert!(finished.len() <= 2);
+		received.extend(finished);
+		std::thread::sleep(Duration::from_millis(1));
+	}
+	assert_eq!(received.len(), coords.len());
+	for (coord, chunk) in &received {
+		assert!(coords.contains(coord));
+		assert!(*chunk == generator.generate(*coord), "{coord:?} is the same as generated right away");
+	}
+	assert!(workers.finished(2).is_empty());
+}
+
+#[test]
+fn blocks_set_while_generating_are_kept() {
+	let mut world = World::empty();
+	world.generator = Arc::new(TerrainGen::new(4321));
+	let coord = ChunkCoord::new(0, -2, 0);
+	let (x, y, z) = coord.unpack_to_worldpos();
+	let pos = IVec3::new(x + 3, y + 5, z + 7);
+	let generator = Arc::clone(&world.generator);
+	world.workers.request(coord, &generator);
+	world.set_block(pos, Block::new(mat::STONE_SLAB));
+	assert!(world.workers.is_pending(coord));
+
+	let deadline = Instant::now() + Duration::from_secs(20);
+	while world.workers.is_pending(coord) && Instant::now() < deadline {
+		for (coord, chunk) in world.workers.finished(1) {
+			world.insert_finished_chunk(coord, chunk);
+		}
+		std::thread::sleep(Duration::from_millis(1));
+	}
+	assert!(!world.workers.is_pending(coord));
+	// the generated ground came in around the block
+	assert_eq!(world.get_block(pos).material(), mat::STONE_SLAB);
+	let generated = generator.generate(coord);
+	let chunk = world.get_chunk(coord).unwrap();
+	let edited: usize = BlockPosition::from(pos).into();
+	assert!((0..Chunk::VOLUME).filter(|index| *index != edited).all(|index| chunk.get_block(index) == generated.get_block(index)));
+}
+
+#[test]
+fn chunk_columns_share_their_heightmap() {
+	let terrain = TerrainGen::new(2024);
+	let map = terrain.column_map(3, -7);
+	for (x, z) in [(0, 0), (15, 0), (7, 9), (15, 15)] {
+		assert_eq!(map.get(x, z), terrain.column(3 * 16 + x as i32, -7 * 16 + z as i32));
+	}
+	assert_eq!(terrain.cached_column(3 * 16 + 5, -7 * 16 + 2), map.get(5, 2));
+	// every chunk stacked in the column and the generator behind the trait get the same one
+	assert!(Arc::ptr_eq(&map, &terrain.column_map(3, -7)));
+	let generator: Arc<dyn WorldGenerator> = Arc::new(terrain.clone());
+	assert!(Arc::ptr_eq(&map, &generator.column_map(3, -7).unwrap()));
+	assert!(map.highest() >= map.get(7, 9).height);
+	assert!(FlatGen::new(Vec::new()).column_map(0, 0).is_none());
+	// another sea level makes other columns, it doesn't take them from the cache
+	assert!(!Arc::ptr_eq(&map, &terrain.clone().with_sea_level(SEA_LEVEL + 30).column_map(3, -7)));
+
+	// a full cache drops the maps unused the longest
+	let cache = ColumnCache::new();
+	let flat = |x: i32, z: i32| ColumnMap::new(x, z, |_, _| Column { height: 0, biome: Biome::Plains, water: SEA_LEVEL });
+	let first = cache.get_or_make(0, 0, || flat(0, 0));
+	for i in 1..CACHE_CAPACITY as i32 * 2 {
+		cache.get_or_make(i, 0, || flat(i, 0));
+		cache.get_or_make(0, 0, || panic!("still cached"));
+	}
+	assert!(cache.len() <= CACHE_CAPACITY);
+	assert!(Arc::ptr_eq(&first, &cache.get_or_make(0, 0, || flat(0, 0))));
+}
+
+#[test]
+fn heightmaps_turn_into_ground() {
+	// a white pixel between black ones, blended in between
+	let image = HeightImage::new(3, 1, vec![0.0, 1.0, 0.0]).unwrap();
+	assert!(HeightImage::new(3, 2, vec![0.0; 3]).is_none());
+	assert_eq!(image.sample(1.0, 0.0), 1.0);
+	assert_eq!(image.sample(0.5, 0.0), 0.5);
+	assert_eq!(image.sample(-3.0, 5.0), 0.0);
+
+	// the scales and the material after the path, each one can be left out
+	let settings = HeightmapSettings::parse("maps/hill.png, 2, 0.5, dirt").unwrap();
+	assert_eq!((settings.path.as_str(), settings.horizontal_scale, settings.vertical_scale, settings.material), ("maps/hill.png", 2.0, 0.5, mat::DIRT));
+	assert_eq!(HeightmapSettings::parse("hill.png"), Some(HeightmapSettings::new("hill.png")));
+	assert!(HeightmapSettings::parse("hill.png, -1").is_none());
+	assert!(HeightmapSettings::parse("hill.png, 1, 1, cheese").is_none());
+	assert!(HeightmapSettings::parse("").is_none());
+	assert_eq!(settings.height_at(&image, 3.0, 1.0), 128); // the center of the white pixel, 2 blocks wide
+	assert_eq!(settings.size(&image), (6, 2));
+
+	// as a preset: centered on the spawn, flat at the black level around it
+	let generator = HeightmapGen::new(image.clone(), settings.clone());
+	assert!(generator.height_at(0, 0) > HEIGHTMAP_BASE + 64);
+	assert_eq!(generator.height_at(100, 0), HEIGHTMAP_BASE);
+	let chunk = generator.generate(ChunkCoord::new(0, 0, 0));
+	assert_eq!(chunk.get_block(BlockPosition::new(0, 15, 0).into()).material(), mat::DIRT);
+	assert!(chunk.get_block(BlockPosition::new(10, 0, 0).into()).is_empty());
+	assert_eq!(generator.generate(ChunkCoord::new(9, -1, 9)).get_block(BlockPosition::new(3, 15, 3).into()).material(), mat::DIRT);
+	assert!(generator.generate(ChunkCoord::new(0, 9, 0)).get_block(0).is_empty());
+	let saved = GeneratorSettings::new(GeneratorPreset::Heightmap, 0).with_heightmap(settings);
+	assert_eq!(GeneratorSettings::from_bytes(&saved.to_bytes()).unwrap(), saved);
+	let plain = GeneratorSettings::new(GeneratorPreset::Default, 0).to_bytes();
+	assert!(GeneratorSettings::from_bytes(&plain[..plain.len() - 1]).is_err()); // no heightmap flag
+	// the image is gone, the world can't be made without it (it doesn't turn into another one)
+	assert!(saved.build().is_err());
+	assert!(GeneratorSettings::new(GeneratorPreset::Heightmap, 0).build().is_err());
+
+	// read from a 16 bit PNG
+	let path = std::env::temp_dir().join("heightmaps_turn_into_ground.png");
+	image::ImageBuffer::<image::Luma<u16>, _>::from_raw(3, 1, vec![0u16, u16::MAX, 0]).unwrap().save(&path).unwrap();
+	assert_eq!(HeightImage::load(&path).unwrap(), image);
+
+	// stamped from the file the way the game menu does it
+	assert_eq!(import::parse_origin(" 4, -2,7 "), Some(IVec3::new(4, -2, 7)));
+	assert!(import::parse_origin("4, 2").is_none());
+	assert!(import::parse_origin("4, 2, 7, 1").is_none());
+	let mut world = World::empty();
+	let settings = HeightmapSettings::parse(&format!("{}, 1, 0.04, dirt", path.display())).unwrap();
+	import::stamp_file(&mut world, &settings, IVec3::new(30, 20, 30)).unwrap();
+	assert_eq!(world.get_block(IVec3::new(31, 20 + settings.max_height(), 30)).material(), mat::DIRT);
+	assert!(import::stamp_file(&mut world, &HeightmapSettings::new("missing.png"), IVec3::ZERO).is_err());
+	let _ = std::fs::remove_file(&path);
+
+	// stamped into a world: the old ground goes, the hill stands in its place
+	let mut world = World::empty();
+	world.set_block(IVec3::new(-1, 5, 0), Block::new(mat::STONE));
+	let settings = HeightmapSettings::parse("hill.png, 1, 0.04, dirt").unwrap();
+	import::stamp(&mut world, &image, &settings, IVec3::new(-1, 0, 0));
+	assert!(world.get_block(IVec3::new(-1, 5, 0)).is_empty());
+	assert_eq!(world.get_block(IVec3::new(0, settings.max_height(), 0)).material(), mat::DIRT);
+	assert_eq!(world.get_block(IVec3::new(-1, 0, 0)).material(), mat::DIRT);
+	assert!(world.get_block(IVec3::new(-1, 1, 0)).is_empty());
+}
@@ -1,6 +1,6 @@
 
 use crate::ext::ptr;
-use crate::block::extra;
+use crate::block::{extra, entity};
 use crate::ui::{manager, inventory};
 use std::iter::Iterator;
 use winit::{
@@ -115,10 +115,8 @@ impl<'a> crate::State<'a> {
 							}
 						},
 						Key::KeyR => {
-							if is_pressed {
-								extra::remove_targeted_block();
-								return true
-							}
+							extra::set_mining(is_pressed);
+							return true
 						},
 						Key::KeyI => {
 							if is_pressed {
@@ -126,6 +124,7 @@ impl<'a> crate::State<'a> {
 								match state.ui_manager.state.clone() {
 									manager::UIState::Inventory(_) => {
 										state.ui_manager.state = manager::UIState::InGame;
+										*ptr::get_gamestate().open_storage() = None;
 										self.toggle_mouse_capture();
 									},
 									manager::UIState::InGame => {
@@ -249,6 +248,15 @@ impl<'a> crate::State<'a> {
 					}
 					(MouseButton::Right, ElementState::Pressed) => {
 						self.input_system.mouse_button_state.right = true;
+						if self.is_world_running && ptr::get_gamestate().is_running() && self.ui_manager.state == manager::UIState::InGame {
+							if extra::open_looked_storage() {
+								self.ui_manager.state = manager::UIState::Inventory(inventory::InventoryUIState::str().size(entity::CHEST_SIZE).b());
+								if self.input_system.mouse_captured() { self.toggle_mouse_capture(); }
+								self.ui_manager.setup_ui();
+							} else {
+								extra::use_looked_block();
+							}
+						}
 						true
 					}
 					(MouseButton::Right, ElementState::Released) => {
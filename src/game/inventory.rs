@@ -1,5 +1,5 @@
 use crate::ui::inventory::{self, AreaType};
-use crate::game::items::ItemStack;
+use crate::game::items::{ItemStack, MAX_STACK};
 
 // Default inventory sizes - can be upgraded during gameplay
 pub const DEFAULT_ARMOR_SLOTS: u8 = 4;
@@ -8,7 +8,7 @@ pub const DEFAULT_INV_ROWS: u8 = 3;
 pub const DEFAULT_INV_COLS: u8 = 7;
 
 /// A unified container for items that can handle both 1D and 2D layouts
-#[derive(Clone, PartialEq)]
+#[derive(Clone, PartialEq, Debug)]
 pub struct ItemContainer {
 	items: Vec<Option<ItemStack>>,
 	rows: u8,
@@ -129,14 +129,38 @@ impl ItemContainer {
 		self.find_empty_slot().is_none()
 	}
 
-	/// Add an item to the first available slot
-	pub fn add_item(&mut self, item: ItemStack) -> bool {
-		if let Some(index) = self.find_empty_slot() {
-			self.set(index, Some(item));
-			true
-		} else {
-			false
+	/// How many of the item still fit (topping up matching stacks and filling empty slots)
+	pub fn room_for(&self, item: &ItemStack) -> usize {
+		self.items.iter().map(|slot| match slot {
+			None => MAX_STACK as usize,
+			Some(stack) if stack.stacks_with(item) => MAX_STACK.saturating_sub(stack.quantity) as usize,
+			Some(_) => 0,
+		}).sum()
+	}
+
+	/// Add an item, topping up matching stacks first and then the first empty slots
+	/// Either the whole stack fits and gets added or nothing changes
+	pub fn add_item(&mut self, mut item: ItemStack) -> bool {
+		if self.room_for(&item) < item.quantity as usize {
+			return false;
+		}
+		for stack in self.items.iter_mut().flatten() {
+			if item.quantity == 0 {
+				break;
+			}
+			if stack.stacks_with(&item) {
+				let moved = item.quantity.min(MAX_STACK.saturating_sub(stack.quantity));
+				stack.quantity += moved;
+				item.quantity -= moved;
+			}
+		}
+		while item.quantity > 0 {
+			let Some(index) = self.find_empty_slot() else { break; };
+			let moved = item.quantity.min(MAX_STACK);
+			self.set(index, Some(ItemStack { quantity: moved, ..item.clone() }));
+			item.quantity -= moved;
 		}
+		true
 	}
 
 	/// Remove an item at the specified linear index
@@ -1,5 +1,6 @@
 
 use crate::get_nth_file;
+use crate::block::props;
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct ItemStack {
@@ -8,6 +9,9 @@ pub struct ItemStack {
 	pub data: Option<Box<ItemData>>,  // Boxed to reduce size when None
 }
 
+/// Most items a single stack holds
+pub const MAX_STACK: u8 = 64;
+
 const EXTRA_BLOCK_DATA_OFFSET:usize = 1usize; // currently only a single one : air 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Item {
@@ -18,7 +22,7 @@ impl Item {
 	pub fn to_icon(&self) -> String {
 		let file_path = match self {
 			Self::Block(id) => {
-				get_nth_file!(*id as usize - EXTRA_BLOCK_DATA_OFFSET, "blocks")
+				get_nth_file!(props::texture_of(*id) as usize - EXTRA_BLOCK_DATA_OFFSET, "blocks")
 			}
 			Self::Item(id) => {
 				get_nth_file!(*id as usize - EXTRA_BLOCK_DATA_OFFSET, "items") // currently crashes ... allwaysS
@@ -34,13 +38,19 @@ impl Item {
 pub struct ToolFlags(pub u8);
 #[allow(dead_code)]
 impl ToolFlags {
-	const PICKAXE:u8 = 1 << 0; // stone related thing
-	const AXE:u8 = 1 << 1; // wood related thing
-	const SHOVEL:u8 = 1 << 2; // dirt related thing
-	const HOE:u8 = 1 << 3; // leaf related thing
-	const SWORD:u8 = 1 << 4; // web related thing
-	const SCISSORS:u8 = 1 << 5; // wool related thing
+	pub const NONE:u8 = 0;
+	pub const PICKAXE:u8 = 1 << 0; // stone related thing
+	pub const AXE:u8 = 1 << 1; // wood related thing
+	pub const SHOVEL:u8 = 1 << 2; // dirt related thing
+	pub const HOE:u8 = 1 << 3; // leaf related thing
+	pub const SWORD:u8 = 1 << 4; // web related thing
+	pub const SCISSORS:u8 = 1 << 5; // wool related thing
 	// Add more as needed
+
+	/// Is any of the given tool kinds part of this tool
+	#[inline] pub const fn any(&self, flags: u8) -> bool {
+		self.0 & flags != 0
+	}
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -90,4 +100,24 @@ impl ItemStack {
 	#[inline] pub const fn is_item(&self) -> bool {
 		matches!(self.item, Item::Item(_))
 	}
+
+	/// Can the other stack be merged into this one (same item, neither has extra data)
+	#[inline] pub fn stacks_with(&self, other: &ItemStack) -> bool {
+		self.item == other.item && self.data.is_none() && other.data.is_none()
+	}
+
+	/// The tool kinds of the item (None for anything that is not a tool)
+	#[inline] pub fn tool(&self) -> Option<ToolFlags> {
+		self.data.as_ref()?.tool
+	}
+
+	/// Uses up one durability, returns true if the item broke
+	/// Items without durability never break
+	pub fn damage(&mut self) -> bool {
+		let Some(durability) = self.data.as_mut().and_then(|data| data.durability.as_mut()) else {
+			return false;
+		};
+		*durability = durability.saturating_sub(1);
+		*durability == 0
+	}
 }
\ No newline at end of file
@@ -0,0 +1,95 @@
+use crate::block::math::{BlockPosition, ChunkCoord};
+use crate::render::meshing::{ChunkMeshBuilder, GeometryBuffer};
+use crate::render::world::make_chunk_bind_group;
+use glam::IVec3;
+
+/// Number of crack overlay stages shown while mining
+pub const CRACK_STAGES: u8 = 10;
+
+/// Hold-to-mine progress of the block the player is looking at
+#[derive(Debug, Default)]
+pub struct Mining {
+	pub held: bool, // the mine key is down
+	target: Option<IVec3>,
+	progress: f32, // 0-1, the block breaks at 1
+	mesh: Option<GeometryBuffer>, // crack overlay
+	bind_group: Option<wgpu::BindGroup>,
+	drawn: Option<(IVec3, u8)>, // target and stage the overlay was made for
+}
+
+impl Mining {
+	#[inline] pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// The block being mined
+	#[inline] pub const fn target(&self) -> Option<IVec3> {
+		self.target
+	}
+
+	/// Mining progress of the target (0-1)
+	#[inline] pub const fn progress(&self) -> f32 {
+		self.progress
+	}
+
+	/// Crack stage to show (1 to `CRACK_STAGES`), None while there is nothing to show
+	#[inline] pub fn stage(&self) -> Option<u8> {
+		if self.target.is_none() || self.progress <= 0.0 {
+			return None;
+		}
+		Some(((self.progress * CRACK_STAGES as f32) as u8 + 1).min(CRACK_STAGES))
+	}
+
+	/// Stops mining and forgets the progress
+	#[inline] pub fn reset(&mut self) {
+		self.target = None;
+		self.progress = 0.0;
+	}
+
+	/// Advances the mining of the looked at block, returns the block position when it breaks
+	/// Looking at another block (or letting go of the key) starts over
+	pub fn step(&mut self, looked: Option<IVec3>, break_time: Option<f32>, delta_seconds: f32) -> Option<IVec3> {
+		if !self.held {
+			self.reset();
+			return None;
+		}
+		if looked != self.target {
+			self.target = looked;
+			self.progress = 0.0;
+		}
+		let (target, time) = (self.target?, break_time?);
+		self.progress += if time <= 0.0 { 1.0 } else { delta_seconds / time };
+		if self.progress < 1.0 {
+			return None;
+		}
+		self.reset();
+		Some(target)
+	}
+
+	/// Rebuilds the crack overlay if the target or the stage changed
+	pub fn make_mesh(&mut self, device: &wgpu::Device) {
+		let shown = self.target.zip(self.stage());
+		if shown == self.drawn {
+			return;
+		}
+		self.drawn = shown;
+		let Some((pos, stage)) = shown else {
+			self.mesh = None;
+			self.bind_group = None;
+			return;
+		};
+		let mut builder = ChunkMeshBuilder::new();
+		builder.add_crack_cube(BlockPosition::from(pos).into(), stage);
+		self.mesh = Some(builder.build(device).0);
+		self.bind_group = Some(make_chunk_bind_group(ChunkCoord::from_world_pos(pos)));
+	}
+
+	/// Draws the crack overlay, has to run with the crack pipeline after the world is drawn
+	pub fn render_crack<'a>(&'a self, render_pass: &mut wgpu::RenderPass<'a>) {
+		if let (Some(mesh), Some(bind_group)) = (&self.mesh, &self.bind_group) {
+			render_pass.set_bind_group(2, bind_group, &[]);
+			render_pass.set_vertex_buffer(1, mesh.instance_buffer.slice(..));
+			render_pass.draw(0..6, 0..mesh.num_instances);
+		}
+	}
+}
@@ -171,8 +171,11 @@ impl Player {
 			target_velocity, 
 			acceleration * dt
 		);
+		// a knockback (explosions ...) carries the player on top of the walking and fades like stopping does
+		let knockback = self.body.velocity;
+		self.body.velocity = knockback.lerp(Vec3::ZERO, 10. * dt);
 
-		self.controller.velocity * dt
+		(self.controller.velocity + knockback) * dt
 	}
 
 	/// Gets the player's current position
@@ -186,6 +189,11 @@ impl Player {
 	/// Appends position to both player and camera
 	#[inline] pub fn append_position(&mut self, offset: Vec3) {
 		self.pos += offset;
+		self.body.aabb = self.body.aabb.translate(offset);
+	}
+	/// The body things like explosions push around
+	#[inline] pub const fn body_mut(&mut self) -> &mut aabb::PhysicsBody {
+		&mut self.body
 	}
 	#[inline] pub const fn controller(&mut self) -> &mut PlayerController {
 		&mut self.controller
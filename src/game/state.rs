@@ -6,8 +6,11 @@ use std::path::PathBuf;
 use crate::game::player;
 use crate::ext::ptr;
 use crate::world;
+use crate::world::generator::GeneratorSettings;
 use std::sync::atomic::Ordering;
-use glam::Vec3;
+use crate::game::inventory::ItemContainer;
+use crate::game::mining::Mining;
+use glam::{IVec3, Vec3};
 
 #[allow(dead_code)]
 pub struct GameState {
@@ -16,6 +19,9 @@ pub struct GameState {
 	world: world::main::World, // lol main data storage :)
 	save_path: std::path::PathBuf,
 	is_running: bool,
+	open_storage: Option<IVec3>, // world position of the block whose storage is shown in the UI
+	mining: Mining,
+	seed: u32,
 }
 
 pub fn make_world(save_path: PathBuf) {
@@ -51,8 +57,9 @@ pub fn make_world(save_path: PathBuf) {
 
 #[allow(dead_code)]
 impl GameState {
+	/// Fails when the generator of the world can't be made (its heightmap is gone ...)
 	#[inline]
-	pub fn new(worldname: &str) -> Self {
+	pub fn new(worldname: &str) -> std::io::Result<Self> {
 		let state = ptr::get_state();
 		let offset = Vec3::new(0., 1.7, 0.); let pos = Vec3::new(0.5,0.5,0.5);
 		let mut player = player::Player::new(CameraConfig::new(offset), pos, state.device(), *state.size(), &state.render_context.layouts[1]);
@@ -69,14 +76,25 @@ impl GameState {
 			Ok(_) => (), // Everything is fine, do nothing
 			Err(e) => println!("Error updating world data: {}", e),
 		}
+		let settings = world::manager::load_world_data(&save_path)
+			.map(|data| data.generator)
+			.unwrap_or_else(|e| {
+				println!("Error loading world data: {}", e);
+				GeneratorSettings::default()
+			});
+		let mut world = world::main::World::empty();
+		world.generator = settings.build()?;
 		
-		Self {
+		Ok(Self {
 			worldname: worldname.to_string(),
 			player,
-			world: world::main::World::empty(),
+			world,
 			save_path,
 			is_running: false,
-		}
+			open_storage: None,
+			mining: Mining::new(),
+			seed: settings.seed,
+		})
 	}
 	#[inline] pub const fn world_mut(&mut self) -> &mut world::main::World {
 		&mut self.world
@@ -102,20 +120,41 @@ impl GameState {
 	#[inline] pub const fn running(&mut self) -> &mut bool {
 		&mut self.is_running
 	}
+	#[inline] pub const fn open_storage(&mut self) -> &mut Option<IVec3> {
+		&mut self.open_storage
+	}
+	#[inline] pub const fn mining(&self) -> &Mining {
+		&self.mining
+	}
+	#[inline] pub const fn mining_mut(&mut self) -> &mut Mining {
+		&mut self.mining
+	}
+	/// Pushes the player away from the explosions the world had since the last update
+	pub fn push_player_from_explosions(&mut self) {
+		for explosion in self.world.take_explosions() {
+			explosion.push(self.player.body_mut());
+		}
+	}
+	/// The container of the block entity currently opened in the storage UI
+	#[inline] pub fn storage_container(&self) -> Option<&ItemContainer> {
+		self.world.get_block_entity(self.open_storage?)?.container()
+	}
 
 
 	#[inline] pub const fn seed(&self) -> &u32 {
-		&0u32
+		&self.seed
 	}
 }
 
+/// Opens the world, nothing changes if it can't be
 #[inline]
-pub fn start_world(worldname: &str) {
-	let game_state = GameState::new(worldname);
+pub fn start_world(worldname: &str) -> std::io::Result<()> {
+	let game_state = GameState::new(worldname)?;
 	ptr::GAMESTATE_PTR.store(Box::into_raw(Box::new(game_state)), Ordering::Release);
 	
 	// This will only execute when not in test configuration
 	if !cfg!(test) {
 		ptr::get_state().is_world_running = true;
 	}
+	Ok(())
 }
\ No newline at end of file
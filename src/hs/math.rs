@@ -118,6 +118,7 @@ pub fn random_bool() -> bool {
 
 // Inspiration from https://github.com/Auburn/FastNoiseLite/blob/master/Rust/src/lib.rs
 
+#[derive(Clone, Copy, Debug)]
 pub struct Noise {
 	seed: u32,
 }
@@ -338,6 +339,83 @@ impl Noise {
 		Self::smooth_interpolate((base * 0.7 + ridged * 0.2 + warped * 0.3) * 0.6) // Reduced overall multiplier
 	}
 
+	#[inline]
+	fn grad_3d(seed: i32, x_primed: i32, y_primed: i32, z_primed: i32, xd: f32, yd: f32, zd: f32) -> f32 {
+		let hash = Self::hash(seed ^ z_primed, x_primed, y_primed);
+		let hash = hash ^ (hash >> 16);
+		let idx = ((hash & 0xF) * 3) as usize; // 16 gradients, the 12 cube edges and 4 of them again
+		xd * GRADIENTS_3D[idx] + yd * GRADIENTS_3D[idx + 1] + zd * GRADIENTS_3D[idx + 2]
+	}
+
+	/// Simplex noise in 3D, about -1 to 1
+	pub fn noise_3d(&self, x: f32, y: f32, z: f32) -> f32 {
+		const F3: f32 = 1.0 / 3.0;
+		const G3: f32 = 1.0 / 6.0;
+		const PRIME_X: i32 = 0x5205402B;
+		const PRIME_Y: i32 = 0x5AC0E4F1;
+		const PRIME_Z: i32 = 0x1F1F1F1F;
+
+		// Skew the input space to find the simplex cell
+		let s = (x + y + z) * F3;
+		let i = Self::floor(x + s);
+		let j = Self::floor(y + s);
+		let k = Self::floor(z + s);
+		let t = (i + j + k) as f32 * G3;
+		let x0 = x - (i as f32 - t);
+		let y0 = y - (j as f32 - t);
+		let z0 = z - (k as f32 - t);
+
+		// Which of the six tetrahedra of the cube the point is in
+		let (i1, j1, k1, i2, j2, k2) = if x0 >= y0 {
+			if y0 >= z0 { (1, 0, 0, 1, 1, 0) }
+			else if x0 >= z0 { (1, 0, 0, 1, 0, 1) }
+			else { (0, 0, 1, 1, 0, 1) }
+		} else if y0 < z0 { (0, 0, 1, 0, 1, 1) }
+		else if x0 < z0 { (0, 1, 0, 0, 1, 1) }
+		else { (0, 1, 0, 1, 1, 0) };
+
+		let i = i.wrapping_mul(PRIME_X);
+		let j = j.wrapping_mul(PRIME_Y);
+		let k = k.wrapping_mul(PRIME_Z);
+		let corner = |di: i32, dj: i32, dk: i32, offset: f32| {
+			let (xd, yd, zd) = (x0 - di as f32 + offset, y0 - dj as f32 + offset, z0 - dk as f32 + offset);
+			let a = 0.6 - xd * xd - yd * yd - zd * zd;
+			if a <= 0.0 {
+				return 0.0;
+			}
+			let a2 = a * a;
+			a2 * a2 * Self::grad_3d(
+				self.seed as i32,
+				i.wrapping_add(di * PRIME_X),
+				j.wrapping_add(dj * PRIME_Y),
+				k.wrapping_add(dk * PRIME_Z),
+				xd, yd, zd,
+			)
+		};
+		let n = corner(0, 0, 0, 0.0)
+			+ corner(i1, j1, k1, G3)
+			+ corner(i2, j2, k2, 2.0 * G3)
+			+ corner(1, 1, 1, 3.0 * G3);
+		n * 32.0
+	}
+
+	/// Octaves of 3D noise, every one twice the frequency and half the amplitude of the last, about -1 to 1
+	pub fn fractal_noise_3d(&self, x: f32, y: f32, z: f32, octaves: u32) -> f32 {
+		let mut noise_sum = 0.;
+		let mut amplitude = 1.;
+		let mut frequency = 1.;
+		let mut max_value = 0.;
+		for octave in 0..octaves {
+			// shifted a bit every octave so they don't all cross zero at the origin
+			let shift = octave as f32 * 17.3;
+			noise_sum += self.noise_3d(x * frequency + shift, y * frequency + shift, z * frequency + shift) * amplitude;
+			max_value += amplitude;
+			amplitude *= 0.5;
+			frequency *= 2.0;
+		}
+		if max_value > 0.0 { noise_sum / max_value } else { 0.0 }
+	}
+
 	// Enhanced smooth interpolation with gentler curves
 	pub const fn smooth_interpolate(noise: f32) -> f32 {
 	    // Hyperbolic tangent approximation that's smooth everywhere
@@ -431,4 +509,12 @@ const GRADIENTS_2D: [f32; 128] = [
 	-0.195090322016128, 0.98078528040323,
 	-0.555570233019602, 0.831469612302545,
 	-0.0980171403295606, 0.995184726672197,
-];
\ No newline at end of file
+];
+
+/// The 12 edges of a cube, the first 4 again to make 16
+const GRADIENTS_3D: [f32; 48] = [
+	1.0, 1.0, 0.0,  -1.0, 1.0, 0.0,  1.0, -1.0, 0.0,  -1.0, -1.0, 0.0,
+	1.0, 0.0, 1.0,  -1.0, 0.0, 1.0,  1.0, 0.0, -1.0,  -1.0, 0.0, -1.0,
+	0.0, 1.0, 1.0,  0.0, -1.0, 1.0,  0.0, 1.0, -1.0,  0.0, -1.0, -1.0,
+	1.0, 1.0, 0.0,  -1.0, 1.0, 0.0,  0.0, -1.0, 1.0,  0.0, -1.0, -1.0,
+];
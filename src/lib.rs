@@ -26,6 +26,16 @@ pub mod debug { // debug, test related
 	pub mod binary;
 	pub mod metadata;
 	pub mod physics;
+	pub mod tick;
+	pub mod light;
+	pub mod mining;
+	pub mod loot;
+	pub mod signal;
+	pub mod interact;
+	pub mod shape;
+	pub mod farming;
+	pub mod explosion;
+	pub mod worldgen;
 }
 pub mod ext { // extra things that did not fit anywhere else
 	pub mod audio; // audio manager, in extra thread
@@ -48,16 +58,40 @@ pub mod game { // game related, instance related
 	pub mod inventory; // basic inventory impl.
 	pub mod player; // main camera and player impl.
 	pub mod state; // game-state with seed and stuff
+	pub mod mining; // hold-to-mine progress and the crack overlay
 }
 pub mod world { // world related, tiny bit rendering and game related
 	pub mod main;
 	pub mod manager;
 	pub mod handler;
+	pub mod tick; // scheduled and random block ticks
+	pub mod falling; // sand, gravel ... falling as entities
+	pub mod dropped; // item stacks lying in the world
+	pub mod neighbor; // blocks hearing about the blocks next to them changing
+	pub mod explosion; // blasts breaking blocks and throwing things around (TNT)
+	pub mod biome; // climate picked biomes, shaping and covering the ground
+	pub mod generator; // the world generator trait and its presets
+	pub mod terrain; // the generated ground
+	pub mod heightmap; // the generated ground of whole chunk columns, cached
+	pub mod water; // oceans, rivers and lakes of the generated ground
+	pub mod ore; // ore veins in the generated rock
+	pub mod structure; // trees, boulders and ruins standing on the generated ground
+	pub mod import; // heightmap images turned into ground
+	pub mod workers; // background threads generating the chunks coming into range
 }
 pub mod block { // block related, chunk related
 	pub mod main;
 	pub mod math;
 	pub mod extra;
+	pub mod props; // per-material block data (transparency ...)
+	pub mod entity; // per-block data stored by the chunk (chests ...)
+	pub mod fluid; // water flow, level kept in the block state
+	pub mod light; // block and sky light flood fill
+	pub mod loot; // what broken blocks drop
+	pub mod signal; // levers, wires, lamps ... the signal circuits
+	pub mod shape; // boxes of the blocks that are not full cubes (doors ...)
+	pub mod interact; // what blocks do when the player uses them
+	pub mod farming; // farmland and growing crops
 }
 pub mod ui { // ui related
 	pub mod element;
@@ -71,6 +105,7 @@ pub mod ui { // ui related
 pub mod physic {
 	pub mod aabb;
 	pub mod body;
+	pub mod ray; // walking the blocks along a ray
 }
 
 
@@ -287,6 +322,17 @@ impl<'a> State<'a> {
 				let player = &mut ext::ptr::get_gamestate().player_mut();
 				player.append_position(movement_delta);
 			}
+
+			let game_state = ext::ptr::get_gamestate();
+			if game_state.is_running() {
+				let seed = *game_state.seed();
+				game_state.world_mut().update_ticks(delta_seconds, seed);
+				game_state.push_player_from_explosions();
+				game_state.world_mut().update_falling_blocks(delta_seconds);
+				game_state.world_mut().update_dropped_items(delta_seconds);
+				block::extra::update_mining(delta_seconds);
+				block::extra::pick_up_dropped_items();
+			}
 		}
 		if self.ui_manager.visibility {
 			self.ui_manager.update_anim(delta_seconds);
@@ -0,0 +1,58 @@
+use glam::{IVec3, Vec3};
+
+/// Walks the blocks a ray passes through, in order (DDA)
+/// Every step gives the block position, the normal of the face the ray entered it through (zero for the first block)
+/// and the distance along the ray where it entered
+#[derive(Clone, Copy, Debug)]
+pub struct VoxelRay {
+	pos: IVec3,
+	step: IVec3,
+	t_max: Vec3, // distance to the next block boundary on each axis
+	t_delta: Vec3, // distance between block boundaries on each axis
+	normal: IVec3,
+	traveled: f32,
+}
+
+impl VoxelRay {
+	/// Starts at the block the origin is in, the direction should be normalized for the distances to be in blocks
+	pub fn new(origin: Vec3, dir: Vec3) -> Self {
+		let step = IVec3::new(dir.x.signum() as i32, dir.y.signum() as i32, dir.z.signum() as i32);
+		let pos = origin.floor().as_ivec3();
+		let inverse = Vec3::ONE / dir.abs().max(Vec3::splat(f32::MIN_POSITIVE));
+		let boundary = |axis: usize| if step[axis] > 0 {
+			(pos[axis] + 1) as f32 - origin[axis]
+		} else {
+			origin[axis] - pos[axis] as f32
+		};
+		Self {
+			pos,
+			step,
+			t_max: Vec3::new(boundary(0), boundary(1), boundary(2)) * inverse,
+			t_delta: inverse,
+			normal: IVec3::ZERO,
+			traveled: 0.0,
+		}
+	}
+}
+
+impl Iterator for VoxelRay {
+	type Item = (IVec3, IVec3, f32);
+
+	fn next(&mut self) -> Option<Self::Item> {
+		let current = (self.pos, self.normal, self.traveled);
+		// Move to next block boundary
+		let axis = if self.t_max.x < self.t_max.y && self.t_max.x < self.t_max.z {
+			0
+		} else if self.t_max.y < self.t_max.z {
+			1
+		} else {
+			2
+		};
+		self.normal = IVec3::ZERO;
+		self.normal[axis] = -self.step[axis];
+		self.pos[axis] += self.step[axis];
+		self.traveled = self.t_max[axis];
+		self.t_max[axis] += self.t_delta[axis];
+		Some(current)
+	}
+}
@@ -1,8 +1,9 @@
 
 use crate::render::world::NeighboringChunks;
-use crate::block::main::Chunk;
+use crate::block::{main::{Block, Chunk}, props};
 use crate::block::math::BlockPosition;
-use glam::IVec3;
+use crate::block::shape::BlockBox;
+use glam::{IVec3, Vec3};
 use std::mem;
 use wgpu::util::DeviceExt;
 
@@ -10,16 +11,18 @@ use wgpu::util::DeviceExt;
 // Vertex Definition
 // =============================================
 
-/// A vertex with position, normal
+/// A vertex of the face quad
 #[repr(C)]
 #[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable, Default)]
 pub struct Vertex {
-	pub position: u32,  // 5 bits per axis (x,y,z)
+	pub position: u32,  // quad corner in bits 0-1 + the corner used instead when the diagonal is flipped in bits 2-3
 }
 #[repr(C)]
-#[derive(Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
+#[derive(Copy, Clone, Debug, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
 pub struct InstanceRaw {
-	pub packed_data: u32,  // 5 bits per axis (x,y,z) + normal index in bits 16-18
+	pub packed_data: u32,  // 4 bits per axis (x,y,z) + normal index in bits 12-14 + translucent flag in bit 15 + texture in bits 16-31
+	pub light: u32, // block light in bits 0-3 + sky light in bits 4-7 (of the block the face looks at) + ambient occlusion of the 4 corners in bits 8-15 + flipped diagonal in bit 16 + crack stage in bits 17-20 + dropped item in bit 21
+	pub shape: u32, // the box the face belongs to, `BlockBox::packed`
 }
 
 impl Vertex {
@@ -43,6 +46,10 @@ impl Vertex {
 			position: pos
 		}
 	}
+	/// Vertex at the quad corner, and at the `flipped` corner when the diagonal is flipped
+	pub const fn corner(corner: u32, flipped: u32) -> Self {
+		Self::new(corner | flipped << 2)
+	}
 }
 impl InstanceRaw {
 	pub const fn desc() -> wgpu::VertexBufferLayout<'static> {
@@ -55,6 +62,16 @@ impl InstanceRaw {
 					shader_location: 1,
 					format: wgpu::VertexFormat::Uint32,
 				},
+				wgpu::VertexAttribute {
+					offset: mem::size_of::<u32>() as wgpu::BufferAddress,
+					shader_location: 2,
+					format: wgpu::VertexFormat::Uint32,
+				},
+				wgpu::VertexAttribute {
+					offset: (mem::size_of::<u32>() * 2) as wgpu::BufferAddress,
+					shader_location: 3,
+					format: wgpu::VertexFormat::Uint32,
+				},
 			],
 		}
 	}
@@ -67,48 +84,173 @@ impl InstanceRaw {
 /// Builder for constructing chunk meshes efficiently
 pub struct ChunkMeshBuilder {
 	pub instances: Vec<InstanceRaw>,
+	pub translucent: Vec<InstanceRaw>, // faces for the blended pass
 }
 const EXTRA_BLOCK_DATA_OFFSET:u32 = 1u32; // currently only a single one : air 
+const TRANSLUCENT_FLAG:u32 = 1u32 << 15; // the free bit after the normal index
+const AO_SHIFT:u32 = 8; // after the light byte
+const FLIP_FLAG:u32 = 1u32 << 16; // after the ambient occlusion
+const NO_AO:u32 = 0xFF << AO_SHIFT; // every corner fully lit
+const CRACK_SHIFT:u32 = 17; // after the flipped diagonal, 0 is not a crack overlay
+const DROPPED_FLAG:u32 = 1u32 << 21; // after the crack stage, drawn as a small cube on the floor of the block
+const FULL_SHAPE:u32 = BlockBox::FULL.packed();
 impl ChunkMeshBuilder {
 	/// Creates a new mesh builder with optimized initial capacity
 	#[inline] pub fn new() -> Self {
 		Self { // set the starting capacity smaller because now with all the culling there is chance for a chunk to be invisible
 			instances: Vec::with_capacity(Chunk::SIZE),
+			translucent: Vec::new(),
 		}
 	}
-	#[inline] pub fn build(self, device: &wgpu::Device) -> GeometryBuffer {
-		GeometryBuffer::new(device, &self.instances)
+	/// Builds the opaque mesh and the translucent one (if there is any translucent face)
+	#[inline] pub fn build(self, device: &wgpu::Device) -> (GeometryBuffer, Option<TranslucentMesh>) {
+		let translucent = if self.translucent.is_empty() {
+			None
+		} else {
+			Some(TranslucentMesh::new(device, self.translucent))
+		};
+		(GeometryBuffer::new(device, &self.instances), translucent)
 	}
 	// pos is allways 0-15
-	pub fn add_cube(&mut self, pos: IVec3, id: u16, chunk: &Chunk, neighbors: &NeighboringChunks) {
+	pub fn add_cube(&mut self, pos: IVec3, block: Block, chunk: &Chunk, neighbors: &NeighboringChunks) {
+		if block.props().shape.is_some() {
+			self.add_shaped_block(pos, block, chunk, neighbors);
+			return;
+		}
+		let id = props::texture_of(block.material());
+		let is_translucent = block.props().is_translucent();
 		for (idx, normal) in CUBE_FACES.iter().enumerate() {
 			let neighbor_pos: IVec3 = pos + *normal;
 			
-			if !self.should_cull_face(neighbor_pos, chunk, &neighbors) {
+			if !self.should_cull_face(block, neighbor_pos, *normal, chunk, &neighbors) {
+				let ao = Self::face_ao(pos, idx, chunk, neighbors);
+				// split the quad along the brighter diagonal, otherwise a single dark corner smears over the whole face
+				let flip = if ao[0] + ao[2] < ao[1] + ao[3] { FLIP_FLAG } else { 0 };
+				let ao = ao.iter().enumerate().fold(0, |packed, (corner, level)| packed | (*level as u32) << (corner * 2));
+				let light = self.face_light(neighbor_pos, chunk, &neighbors) as u32 | ao << AO_SHIFT | flip;
 				let pos = u16::from(BlockPosition::from(pos)) as u32;
-				self.instances.push(InstanceRaw {
-					packed_data: (pos | (idx as u32) << 12 | (id as u32 - EXTRA_BLOCK_DATA_OFFSET) << 16)
-				});
+				let packed_data = pos | (idx as u32) << 12 | (id as u32 - EXTRA_BLOCK_DATA_OFFSET) << 16;
+				if is_translucent {
+					self.translucent.push(InstanceRaw { packed_data: packed_data | TRANSLUCENT_FLAG, light, shape: FULL_SHAPE });
+				} else {
+					self.instances.push(InstanceRaw { packed_data, light, shape: FULL_SHAPE });
+				}
+			}
+		}
+	}
+	/// Adds the faces of every box of a block that is not a full cube (doors ...)
+	/// Only the faces on the side of the block can hide behind a neighbor, the ones inside are lit by the block's own light
+	fn add_shaped_block(&mut self, pos: IVec3, block: Block, chunk: &Chunk, neighbors: &NeighboringChunks) {
+		let id = props::texture_of(block.material());
+		let packed_pos = u16::from(BlockPosition::from(pos)) as u32;
+		let own_light = self.face_light(pos, chunk, neighbors);
+		for shape in block.shape() {
+			for (idx, normal) in CUBE_FACES.iter().enumerate() {
+				let neighbor_pos = pos + *normal;
+				let on_side = shape.touches_side(*normal);
+				if on_side && self.should_cull_face(block, neighbor_pos, *normal, chunk, neighbors) {
+					continue;
+				}
+				let light = if on_side { self.face_light(neighbor_pos, chunk, neighbors) } else { own_light };
+				let packed_data = packed_pos | (idx as u32) << 12 | (id as u32 - EXTRA_BLOCK_DATA_OFFSET) << 16;
+				self.instances.push(InstanceRaw { packed_data, light: light as u32 | NO_AO, shape: shape.packed() });
 			}
 		}
 	}
-	#[inline] fn should_cull_face(&self, pos: IVec3, chunk: &Chunk, neighbors: &NeighboringChunks) -> bool {    
+	/// Adds every face of a block that is not part of a chunk (falling blocks ...), lit by the light it is in
+	pub fn add_loose_cube(&mut self, pos: IVec3, block: Block, light: u8) {
+		let id = props::texture_of(block.material());
+		let pos = u16::from(BlockPosition::from(pos)) as u32;
+		for idx in 0..CUBE_FACES.len() {
+			let packed_data = pos | (idx as u32) << 12 | (id as u32 - EXTRA_BLOCK_DATA_OFFSET) << 16;
+			self.instances.push(InstanceRaw { packed_data, light: light as u32 | NO_AO, shape: FULL_SHAPE });
+		}
+	}
+	/// Adds a dropped block item, a small cube standing in the middle of the block's floor
+	pub fn add_dropped_cube(&mut self, pos: IVec3, block: Block, light: u8) {
+		let start = self.instances.len();
+		self.add_loose_cube(pos, block, light);
+		for instance in self.instances[start..].iter_mut() {
+			instance.light |= DROPPED_FLAG;
+		}
+	}
+	/// Adds the crack overlay faces around a block being mined (stage 1 and up)
+	pub fn add_crack_cube(&mut self, pos: IVec3, stage: u8) {
+		let pos = u16::from(BlockPosition::from(pos)) as u32;
+		for idx in 0..CUBE_FACES.len() {
+			let packed_data = pos | (idx as u32) << 12 | TRANSLUCENT_FLAG;
+			self.instances.push(InstanceRaw { packed_data, light: NO_AO | (stage as u32) << CRACK_SHIFT, shape: FULL_SHAPE });
+		}
+	}
+	/// Ambient occlusion level (0 darkest - 3 open) of each face corner, from the 2 side and the diagonal block in front of it
+	fn face_ao(pos: IVec3, face: usize, chunk: &Chunk, neighbors: &NeighboringChunks) -> [u8; 4] {
+		let normal = CUBE_FACES[face];
+		let front = pos + normal;
+		FACE_CORNERS[face].map(|corner| {
+			// the two directions along the face that lead towards the corner
+			let mut steps = [IVec3::ZERO; 2];
+			let mut step_count = 0;
+			for axis in 0..3 {
+				if normal[axis] != 0 {
+					continue;
+				}
+				steps[step_count][axis] = if corner[axis] == 1 { 1 } else { -1 };
+				step_count += 1;
+			}
+			let side_a = Self::occludes(front + steps[0], chunk, neighbors);
+			let side_b = Self::occludes(front + steps[1], chunk, neighbors);
+			let diagonal = Self::occludes(front + steps[0] + steps[1], chunk, neighbors);
+			if side_a && side_b {
+				0 // the corner is boxed in, the diagonal one can not matter
+			} else {
+				3 - side_a as u8 - side_b as u8 - diagonal as u8
+			}
+		})
+	}
+	/// Does the block (chunk relative position, it can be in any chunk around) darken the corners near it
+	#[inline] fn occludes(pos: IVec3, chunk: &Chunk, neighbors: &NeighboringChunks) -> bool {
+		let offset = pos.div_euclid(IVec3::splat(Chunk::SIZE_I));
+		let chunk = if offset == IVec3::ZERO { Some(chunk) } else { neighbors.get(offset) };
+		chunk.is_some_and(|chunk| chunk.get_block(usize::from(BlockPosition::from(pos))).props().is_opaque())
+	}
+	/// The light of the block in front of a face (it can be in a neighboring chunk)
+	#[inline] fn face_light(&self, pos: IVec3, chunk: &Chunk, neighbors: &NeighboringChunks) -> u8 {
+		let idx = usize::from(BlockPosition::from(pos));
+		if chunk.contains_position(pos) {
+			return chunk.get_light(idx);
+		}
+		self.get_neighbor_chunk_and_local_pos(pos, neighbors)
+			.map_or(0, |chunk| chunk.get_light(idx))
+	}
+	#[inline] fn should_cull_face(&self, block: Block, pos: IVec3, normal: IVec3, chunk: &Chunk, neighbors: &NeighboringChunks) -> bool {    
 		// Check if position is inside current chunk
 		let idx = usize::from(BlockPosition::from(pos));
 		if chunk.contains_position(pos) {
-			return !chunk.get_block(idx).is_empty();
+			return Self::is_face_hidden(block, chunk.get_block(idx), normal);
 		}
 		
 		// Position is in neighboring chunk
 		let neighbor_chunk = self.get_neighbor_chunk_and_local_pos(pos, &neighbors);
 		
 		match neighbor_chunk {
-			Some(chunk) => {
-				!chunk.get_block(idx).is_empty()
-			}, 
+			Some(chunk) => Self::is_face_hidden(block, chunk.get_block(idx), normal),
 			None => true, // No neighbor chunk means not loaded cull for now and reload mesh if it loads in
 		}
 	}
+	/// Opaque neighbors hide everything, see-through ones only hide the same material (water next to water)
+	/// Neighbors that are not full cubes only hide the face when they cover the whole side it looks at (the bottom of a slab ...)
+	#[inline] fn is_face_hidden(block: Block, neighbor: &Block, normal: IVec3) -> bool {
+		if neighbor.is_empty() {
+			return false;
+		}
+		if neighbor.props().shape.is_some() {
+			return neighbor.covers_side(-normal);
+		}
+		if neighbor.props().is_opaque() {
+			return true;
+		}
+		!block.props().is_opaque() && block.material() == neighbor.material() && block.props().shape.is_none()
+	}
 	#[inline] fn get_neighbor_chunk_and_local_pos<'a>(&self, neighbor_pos: IVec3, neighbors: &NeighboringChunks<'a>) -> Option<&'a Chunk> {
 		// Calculate which neighbor we need to check
 		// The neighbor indices should match the order in CUBE_FACES_F:
@@ -133,6 +275,46 @@ impl ChunkMeshBuilder {
 	}
 }
 
+/// Camera movement (in blocks) after which the translucent faces of a chunk are sorted again
+const RESORT_DISTANCE: f32 = 1.0;
+
+/// The blended faces of a chunk, kept to be sorted back to front again as the camera moves
+/// (the depth is not written in the blended pass, the faces have to come in order)
+#[derive(Debug, Clone, PartialEq)]
+pub struct TranslucentMesh {
+	pub buffer: GeometryBuffer,
+	faces: Vec<InstanceRaw>,
+	sorted_for: Option<Vec3>, // chunk space camera position of the last sort
+}
+
+impl TranslucentMesh {
+	#[inline] pub fn new(device: &wgpu::Device, faces: Vec<InstanceRaw>) -> Self {
+		Self { buffer: GeometryBuffer::new(device, &faces), faces, sorted_for: None }
+	}
+
+	/// Sorts the faces again if the camera (in chunk space) moved far enough since the last sort
+	pub fn sort(&mut self, queue: &wgpu::Queue, cam_pos: Vec3) {
+		if self.sorted_for.is_some_and(|sorted_for| sorted_for.distance_squared(cam_pos) < RESORT_DISTANCE * RESORT_DISTANCE) {
+			return;
+		}
+		self.sorted_for = Some(cam_pos);
+		sort_back_to_front(&mut self.faces, cam_pos);
+		queue.write_buffer(&self.buffer.instance_buffer, 0, bytemuck::cast_slice(&self.faces));
+	}
+}
+
+/// Chunk space center of a face
+#[inline] fn face_center(face: &InstanceRaw) -> Vec3 {
+	let pos: IVec3 = BlockPosition::from((face.packed_data & 0xFFF) as u16).into();
+	let normal = CUBE_FACES[((face.packed_data >> 12) & 0x7) as usize];
+	pos.as_vec3() + Vec3::splat(0.5) + normal.as_vec3() * 0.5
+}
+
+/// Orders the faces farthest from the camera (in chunk space) first
+pub fn sort_back_to_front(faces: &mut [InstanceRaw], cam_pos: Vec3) {
+	faces.sort_by(|a, b| face_center(b).distance_squared(cam_pos).total_cmp(&face_center(a).distance_squared(cam_pos)));
+}
+
 /// Make sure your CUBE_FACES constant matches the neighbor array order:
 /// Normal vectors for face lookup
 const CUBE_FACES: [IVec3; 6] = [
@@ -143,13 +325,25 @@ const CUBE_FACES: [IVec3; 6] = [
 	IVec3::Y,     // [4] Top face
 	IVec3::NEG_Y, // [5] Bottom face
 ];
+/// Block space position of the quad corners of each face, in `CUBE_FACES` order
+/// Has to match `normal_to_rot` applied to the corners in the chunk shader
+const FACE_CORNERS: [[IVec3; 4]; 6] = [
+	[IVec3::new(0, 0, 0), IVec3::new(0, 0, 1), IVec3::new(0, 1, 1), IVec3::new(0, 1, 0)], // Left
+	[IVec3::new(1, 1, 0), IVec3::new(1, 1, 1), IVec3::new(1, 0, 1), IVec3::new(1, 0, 0)], // Right
+	[IVec3::new(0, 0, 0), IVec3::new(0, 1, 0), IVec3::new(1, 1, 0), IVec3::new(1, 0, 0)], // Front
+	[IVec3::new(0, 1, 1), IVec3::new(0, 0, 1), IVec3::new(1, 0, 1), IVec3::new(1, 1, 1)], // Back
+	[IVec3::new(0, 1, 0), IVec3::new(0, 1, 1), IVec3::new(1, 1, 1), IVec3::new(1, 1, 0)], // Top
+	[IVec3::new(0, 0, 1), IVec3::new(0, 0, 0), IVec3::new(1, 0, 0), IVec3::new(1, 0, 1)], // Bottom
+];
+/// Two triangles of the face quad, corners 0-2 are the diagonal (1-3 when flipped)
+/// Corners: 0 = (0,0,0), 1 = (0,0,1), 2 = (1,0,1), 3 = (1,0,0) before the shader turns them to the face
 pub const VERTICES: [Vertex; 6] = {
-	let p0 = Vertex::new((0 as u16 | (0 as u16) << 4 | (0 as u16) << 8) as u32);
-	let p1 = Vertex::new((0 as u16 | (0 as u16) << 4 | (1 as u16) << 8) as u32);
-	let p2 = Vertex::new((1 as u16 | (0 as u16) << 4 | (1 as u16) << 8) as u32);
-	let p3 = Vertex::new((1 as u16 | (0 as u16) << 4 | (1 as u16) << 8) as u32);
-	let p4 = Vertex::new((1 as u16 | (0 as u16) << 4 | (0 as u16) << 8) as u32);
-	let p5 = Vertex::new((0 as u16 | (0 as u16) << 4 | (0 as u16) << 8) as u32);
+	let p0 = Vertex::corner(0, 1);
+	let p1 = Vertex::corner(1, 2);
+	let p2 = Vertex::corner(2, 3);
+	let p3 = Vertex::corner(2, 3);
+	let p4 = Vertex::corner(3, 0);
+	let p5 = Vertex::corner(0, 1);
 	[p0, p1, p2, p3, p4, p5]
 };
 
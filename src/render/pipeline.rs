@@ -12,6 +12,8 @@ use wgpu;
 pub struct Pipeline {
 	// Pipelines
 	pub chunk_pipeline: wgpu::RenderPipeline,
+	pub translucent_pipeline: wgpu::RenderPipeline,
+	pub crack_pipeline: wgpu::RenderPipeline,
 	pub post_pipeline: wgpu::RenderPipeline,
 	pub sky_pipeline: wgpu::RenderPipeline,
 }
@@ -48,6 +50,8 @@ impl Pipeline {
 
 		Self {
 			chunk_pipeline: create_chunk_pipeline(device, &chunk_layout, &shaders.chunk, config.format),
+			translucent_pipeline: create_translucent_pipeline(device, &chunk_layout, &shaders.chunk, config.format),
+			crack_pipeline: create_crack_pipeline(device, &chunk_layout, &shaders.chunk, config.format),
 			post_pipeline: create_post_pipeline(device, &post_layout, &shaders.post, config.format),
 			sky_pipeline: create_sky_pipeline(device, &sky_layout, &shaders.sky, config.format),
 		}
@@ -143,6 +147,50 @@ fn create_chunk_pipeline(
 	)
 }
 #[inline]
+fn create_translucent_pipeline(
+	device: &wgpu::Device,
+	layout: &wgpu::PipelineLayout,
+	shader: &wgpu::ShaderModule,
+	format: wgpu::TextureFormat,
+) -> wgpu::RenderPipeline {
+	create_base_pipeline(
+		device,
+		Some(layout),
+		shader,
+		format,
+		&[Vertex::desc(), InstanceRaw::desc()],
+		Some(translucent_depth_stencil_state()),
+		wgpu::PrimitiveState {
+			cull_mode: None, // water surface has to be visible from below too
+			..default_primitive_state()
+		},
+		"Translucent Render Pipeline",
+	)
+}
+#[inline]
+fn create_crack_pipeline(
+	device: &wgpu::Device,
+	layout: &wgpu::PipelineLayout,
+	shader: &wgpu::ShaderModule,
+	format: wgpu::TextureFormat,
+) -> wgpu::RenderPipeline {
+	create_base_pipeline(
+		device,
+		Some(layout),
+		shader,
+		format,
+		&[Vertex::desc(), InstanceRaw::desc()],
+		Some(wgpu::DepthStencilState {
+			depth_compare: wgpu::CompareFunction::LessEqual,
+			// pulled towards the camera so it does not flicker with the block under it
+			bias: wgpu::DepthBiasState { constant: -2, slope_scale: -1.0, clamp: 0.0 },
+			..translucent_depth_stencil_state()
+		}),
+		default_primitive_state(),
+		"Crack Render Pipeline",
+	)
+}
+#[inline]
 fn create_post_pipeline(
 	device: &wgpu::Device,
 	layout: &wgpu::PipelineLayout,
@@ -204,6 +252,13 @@ fn depth_stencil_state() -> wgpu::DepthStencilState {
 		bias: wgpu::DepthBiasState::default(),
 	}
 }
+#[inline]
+fn translucent_depth_stencil_state() -> wgpu::DepthStencilState {
+	wgpu::DepthStencilState {
+		depth_write_enabled: false, // test against the opaque depth but do not hide each other
+		..depth_stencil_state()
+	}
+}
 
 // --- Optimized Render Passes ---
 #[inline]
@@ -248,6 +303,9 @@ pub fn render_all(current_state: &mut State) -> Result<(), wgpu::SurfaceError> {
 			sky_pass.set_bind_group(1, game_state.player().camera_system().bind_group(), &[]);
 			sky_pass.draw(0..36, 0..1);
 		}
+		// Create vertex buffer
+		let vertex_buffer = current_state.device().create_buffer_init(&wgpu::util::BufferInitDescriptor {
+			label: Some("Vertex Buffer"), contents: bytemuck::cast_slice(&VERTICES), usage: wgpu::BufferUsages::VERTEX });
 		{
 			let mut rpass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
 				label: Some("3D Render Pass"),
@@ -275,12 +333,8 @@ pub fn render_all(current_state: &mut State) -> Result<(), wgpu::SurfaceError> {
 			rpass.set_pipeline(&current_state.pipeline().chunk_pipeline);
 			rpass.set_bind_group(0, current_state.texture_manager().bind_group(), &[]);
 			rpass.set_bind_group(1, game_state.player().camera_system().bind_group(), &[]);
+			rpass.set_vertex_buffer(0, vertex_buffer.slice(..));
 			{
-				// Create vertex buffer
-				let vertex_buffer = current_state.device().create_buffer_init(&wgpu::util::BufferInitDescriptor {
-					label: Some("Vertex Buffer"), contents: bytemuck::cast_slice(&VERTICES), usage: wgpu::BufferUsages::VERTEX });
-				rpass.set_vertex_buffer(0, vertex_buffer.slice(..));
-
 				//let indices = vec![0, 1, 2, 2, 3, 0]; // Two triangles forming a quad
 				/*let index_buffer = current_state.device().create_buffer_init(&wgpu::util::BufferInitDescriptor {
 					label: Some("Index Buffer"),
@@ -291,6 +345,42 @@ pub fn render_all(current_state: &mut State) -> Result<(), wgpu::SurfaceError> {
 			}
 			ptr::get_gamestate().world().render_chunks(&mut rpass);
 		}
+		// the faces inside the chunks are put in order before the pass draws them
+		ptr::get_gamestate().world_mut().sort_translucent_faces(current_state.queue(), game_state.player().cam_pos());
+		{
+			// Translucent pass : keeps the opaque depth, blends back to front
+			let mut tpass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+				label: Some("Translucent Render Pass"),
+				color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+					view: &view,
+					resolve_target: None,
+					ops: wgpu::Operations {
+						load: wgpu::LoadOp::Load,
+						store: wgpu::StoreOp::Store,
+					},
+				})],
+				depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
+					view: &binding,
+					depth_ops: Some(wgpu::Operations {
+						load: wgpu::LoadOp::Load,
+						store: wgpu::StoreOp::Store,
+					}),
+					stencil_ops: None,
+				}),
+				occlusion_query_set: None,
+				timestamp_writes: None,
+			});
+
+			tpass.set_pipeline(&current_state.pipeline().translucent_pipeline);
+			tpass.set_bind_group(0, current_state.texture_manager().bind_group(), &[]);
+			tpass.set_bind_group(1, game_state.player().camera_system().bind_group(), &[]);
+			tpass.set_vertex_buffer(0, vertex_buffer.slice(..));
+			let cam_pos = game_state.player().cam_pos();
+			ptr::get_gamestate().world().render_translucent_chunks(&mut tpass, cam_pos);
+
+			tpass.set_pipeline(&current_state.pipeline().crack_pipeline);
+			game_state.mining().render_crack(&mut tpass);
+		}
 	}
 
 	// Post processing pass 
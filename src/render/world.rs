@@ -1,36 +1,54 @@
 
 use wgpu::util::DeviceExt;
-use glam::IVec3;
+use glam::{IVec3, Vec3};
 use crate::block::main::{Block, Chunk};
 use crate::block::math::{ChunkCoord, BlockPosition};
 use crate::render::meshing::{ChunkMeshBuilder, GeometryBuffer};
 use crate::ext::ptr;
 use crate::world::main::World;
 
+/// Every chunk around a chunk, the diagonal ones too (ambient occlusion looks into them)
 pub struct NeighboringChunks<'a> {
-	chunks: [Option<&'a Chunk>; 6],
+	chunks: [Option<&'a Chunk>; 27], // indexed by `Self::slot` of the chunk offset, the middle one is always None
 }
 
 impl<'a> NeighboringChunks<'a> {
-	pub fn new(chunks: [Option<&'a Chunk>; 6]) -> Self {
+	const MIDDLE: usize = 13;
+
+	pub fn new(chunks: [Option<&'a Chunk>; 27]) -> Self {
 		Self { chunks }
 	}
 
+	/// Index of a chunk offset (-1..=1 on every axis)
+	#[inline] pub const fn slot(offset: IVec3) -> usize {
+		((offset.x + 1) * 9 + (offset.y + 1) * 3 + (offset.z + 1)) as usize
+	}
+	/// Chunk offset of an index
+	#[inline] pub const fn offset(slot: usize) -> IVec3 {
+		IVec3::new(slot as i32 / 9 - 1, slot as i32 / 3 % 3 - 1, slot as i32 % 3 - 1)
+	}
+
+	/// The chunk at the chunk offset (-1..=1 on every axis)
+	#[inline] pub const fn get(&self, offset: IVec3) -> Option<&'a Chunk> { self.chunks[Self::slot(offset)] }
+
 	// Directional accessors
-	pub fn left(&self) -> Option<&'a Chunk> { self.chunks[0] }    // (-1, 0, 0)
-	pub fn right(&self) -> Option<&'a Chunk> { self.chunks[1] }    // (1, 0, 0)
-	pub fn front(&self) -> Option<&'a Chunk> { self.chunks[2] }   // (0, 0, -1)
-	pub fn back(&self) -> Option<&'a Chunk> { self.chunks[3] }    // (0, 0, 1)
-	pub fn top(&self) -> Option<&'a Chunk> { self.chunks[4] }     // (0, 1, 0)
-	pub fn bottom(&self) -> Option<&'a Chunk> { self.chunks[5] }  // (0, -1, 0)
+	pub fn left(&self) -> Option<&'a Chunk> { self.get(IVec3::NEG_X) }    // (-1, 0, 0)
+	pub fn right(&self) -> Option<&'a Chunk> { self.get(IVec3::X) }    // (1, 0, 0)
+	pub fn front(&self) -> Option<&'a Chunk> { self.get(IVec3::NEG_Z) }   // (0, 0, -1)
+	pub fn back(&self) -> Option<&'a Chunk> { self.get(IVec3::Z) }    // (0, 0, 1)
+	pub fn top(&self) -> Option<&'a Chunk> { self.get(IVec3::Y) }     // (0, 1, 0)
+	pub fn bottom(&self) -> Option<&'a Chunk> { self.get(IVec3::NEG_Y) }  // (0, -1, 0)
 
 	pub fn is_some(&self) -> bool {
-		self.chunks.iter().all(Option::is_some)
+		self.iter().all(|chunk| chunk.is_some())
 	}
 
-	// Add an iter() method that returns an iterator over Option<&Chunk>
+	// Add an iter() method that returns an iterator over Option<&Chunk> (all 26 around)
 	pub fn iter(&self) -> impl Iterator<Item = Option<&'a Chunk>> + '_ {
-		self.chunks.iter().copied()
+		self.chunks.iter()
+			.enumerate()
+			.filter(|(slot, _)| *slot != Self::MIDDLE)
+			.map(|(_, chunk)| *chunk)
 	}
 }
 
@@ -39,6 +57,11 @@ impl<'a> NeighboringChunks<'a> {
 // =============================================
 
 impl Chunk {
+	/// Could the mesh be out of date (cheap check before looking up the neighbors)
+	#[inline] pub fn needs_mesh(&self) -> bool {
+		self.dirty || !(self.mesh.is_some() ^ self.is_empty()) || !self.final_mesh
+	}
+
 	pub fn make_mesh(&mut self, device: &wgpu::Device, _queue: &wgpu::Queue, neighbors: NeighboringChunks) {
 		if !self.dirty 
 			&& (self.mesh.is_some() ^ self.is_empty()) 
@@ -50,6 +73,7 @@ impl Chunk {
 		// Early return if chunk is empty
 		if self.is_empty() && self.mesh.is_some() {
 			self.mesh = Some(GeometryBuffer::empty(device));
+			self.translucent_mesh = None;
 			self.dirty = false;
 			self.final_mesh = true;
 		}
@@ -63,14 +87,16 @@ impl Chunk {
 			}
 			let local_pos:IVec3 = BlockPosition::from(pos).into();
 			match block {
-				Block::Simple(..) => {
-					builder.add_cube(local_pos, block.material(), &self, &neighbors);
+				Block::Simple(..) | Block::Stateful(..) => {
+					builder.add_cube(local_pos, block, &self, &neighbors);
 				},
 				_ => {},
 			}
 		}
 
-		self.mesh = Some(builder.build(device));
+		let (mesh, translucent_mesh) = builder.build(device);
+		self.mesh = Some(mesh);
+		self.translucent_mesh = translucent_mesh;
 		self.dirty = false;
 		if neighbors.is_some() {
 			self.final_mesh = true;
@@ -88,7 +114,7 @@ impl World {
 		let world_ptr = self as *mut World;
 
 		for (chunk_coord, chunk) in self.chunks.iter_mut() {
-			if chunk.is_empty() {
+			if chunk.is_empty() || !chunk.needs_mesh() {
 				continue;
 			}
 
@@ -103,51 +129,89 @@ impl World {
 
 			chunk.make_mesh(device, queue, neighbors);
 		}
+		self.make_falling_meshes(device);
+		self.make_dropped_meshes(device);
+	}
+
+	/// Falling blocks are drawn as a single cube snapped to the block they are in
+	fn make_falling_meshes(&mut self, device: &wgpu::Device) {
+		let mut falling = std::mem::take(&mut self.falling);
+		for entity in falling.iter_mut() {
+			let pos = entity.block_pos();
+			if entity.drawn_at == Some(pos) {
+				continue;
+			}
+			let mut builder = ChunkMeshBuilder::new();
+			builder.add_loose_cube(BlockPosition::from(pos).into(), entity.block, self.get_light(pos));
+			entity.mesh = Some(builder.build(device).0);
+			entity.bind_group = Some(make_chunk_bind_group(ChunkCoord::from_world_pos(pos)));
+			entity.drawn_at = Some(pos);
+		}
+		self.falling = falling;
+	}
+
+	/// Dropped block items are drawn as a small cube on the floor of the block they are in
+	/// Other items have no texture to draw with yet
+	fn make_dropped_meshes(&mut self, device: &wgpu::Device) {
+		let mut dropped = std::mem::take(&mut self.dropped);
+		for item in dropped.iter_mut() {
+			let pos = item.block_pos();
+			let Some(material) = item.stack.get_block_id() else { continue; };
+			if item.drawn_at == Some(pos) {
+				continue;
+			}
+			let mut builder = ChunkMeshBuilder::new();
+			builder.add_dropped_cube(BlockPosition::from(pos).into(), Block::new(material), self.get_light(pos));
+			item.mesh = Some(builder.build(device).0);
+			item.bind_group = Some(make_chunk_bind_group(ChunkCoord::from_world_pos(pos)));
+			item.drawn_at = Some(pos);
+		}
+		self.dropped = dropped;
 	}
 
 	#[inline]
 	pub fn get_neighboring_chunks(&self, chunk_coord: ChunkCoord) -> NeighboringChunks {
-		//self.chunks.get(&chunk_coord);
-		let neigh = chunk_coord.get_adjacent();
-		NeighboringChunks::new([
-			self.get_chunk(neigh[0]),  // Left
-			self.get_chunk(neigh[1]), // Right
-			self.get_chunk(neigh[2]),   // Front
-			self.get_chunk(neigh[3]), // Back
-			self.get_chunk(neigh[4]), // Top
-			self.get_chunk(neigh[5]),   // Bottom
-		])
+		NeighboringChunks::new(std::array::from_fn(|slot| {
+			let offset = NeighboringChunks::offset(slot);
+			if offset == IVec3::ZERO {
+				return None;
+			}
+			self.get_chunk(chunk_coord.offset(offset.x, offset.y, offset.z))
+		}))
 	}
 }
 impl Chunk {
 	/// Recreates chunk's bind group
 	pub fn create_bind_group(&mut self, chunk_coord: ChunkCoord) {
 		if self.bind_group.is_some() { return; }
-		let state = ptr::get_state();
-		let device = state.device();
-		let chunk_bind_group_layout = &state.render_context.layouts[2];
-
-		// Create position buffer
-		let position_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
-			label: Some("Chunk Position Buffer"),
-			contents: bytemuck::cast_slice(&[<ChunkCoord as Into<u64>>::into(chunk_coord)]),
-			usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
-		});
-
-		// Create bind group
-		let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
-			layout: chunk_bind_group_layout,
-			entries: &[wgpu::BindGroupEntry {
-				binding: 0,
-				resource: position_buffer.as_entire_binding(),
-			}],
-			label: Some("chunk_bind_group"),
-		});
-
-		self.bind_group = Some(bind_group);
+		self.bind_group = Some(make_chunk_bind_group(chunk_coord));
 	}
 }
 
+/// Creates the bind group holding a chunk's position (everything drawn with the chunk shader needs one)
+pub fn make_chunk_bind_group(chunk_coord: ChunkCoord) -> wgpu::BindGroup {
+	let state = ptr::get_state();
+	let device = state.device();
+	let chunk_bind_group_layout = &state.render_context.layouts[2];
+
+	// Create position buffer
+	let position_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+		label: Some("Chunk Position Buffer"),
+		contents: bytemuck::cast_slice(&[<ChunkCoord as Into<u64>>::into(chunk_coord)]),
+		usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+	});
+
+	// Create bind group
+	device.create_bind_group(&wgpu::BindGroupDescriptor {
+		layout: chunk_bind_group_layout,
+		entries: &[wgpu::BindGroupEntry {
+			binding: 0,
+			resource: position_buffer.as_entire_binding(),
+		}],
+		label: Some("chunk_bind_group"),
+	})
+}
+
 
 impl World {
 	pub fn render_chunks<'a>(&'a self, render_pass: &mut wgpu::RenderPass<'a>) {
@@ -168,6 +232,52 @@ impl World {
 				render_pass.draw(0..6, 0..mesh.num_instances as u32);
 			}
 		}
+		for entity in self.falling.iter() {
+			if let (Some(mesh), Some(bind_group)) = (&entity.mesh, &entity.bind_group) {
+				render_pass.set_bind_group(2, bind_group, &[]);
+				render_pass.set_vertex_buffer(1, mesh.instance_buffer.slice(..));
+				render_pass.draw(0..6, 0..mesh.num_instances as u32);
+			}
+		}
+		for item in self.dropped.iter() {
+			if let (Some(mesh), Some(bind_group)) = (&item.mesh, &item.bind_group) {
+				render_pass.set_bind_group(2, bind_group, &[]);
+				render_pass.set_vertex_buffer(1, mesh.instance_buffer.slice(..));
+				render_pass.draw(0..6, 0..mesh.num_instances as u32);
+			}
+		}
+	}
+
+	/// Sorts the translucent faces inside the chunks back to front for the camera, the chunks themselves are ordered when drawn
+	pub fn sort_translucent_faces(&mut self, queue: &wgpu::Queue, cam_pos: Vec3) {
+		for (chunk_coord, chunk) in self.chunks.iter_mut() {
+			if let Some(mesh) = chunk.translucent_mesh.as_mut() {
+				mesh.sort(queue, cam_pos - chunk_coord.to_world_pos());
+			}
+		}
+	}
+
+	/// Draws the translucent meshes back to front, has to run after every opaque chunk is drawn
+	pub fn render_translucent_chunks<'a>(&'a self, render_pass: &mut wgpu::RenderPass<'a>, cam_pos: Vec3) {
+		let half_chunk = Vec3::splat(Chunk::SIZE_F / 2.0);
+		let mut sorted: Vec<(f32, &GeometryBuffer, &wgpu::BindGroup)> = self.chunks.iter()
+			.filter_map(|(chunk_coord, chunk)| {
+				let (mesh, bind_group) = (chunk.translucent_mesh()?, chunk.bind_group()?);
+				if mesh.num_instances == 0 {
+					return None;
+				}
+				let center = chunk_coord.to_world_pos() + half_chunk;
+				Some((center.distance_squared(cam_pos), mesh, bind_group))
+			})
+			.collect();
+		// Farthest first so the closer water blends over the farther one
+		sorted.sort_by(|a, b| b.0.total_cmp(&a.0));
+
+		for (_, mesh, bind_group) in sorted {
+			render_pass.set_bind_group(2, bind_group, &[]);
+			render_pass.set_vertex_buffer(1, mesh.instance_buffer.slice(..));
+			render_pass.draw(0..6, 0..mesh.num_instances);
+		}
 	}
 
 	pub fn create_bind_group(&mut self, chunk_coord: ChunkCoord) {
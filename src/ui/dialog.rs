@@ -108,6 +108,27 @@ impl DialogManager {
 		id
 	}
 
+	/// Shows an error until the user dismisses it (non-blocking)
+	pub fn show_error(&self, message: impl Into<String>) -> u8 {
+		let prompt = message.into();
+		let id: u8 = self.inner.counter.fetch_add(1, Ordering::Relaxed);
+
+		if let Ok(mut pending) = self.inner.pending.write() {
+			pending.insert(id, PendingDialog {
+				response_holder: Arc::new(Mutex::new(None)),
+				prompt: prompt.clone(),
+				created_at: Instant::now(),
+				callback: None,
+				waker: Arc::new(Mutex::new(None)),
+			});
+		}
+
+		// Show dialog in UI
+		ptr::get_state().ui_manager.error(id, &prompt);
+
+		id
+	}
+
 	/// Respond to a specific dialog by ID
 	pub fn respond(&self, id: u8, response: bool) -> bool {
 		if let Ok(pending_lock) = self.inner.pending.read() {
@@ -333,4 +354,10 @@ impl UIManager {
 		ui_manager.state = UIState::Confirm(UIStateID::from(&ui_manager.state), id.clone());
 		ui_manager.setup_ui();
 	}
+
+	pub fn error(&mut self, id: u8, _prompt: impl Into<String>) {
+		let ui_manager = &mut ptr::get_state().ui_manager;
+		ui_manager.state = UIState::Error(UIStateID::from(&ui_manager.state), id);
+		ui_manager.setup_ui();
+	}
 }
\ No newline at end of file
@@ -466,7 +466,13 @@ impl UIManager {
 		if area.rows == 0 || area.columns == 0 { return; }
 		let config = &ptr::get_settings();
 		let inventory = &ptr::get_gamestate().player().inventory();
-		let items = inventory.get_items_by_area(&area.name);
+		let items = match area.name {
+			AreaType::Storage => match ptr::get_gamestate().storage_container() {
+				Some(container) => container,
+				None => inventory.get_items_by_area(&area.name),
+			},
+			_ => inventory.get_items_by_area(&area.name),
+		};
 		
 		for row in 0..area.rows {
 			for col in 0..area.columns {
@@ -126,6 +126,7 @@ pub fn close_pressed() {
 		UIState::ConnectLocal => state.ui_manager.state = UIState::WorldSelection,
 		UIState::Inventory(_) => {
 			state.ui_manager.state = UIState::InGame;
+			*ptr::get_gamestate().open_storage() = None;
 			state.toggle_mouse_capture();
 		},
 		_ => return,
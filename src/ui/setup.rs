@@ -3,7 +3,10 @@ use crate::network::api;
 use crate::block;
 use crate::ext::{ptr, memory, color::Solor};
 use crate::world::{handler, manager};
-use crate::ui::manager::{UIState, close_pressed, UIManager, UIStateID, get_element_str_by_id};
+use crate::world::generator::{self, GeneratorPreset, GeneratorSettings};
+use crate::world::import::{self, HeightmapSettings};
+use crate::world::terrain;
+use crate::ui::manager::{UIState, close_pressed, UIManager, UIStateID, get_element_data_by_id, get_element_str_by_id};
 use crate::ui::element::UIElement;
 
 impl UIManager {
@@ -126,6 +129,54 @@ impl UIManager {
 			});
 		self.add_element(memory_button);
 
+		// stamps a heightmap image into the world: the image, its scales and material, then its corner (the player's feet if left empty)
+		let stamp_id = self.next_id();
+		let stamp_input = UIElement::input(stamp_id)
+			.with_position(-0.8, -0.45)
+			.with_size(0.4, 0.08)
+			.with_style(&theme.inputs.basic)
+			.with_placeholder("map.png, 1, 0.25, stone")
+			.with_z_index(5);
+		self.add_element(stamp_input);
+
+		let origin_id = self.next_id();
+		let origin_input = UIElement::input(origin_id)
+			.with_position(-0.8, -0.55)
+			.with_size(0.4, 0.08)
+			.with_style(&theme.inputs.basic)
+			.with_placeholder("x, y, z")
+			.with_z_index(5);
+		self.add_element(origin_input);
+
+		let stamp_button = UIElement::button(self.next_id(), "Stamp Heightmap")
+			.with_position(-0.8, -0.65)
+			.with_size(0.4, 0.08)
+			.with_style(&theme.buttons.nice)
+			.with_z_index(6)
+			.with_callback(move || {
+				let text = |id: &usize| get_element_data_by_id(id).and_then(|data| data.text()).unwrap_or("");
+				let dialogs = &ptr::get_state().ui_manager.dialogs;
+				let Some(settings) = HeightmapSettings::parse(text(&stamp_id)) else {
+					dialogs.show_error(format!("Invalid heightmap '{}'", text(&stamp_id)));
+					return;
+				};
+				let game_state = ptr::get_gamestate();
+				let origin = match text(&origin_id).trim() {
+					"" => game_state.player().pos().floor().as_ivec3(),
+					origin => match import::parse_origin(origin) {
+						Some(origin) => origin,
+						None => {
+							dialogs.show_error(format!("Invalid position '{}'", origin));
+							return;
+						}
+					},
+				};
+				if let Err(e) = import::stamp_file(game_state.world_mut(), &settings, origin) {
+					dialogs.show_error(format!("Can't stamp the heightmap: {}", e));
+				}
+			});
+		self.add_element(stamp_button);
+
 		let setting_button = UIElement::button(self.next_id(), "Settings")
 			.with_position(-0.9, 0.0)
 			.with_size(0.4, 0.1)
@@ -460,35 +511,107 @@ impl UIManager {
 		self.add_element(title);
 
 		let form_panel = UIElement::panel(self.next_id())
-			.with_position(-0.4, -0.3)
-			.with_size(0.8, 0.7)
+			.with_position(-0.4, -0.45)
+			.with_size(0.8, 0.85)
 			.with_style(&theme.panels.basic)
 			.with_z_index(1);
 		self.add_element(form_panel);
 
-		let w_name_label = UIElement::label(self.next_id(), "World Name:")
-			.with_position(-0.35, 0.1)
-			.with_size(0.4, 0.08)
-			.with_style(&theme.labels.basic)
-			.with_z_index(3);
-		self.add_element(w_name_label);
+		// a label on the left and its field on the right, one row under the other
+		let rows = ["World Name:", "Seed:", "Generator:", "Layers:", "Sea Level:", "Heightmap:"];
+		let row_y = |row: usize| 0.22 - row as f32 * 0.1;
+		for (row, text) in rows.iter().enumerate() {
+			let label = UIElement::label(self.next_id(), *text)
+				.with_position(-0.35, row_y(row))
+				.with_size(0.25, 0.08)
+				.with_style(&theme.labels.basic)
+				.with_z_index(3);
+			self.add_element(label);
+		}
 
 		let input_id = self.next_id();
 		let world_name_input = UIElement::input(input_id)
-			.with_position(-0.35, -0.0)
-			.with_size(0.7, 0.1)
+			.with_position(-0.08, row_y(0))
+			.with_size(0.43, 0.09)
 			.with_style(&theme.inputs.basic)
 			.with_placeholder("New World")
 			.with_z_index(5);
 		self.add_element(world_name_input);
 
+		let seed_id = self.next_id();
+		let seed_input = UIElement::input(seed_id)
+			.with_position(-0.08, row_y(1))
+			.with_size(0.43, 0.09)
+			.with_style(&theme.inputs.basic)
+			.with_placeholder("Random")
+			.with_z_index(5);
+		self.add_element(seed_input);
+
+		let preset_id = self.next_id();
+		let preset_button = UIElement::multi_state_button(preset_id, GeneratorPreset::ALL.map(GeneratorPreset::name).to_vec())
+			.with_position(-0.08, row_y(2))
+			.with_size(0.43, 0.09)
+			.with_style(&theme.buttons.basic)
+			.with_z_index(5);
+		self.add_element(preset_button);
+
+		// only the superflat preset uses the layers
+		let layers_id = self.next_id();
+		let layers_input = UIElement::input(layers_id)
+			.with_position(-0.08, row_y(3))
+			.with_size(0.43, 0.09)
+			.with_style(&theme.inputs.basic)
+			.with_placeholder(generator::DEFAULT_LAYERS)
+			.with_z_index(5);
+		self.add_element(layers_input);
+
+		let sea_level_id = self.next_id();
+		let sea_level_input = UIElement::input(sea_level_id)
+			.with_position(-0.08, row_y(4))
+			.with_size(0.43, 0.09)
+			.with_style(&theme.inputs.basic)
+			.with_placeholder(terrain::SEA_LEVEL.to_string())
+			.with_z_index(5);
+		self.add_element(sea_level_input);
+
+		// only the heightmap preset uses it: the image, then optionally the scales and the material
+		let heightmap_id = self.next_id();
+		let heightmap_input = UIElement::input(heightmap_id)
+			.with_position(-0.08, row_y(5))
+			.with_size(0.43, 0.09)
+			.with_style(&theme.inputs.basic)
+			.with_placeholder("map.png, 1, 0.25, stone")
+			.with_z_index(5);
+		self.add_element(heightmap_input);
+
 		let gen_button = UIElement::button(self.next_id(), "Create World")
-			.with_position(-0.3, -0.2)
-			.with_size(0.6, 0.1)
+			.with_position(-0.3, -0.42)
+			.with_size(0.6, 0.08)
 			.with_style(&theme.buttons.nice)
 			.with_z_index(6)
 			.with_callback(move || {
-				handler::create_world(get_element_str_by_id(&input_id).to_string());
+				let text = |id: &usize| get_element_data_by_id(id).and_then(|data| data.text()).unwrap_or("");
+				let preset = GeneratorPreset::from_name(text(&preset_id)).unwrap_or(GeneratorPreset::Default);
+				let mut settings = GeneratorSettings::new(preset, generator::seed_from_text(text(&seed_id)));
+				if !text(&layers_id).is_empty() {
+					match generator::parse_layers(text(&layers_id)) {
+						Some(layers) if !layers.is_empty() => settings = settings.with_layers(layers),
+						_ => println!("Invalid layers '{}', using the default ones", text(&layers_id)),
+					}
+				}
+				if !text(&sea_level_id).is_empty() {
+					match text(&sea_level_id).trim().parse() {
+						Ok(sea_level) => settings = settings.with_sea_level(sea_level),
+						Err(_) => println!("Invalid sea level '{}', using the default one", text(&sea_level_id)),
+					}
+				}
+				if !text(&heightmap_id).is_empty() {
+					match HeightmapSettings::parse(text(&heightmap_id)) {
+						Some(heightmap) => settings = settings.with_heightmap(heightmap),
+						None => println!("Invalid heightmap '{}'", text(&heightmap_id)),
+					}
+				}
+				handler::create_world(get_element_str_by_id(&input_id).to_string(), settings);
 				let ui_manager = &mut ptr::get_state().ui_manager;
 				ui_manager.state = UIState::WorldSelection;
 				ui_manager.setup_ui();
@@ -496,7 +619,7 @@ impl UIManager {
 		self.add_element(gen_button);
 
 		let back_button = UIElement::button(self.next_id(), "Back")
-			.with_position(-0.1, -0.45)
+			.with_position(-0.1, -0.6)
 			.with_size(0.2, 0.08)
 			.with_style(&theme.buttons.extra())
 			.with_z_index(8)
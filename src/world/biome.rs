@@ -0,0 +1,186 @@
+use crate::block::main::Material;
+use crate::block::props::mat;
+use crate::hs::math::Noise;
+use crate::world::structure::FeatureKind;
+
+/// Frequency of the temperature and humidity noise, a biome is a few hundred blocks wide
+const CLIMATE_FREQ: f32 = 0.0025;
+/// How far apart (in climate) two biomes blend into each other, wider is smoother
+const BLEND_WIDTH: f32 = 0.25;
+/// The biome weights are worked out on a lattice this many blocks wide and interpolated in between
+/// A biome border then takes at least this long, whatever the climate noise does
+const BLEND_CELL: i32 = 16;
+/// Seed offsets keeping the climate noises apart from each other and from the terrain
+const TEMPERATURE_SALT: u32 = 0x5EED_7E39;
+const HUMIDITY_SALT: u32 = 0x5EED_4D1D;
+
+/// The kinds of land the world is made of
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Biome {
+	Plains,
+	Desert,
+	Snowy,
+	Mountains,
+}
+
+/// What a biome looks like: where it is in the climate, how its ground is shaped and what it is covered with
+#[derive(Clone, Copy, Debug)]
+pub struct BiomeProps {
+	pub name: &'static str,
+	pub temperature: f32, // the climate it is at home in (-1 cold - 1 hot)
+	pub humidity: f32, // (-1 dry - 1 wet)
+	pub base_height: f32, // ground height with flat terrain noise
+	pub amplitude: f32, // how much the terrain noise lifts and sinks the ground
+	pub ridges: f32, // height of the ridges on top (mountain tops)
+	pub surface: Material, // the top block
+	pub subsurface: Material, // the blocks right under the top one
+	pub subsurface_depth: i32,
+	pub features: &'static [(FeatureKind, f32)], // what stands on it, with the chance per feature cell
+}
+
+impl BiomeProps {
+	const fn new(name: &'static str, temperature: f32, humidity: f32) -> Self {
+		Self {
+			name,
+			temperature,
+			humidity,
+			base_height: 4.0,
+			amplitude: 64.0,
+			ridges: 0.0,
+			surface: mat::DIRT_GRASS,
+			subsurface: mat::DIRT,
+			subsurface_depth: 3,
+			features: &[],
+		}
+	}
+	const fn height(mut self, base_height: f32, amplitude: f32) -> Self { self.base_height = base_height; self.amplitude = amplitude; self }
+	const fn ridges(mut self, ridges: f32) -> Self { self.ridges = ridges; self }
+	const fn features(mut self, features: &'static [(FeatureKind, f32)]) -> Self { self.features = features; self }
+	const fn cover(mut self, surface: Material, subsurface: Material, depth: i32) -> Self {
+		self.surface = surface;
+		self.subsurface = subsurface;
+		self.subsurface_depth = depth;
+		self
+	}
+}
+
+/// Indexed by `Biome as usize`
+static BIOMES: [BiomeProps; Biome::COUNT] = [
+	BiomeProps::new("plains", 0.15, 0.35)
+		.features(&[(FeatureKind::Tree, 0.2), (FeatureKind::Boulder, 0.03), (FeatureKind::Ruin, 0.01)]),
+	BiomeProps::new("desert", 0.5, -0.5).height(2.0, 40.0).cover(mat::SAND, mat::STONE_SAND, 4)
+		.features(&[(FeatureKind::Boulder, 0.02), (FeatureKind::Ruin, 0.02)]),
+	BiomeProps::new("snowy", -0.55, 0.2).height(6.0, 96.0).cover(mat::DIRT_SNOW, mat::DIRT, 3)
+		.features(&[(FeatureKind::WhiteTree, 0.15), (FeatureKind::Boulder, 0.02)]),
+	BiomeProps::new("mountains", -0.2, -0.45).height(24.0, 128.0).ridges(40.0).cover(mat::STONE_GRASS, mat::STONE, 1)
+		.features(&[(FeatureKind::Boulder, 0.08), (FeatureKind::Tree, 0.04)]),
+];
+
+impl Biome {
+	pub const COUNT: usize = 4;
+	pub const ALL: [Self; Self::COUNT] = [Self::Plains, Self::Desert, Self::Snowy, Self::Mountains];
+
+	#[inline] pub fn props(self) -> &'static BiomeProps {
+		&BIOMES[self as usize]
+	}
+}
+
+/// Temperature and humidity of a column, both -1 to 1
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Climate {
+	pub temperature: f32,
+	pub humidity: f32,
+}
+
+/// How much every biome has to say about a column, the weights add up to 1
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BiomeBlend {
+	pub weights: [f32; Biome::COUNT],
+}
+
+impl BiomeBlend {
+	/// Every biome weighs in by how close the climate is to its own, the nearest one the most
+	pub fn from_climate(climate: Climate) -> Self {
+		let mut weights = Biome::ALL.map(|biome| {
+			let props = biome.props();
+			let distance_sq = (climate.temperature - props.temperature).powi(2) + (climate.humidity - props.humidity).powi(2);
+			(-distance_sq / (BLEND_WIDTH * BLEND_WIDTH)).exp()
+		});
+		let total: f32 = weights.iter().sum();
+		if total <= f32::MIN_POSITIVE {
+			// far from every biome, the nearest takes it all
+			let nearest = Self::nearest(climate);
+			weights = [0.0; Biome::COUNT];
+			weights[nearest as usize] = 1.0;
+		} else {
+			weights.iter_mut().for_each(|weight| *weight /= total);
+		}
+		Self { weights }
+	}
+
+	fn nearest(climate: Climate) -> Biome {
+		Biome::ALL.into_iter()
+			.min_by(|a, b| {
+				let distance = |biome: &Biome| (climate.temperature - biome.props().temperature).powi(2) + (climate.humidity - biome.props().humidity).powi(2);
+				distance(a).total_cmp(&distance(b))
+			})
+			.unwrap_or(Biome::Plains)
+	}
+
+	/// The biome weighing in the most, it picks the blocks of the column
+	pub fn dominant(&self) -> Biome {
+		Biome::ALL.into_iter()
+			.max_by(|a, b| self.weights[*a as usize].total_cmp(&self.weights[*b as usize]))
+			.unwrap_or(Biome::Plains)
+	}
+
+	/// Weighs two blends together, `t` of the other one
+	#[inline] pub fn lerp(&self, other: &Self, t: f32) -> Self {
+		let mut weights = self.weights;
+		for (weight, other) in weights.iter_mut().zip(other.weights) {
+			*weight += (other - *weight) * t;
+		}
+		Self { weights }
+	}
+
+	/// The weighted mix of a per-biome value (the ground height ...)
+	#[inline] pub fn mix(&self, value: impl Fn(&BiomeProps) -> f32) -> f32 {
+		Biome::ALL.iter().map(|biome| self.weights[*biome as usize] * value(biome.props())).sum()
+	}
+}
+
+/// The temperature and humidity noise of a world
+#[derive(Clone, Copy, Debug)]
+pub struct ClimateNoise {
+	temperature: Noise,
+	humidity: Noise,
+}
+
+impl ClimateNoise {
+	pub const fn new(seed: u32) -> Self {
+		Self {
+			temperature: Noise::new(seed ^ TEMPERATURE_SALT),
+			humidity: Noise::new(seed ^ HUMIDITY_SALT),
+		}
+	}
+
+	/// The climate of the column, two octaves of slow noise each
+	pub fn climate(&self, x: i32, z: i32) -> Climate {
+		let sample = |noise: &Noise| {
+			let (x, z) = (x as f32 * CLIMATE_FREQ, z as f32 * CLIMATE_FREQ);
+			let value = (noise.noise_2d(x, z) + 0.5 * noise.noise_2d(x * 2.0 + 31.7, z * 2.0 - 11.3)) / 1.5;
+			(value * 0.5).clamp(-1.0, 1.0)
+		};
+		Climate { temperature: sample(&self.temperature), humidity: sample(&self.humidity) }
+	}
+
+	/// The biome blend of the column, interpolated between the corners of its lattice cell
+	pub fn blend(&self, x: i32, z: i32) -> BiomeBlend {
+		let (cell_x, cell_z) = (x.div_euclid(BLEND_CELL) * BLEND_CELL, z.div_euclid(BLEND_CELL) * BLEND_CELL);
+		let (tx, tz) = ((x - cell_x) as f32 / BLEND_CELL as f32, (z - cell_z) as f32 / BLEND_CELL as f32);
+		let corner = |dx: i32, dz: i32| BiomeBlend::from_climate(self.climate(cell_x + dx * BLEND_CELL, cell_z + dz * BLEND_CELL));
+		let near = corner(0, 0).lerp(&corner(1, 0), tx);
+		let far = corner(0, 1).lerp(&corner(1, 1), tx);
+		near.lerp(&far, tz)
+	}
+}
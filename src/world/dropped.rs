@@ -0,0 +1,113 @@
+use crate::block::math::ChunkCoord;
+use crate::game::inventory::Inventory;
+use crate::game::items::ItemStack;
+use crate::physic::aabb::{AABB, GRAVITY, PhysicsBody};
+use crate::render::meshing::GeometryBuffer;
+use crate::world::main::World;
+use glam::{IVec3, Vec3};
+
+/// Size of a dropped item's box (blocks)
+pub const ITEM_SIZE: f32 = 0.25;
+/// Seconds before a dropped item can be picked up (so it is seen popping out first)
+pub const PICKUP_DELAY: f32 = 0.5;
+/// Distance from the player an item gets picked up from
+pub const PICKUP_RANGE: f32 = 1.75;
+/// Seconds a dropped item lies around before it disappears
+pub const DESPAWN_TIME: f32 = 300.0;
+/// Longest physics step (same reason as the falling blocks)
+const MAX_STEP: f32 = 1.0 / 60.0;
+/// Falling speed cap (blocks per second)
+const TERMINAL_VELOCITY: f32 = 30.0;
+
+/// An item stack lying in the world until someone picks it up
+#[derive(Debug, Clone)]
+pub struct DroppedItem {
+	pub stack: ItemStack,
+	pub body: PhysicsBody,
+	pub age: f32, // seconds since it was dropped
+	pub mesh: Option<GeometryBuffer>,
+	pub bind_group: Option<wgpu::BindGroup>,
+	pub drawn_at: Option<IVec3>, // block position the mesh was made for
+}
+
+impl DroppedItem {
+	/// Drops the stack with its box centered on the given point
+	#[inline] pub fn new(stack: ItemStack, center: Vec3) -> Self {
+		Self {
+			stack,
+			body: PhysicsBody::new(AABB::from_center(center, Vec3::splat(ITEM_SIZE / 2.0))),
+			age: 0.0,
+			mesh: None,
+			bind_group: None,
+			drawn_at: None,
+		}
+	}
+
+	/// The block position the item currently lies in
+	#[inline] pub fn block_pos(&self) -> IVec3 {
+		let min = self.body.aabb.min;
+		let center = self.body.aabb.center();
+		IVec3::new(center.x.floor() as i32, min.y.floor() as i32, center.z.floor() as i32)
+	}
+
+	/// Moves the item, it rests on top of the first block box it falls into (it falls through open doors ...)
+	fn step(&mut self, world: &World, delta_seconds: f32) {
+		let mut remaining = delta_seconds;
+		while remaining > 0.0 {
+			let dt = remaining.min(MAX_STEP);
+			remaining -= dt;
+			self.body.update(dt, GRAVITY);
+			self.body.velocity.y = self.body.velocity.y.max(-TERMINAL_VELOCITY);
+
+			let pos = self.block_pos();
+			let floor = if world.get_chunk(ChunkCoord::from_world_pos(pos)).is_none() {
+				Some(pos.y as f32 + 1.0) // nothing to fall into yet
+			} else {
+				self.floor_in(world, pos)
+			};
+			if let Some(floor) = floor.filter(|floor| self.body.aabb.min.y < *floor) {
+				self.body.aabb = self.body.aabb.translate(Vec3::new(0.0, floor - self.body.aabb.min.y, 0.0));
+				self.body.velocity = Vec3::ZERO;
+			}
+		}
+	}
+
+	/// The top of the highest box under the item in the block (None if it falls right through)
+	fn floor_in(&self, world: &World, pos: IVec3) -> Option<f32> {
+		let aabb = &self.body.aabb;
+		world.get_block(pos).collision().iter()
+			.map(|shape| shape.aabb(pos))
+			.filter(|shape| shape.min.x < aabb.max.x && shape.max.x > aabb.min.x && shape.min.z < aabb.max.z && shape.max.z > aabb.min.z)
+			.map(|shape| shape.max.y)
+			.reduce(f32::max)
+	}
+}
+
+impl World {
+	/// Drops an item stack into the world, centered on the given point
+	#[inline] pub fn drop_item(&mut self, stack: ItemStack, center: Vec3) {
+		self.dropped.push(DroppedItem::new(stack, center));
+	}
+
+	/// Moves every dropped item and removes the ones that lay around for too long
+	pub fn update_dropped_items(&mut self, delta_seconds: f32) {
+		if self.dropped.is_empty() {
+			return;
+		}
+		let mut dropped = std::mem::take(&mut self.dropped);
+		dropped.retain_mut(|item| {
+			item.age += delta_seconds;
+			item.step(self, delta_seconds);
+			item.age < DESPAWN_TIME
+		});
+		self.dropped = dropped;
+	}
+
+	/// Moves the dropped items near the point into the inventory (the ones that fit)
+	pub fn pick_up_dropped_items(&mut self, pos: Vec3, inventory: &mut Inventory) {
+		self.dropped.retain(|item| {
+			let near = item.body.aabb.distance_squared_to_point(pos) <= PICKUP_RANGE * PICKUP_RANGE;
+			!(near && item.age >= PICKUP_DELAY && inventory.add_item_anywhere(item.stack.clone()))
+		});
+	}
+}
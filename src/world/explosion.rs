@@ -0,0 +1,205 @@
+use crate::block::main::Block;
+use crate::block::props::mat;
+use crate::block::shape::{BlockBox, UNITS};
+use crate::block::{loot, signal};
+use crate::hs::math::Rand;
+use crate::physic::aabb::PhysicsBody;
+use crate::physic::ray::VoxelRay;
+use crate::world::main::World;
+use glam::{IVec3, Vec3};
+use std::collections::HashSet;
+
+// The state byte of a TNT: bit 0 lit
+
+/// Power of a TNT blowing up
+pub const TNT_POWER: f32 = 4.0;
+/// Ticks between lighting a TNT and it blowing up
+pub const FUSE_TICKS: u32 = 80;
+/// Ticks a fire burns before it goes out
+pub const BURN_TICKS: u32 = 100;
+/// Fuse of a TNT lit by another explosion, chains go off quicker
+const CHAIN_FUSE_TICKS: u32 = 10;
+/// Rays start from the points of a grid this wide on the surface of a cube around the center
+const RAY_GRID: i32 = 16;
+/// Strength a ray loses for every block it travels
+const FALLOFF: f32 = 0.75;
+/// Strength every block takes out of a ray on top of its blast resistance
+const BLOCK_FALLOFF: f32 = 0.3;
+/// Knockback at the center, per power
+const KNOCKBACK: f32 = 2.0;
+/// Knockback reaches this many times the power far
+const KNOCKBACK_RANGE: f32 = 2.0;
+/// Chance of a fire starting in a blown up space that has a floor
+const FIRE_CHANCE: f32 = 1.0 / 3.0;
+
+const LIT: u8 = 1 << 0;
+
+#[inline] pub const fn is_lit(state: u8) -> bool { state & LIT != 0 }
+
+static FIRE: [BlockBox; 1] = [BlockBox::new([1, 0, 1], [UNITS - 1, 10, UNITS - 1])];
+
+/// A blast at a point, the power is about how many blocks of dirt it digs through
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Explosion {
+	pub center: Vec3,
+	pub power: f32,
+	pub fire: bool, // sets fires in the blown up spaces
+}
+
+impl Explosion {
+	#[inline] pub const fn new(center: Vec3, power: f32) -> Self {
+		Self { center, power, fire: false }
+	}
+	#[inline] pub const fn with_fire(mut self) -> Self { self.fire = true; self }
+
+	/// The blocks the blast breaks: rays go out in every direction, losing strength with every block of air they cross
+	/// and with the blast resistance of every block they go through
+	pub fn affected_blocks(&self, world: &World, rand: &mut Rand) -> Vec<IVec3> {
+		let mut affected = HashSet::new();
+		let last = RAY_GRID - 1;
+		for x in 0..RAY_GRID {
+			for y in 0..RAY_GRID {
+				for z in 0..RAY_GRID {
+					if ![x, y, z].iter().any(|value| *value == 0 || *value == last) {
+						continue; // only the surface of the cube
+					}
+					let dir = (IVec3::new(x, y, z).as_vec3() / last as f32 * 2.0 - Vec3::ONE).normalize();
+					let mut strength = self.power * (0.7 + 0.6 * rand.next_f32());
+					for (pos, _, traveled) in VoxelRay::new(self.center, dir) {
+						if strength - traveled * FALLOFF <= 0.0 {
+							break;
+						}
+						let block = world.get_block(pos);
+						if block.is_empty() {
+							continue;
+						}
+						strength -= block.props().blast_resistance() + BLOCK_FALLOFF;
+						if strength - traveled * FALLOFF > 0.0 {
+							affected.insert(pos);
+						}
+					}
+				}
+			}
+		}
+		// the same blast breaks the same blocks in the same order (the drops and fires roll in this order)
+		let mut affected: Vec<IVec3> = affected.into_iter().collect();
+		affected.sort_unstable_by_key(|pos| pos.to_array());
+		affected
+	}
+
+	/// The push a body with its center at the point gets: away from the center and a little up, weaker further out
+	pub fn knockback(&self, point: Vec3) -> Vec3 {
+		let offset = point - self.center;
+		let range = self.power * KNOCKBACK_RANGE;
+		let distance = offset.length();
+		if distance >= range {
+			return Vec3::ZERO;
+		}
+		// a little upwards too, so things lying on the ground get thrown as well
+		let dir = (offset.normalize_or(Vec3::Y) + Vec3::Y * 0.5).normalize();
+		dir * KNOCKBACK * self.power * (1.0 - distance / range)
+	}
+
+	/// Pushes the body by its knockback
+	#[inline] pub fn push(&self, body: &mut PhysicsBody) {
+		body.apply_impulse(self.knockback(body.aabb.center()));
+	}
+}
+
+impl World {
+	/// Blows up: breaks the blocks the blast reaches in a single batch, drops some of their loot, pushes the loose bodies away
+	/// and sets fires if it should, returns the positions of the broken blocks
+	/// The bodies outside of the world (the player) are pushed by whoever takes the explosion from `take_explosions`
+	/// Blocks with their own blast behavior do that instead of breaking (TNT lights up)
+	pub fn explode(&mut self, explosion: &Explosion, rand: &mut Rand) -> Vec<IVec3> {
+		let (reacting, broken): (Vec<IVec3>, Vec<IVec3>) = explosion.affected_blocks(self, rand)
+			.into_iter()
+			.partition(|pos| self.get_block(*pos).props().on_blast.is_some());
+
+		// the loot has to be rolled before the blocks are gone, bigger blasts destroy more of it
+		let mut drops = Vec::new();
+		for pos in &broken {
+			if rand.next_f32() < 1.0 / explosion.power.max(1.0) {
+				drops.extend(loot::roll(self.get_block(*pos), rand).into_iter().map(|stack| (*pos, stack)));
+			}
+			if let Some(container) = self.get_block_entity(*pos).and_then(|entity| entity.container()) {
+				drops.extend(container.iter().flatten().cloned().map(|stack| (*pos, stack)));
+			}
+		}
+		let removed: Vec<IVec3> = self.remove_blocks(&broken).into_iter().map(|(pos, _)| pos).collect();
+		for pos in reacting {
+			if let Some(on_blast) = self.get_block(pos).props().on_blast {
+				on_blast(self, pos);
+			}
+		}
+		for (pos, stack) in drops {
+			self.drop_item(stack, pos.as_vec3() + Vec3::splat(0.5));
+		}
+
+		for item in self.dropped.iter_mut() {
+			explosion.push(&mut item.body);
+		}
+		for entity in self.falling.iter_mut() {
+			explosion.push(&mut entity.body);
+		}
+		self.explosions.push(*explosion);
+
+		if explosion.fire {
+			for pos in &removed {
+				let has_floor = self.get_block(*pos - IVec3::Y).covers_side(IVec3::Y);
+				if has_floor && self.get_block(*pos).is_empty() && rand.next_f32() < FIRE_CHANCE {
+					self.set_block(*pos, Block::new(mat::FIRE));
+				}
+			}
+		}
+		removed
+	}
+}
+
+impl World {
+	/// The explosions since the last call, oldest first
+	#[inline] pub fn take_explosions(&mut self) -> Vec<Explosion> {
+		std::mem::take(&mut self.explosions)
+	}
+}
+
+/// Lights a TNT, it blows up when its fuse burns down
+pub fn ignite(world: &mut World, pos: IVec3) {
+	world.set_block_state(pos, LIT); // the scheduled tick of a changed block is the fuse
+}
+
+//
+// Block behaviors used by the props table
+//
+
+/// Blast reaction of the TNT: lit by another explosion, with a shorter fuse
+pub fn chain_ignite(world: &mut World, pos: IVec3) {
+	world.schedule_tick(pos, CHAIN_FUSE_TICKS); // before the state change would schedule the long one
+	ignite(world, pos);
+}
+
+/// Neighbor update of the TNT: a signal reaching it lights it
+pub fn tnt_update(world: &mut World, pos: IVec3, _source: IVec3) {
+	if signal::received(world, pos) > 0 {
+		ignite(world, pos);
+	}
+}
+
+/// Scheduled tick of the TNT: a lit one blows up
+pub fn tnt_fuse(world: &mut World, pos: IVec3, rand: &mut Rand) {
+	if !is_lit(world.get_block(pos).state()) {
+		return;
+	}
+	world.set_block(pos, Block::None);
+	world.explode(&Explosion::new(pos.as_vec3() + Vec3::splat(0.5), TNT_POWER), rand);
+}
+
+/// Scheduled tick of the fire: it goes out
+pub fn burn_out(world: &mut World, pos: IVec3, _rand: &mut Rand) {
+	world.set_block(pos, Block::None);
+}
+
+/// Fire is a bit lower than a block
+pub fn fire_shape(_block: &Block) -> &'static [BlockBox] {
+	&FIRE
+}
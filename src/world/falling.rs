@@ -0,0 +1,132 @@
+use crate::block::main::Block;
+use crate::block::math::ChunkCoord;
+use crate::hs::math::Rand;
+use crate::physic::aabb::{AABB, GRAVITY, PhysicsBody};
+use crate::render::meshing::GeometryBuffer;
+use crate::world::main::World;
+use glam::IVec3;
+
+/// Ticks between a falling block losing its support and it starting to fall
+pub const FALL_DELAY: u32 = 2;
+/// Longest physics step, keeps a fast block from skipping a one block thick floor
+const MAX_STEP: f32 = 1.0 / 60.0;
+/// Falling speed cap (blocks per second)
+const TERMINAL_VELOCITY: f32 = 30.0;
+
+/// A block that lost its support and falls as an entity until it lands
+#[derive(Debug, Clone)]
+pub struct FallingBlock {
+	pub block: Block,
+	pub body: PhysicsBody,
+	pub mesh: Option<GeometryBuffer>,
+	pub bind_group: Option<wgpu::BindGroup>,
+	pub drawn_at: Option<IVec3>, // block position the mesh was made for
+}
+
+impl FallingBlock {
+	#[inline] pub fn new(block: Block, pos: IVec3) -> Self {
+		Self {
+			block,
+			body: PhysicsBody::new(AABB::from_ivec(pos, pos + IVec3::ONE)),
+			mesh: None,
+			bind_group: None,
+			drawn_at: None,
+		}
+	}
+
+	/// The block position the entity currently occupies
+	#[inline] pub fn block_pos(&self) -> IVec3 {
+		let min = self.body.aabb.min;
+		let center = self.body.aabb.center();
+		IVec3::new(center.x.floor() as i32, min.y.floor() as i32, center.z.floor() as i32)
+	}
+
+	/// Has the bottom of the entity reached a collision box of the block at the position (the top of a slab ...)
+	fn hits(&self, world: &World, pos: IVec3) -> bool {
+		let bottom = self.body.aabb.min.y;
+		world.get_block(pos).collision().iter().any(|shape| shape.aabb(pos).max.y > bottom)
+	}
+}
+
+/// Can a falling block move into this block: nothing in it to bump into (it sinks through fluids, crushes crops ...)
+#[inline] pub fn can_fall_into(block: &Block) -> bool {
+	block.collision().is_empty()
+}
+
+/// Scheduled tick of the blocks that fall: turns the block into a falling entity if nothing holds it
+pub fn fall_check(world: &mut World, pos: IVec3, _rand: &mut Rand) {
+	let block = *world.get_block(pos);
+	if !block.props().falls || !can_fall_into(world.get_block(pos - IVec3::Y)) {
+		return;
+	}
+	// nothing to fall into if the chunk below is not there yet
+	if world.get_chunk(ChunkCoord::from_world_pos(pos - IVec3::Y)).is_none() {
+		return;
+	}
+	world.set_block(pos, Block::None); // schedules the block above, so stacks fall one after another
+	world.falling.push(FallingBlock::new(block, pos));
+}
+
+impl World {
+	/// Moves every falling block and places back the ones that landed
+	pub fn update_falling_blocks(&mut self, delta_seconds: f32) {
+		if self.falling.is_empty() {
+			return;
+		}
+		let mut falling = std::mem::take(&mut self.falling);
+		falling.retain_mut(|entity| {
+			let mut remaining = delta_seconds;
+			while remaining > 0.0 {
+				let dt = remaining.min(MAX_STEP);
+				remaining -= dt;
+				entity.body.update(dt, GRAVITY);
+				entity.body.velocity.y = entity.body.velocity.y.max(-TERMINAL_VELOCITY);
+
+				let pos = entity.block_pos();
+				if entity.hits(self, pos) || self.get_chunk(ChunkCoord::from_world_pos(pos)).is_none() {
+					self.land_falling_block(entity.block, pos + IVec3::Y);
+					return false;
+				}
+			}
+			true
+		});
+		// landing may have started new blocks falling
+		falling.append(&mut self.falling);
+		self.falling = falling;
+	}
+
+	/// Places the falling block back in the world (lost if something took its place meanwhile)
+	fn land_falling_block(&mut self, block: Block, pos: IVec3) {
+		if can_fall_into(self.get_block(pos)) {
+			self.set_block(pos, block);
+		}
+	}
+
+	/// Puts every falling block back in the world where it is, they are not saved as entities
+	/// Placed in the air they start falling again on the next tick
+	pub fn settle_falling_blocks(&mut self) {
+		self.settle_falling_blocks_where(|_| true);
+	}
+
+	/// Puts the falling blocks inside the chunk back in the world, before it goes away
+	pub fn settle_falling_blocks_in(&mut self, chunk_coord: ChunkCoord) {
+		self.settle_falling_blocks_where(|pos| ChunkCoord::from_world_pos(pos) == chunk_coord);
+	}
+
+	fn settle_falling_blocks_where(&mut self, inside: impl Fn(IVec3) -> bool) {
+		let falling = std::mem::take(&mut self.falling);
+		for entity in falling {
+			let pos = entity.block_pos();
+			if !inside(pos) {
+				self.falling.push(entity);
+				continue;
+			}
+			// the block it is sinking into may be taken, the first free one above it is where it came from
+			let mut pos = pos;
+			while !can_fall_into(self.get_block(pos)) && ChunkCoord::from_world_pos(pos + IVec3::Y) == ChunkCoord::from_world_pos(pos) {
+				pos += IVec3::Y;
+			}
+			self.land_falling_block(entity.block, pos);
+		}
+	}
+}
@@ -0,0 +1,372 @@
+use crate::block::main::{Block, Chunk, Material};
+use crate::block::math::{BlockPosition, ChunkCoord};
+use crate::block::props::{self, mat};
+use crate::hs::math::{self, Noise, Rand};
+use crate::world::heightmap::ColumnMap;
+use crate::world::import::{HeightmapGen, HeightmapSettings};
+use crate::world::terrain::{TerrainGen, SEA_LEVEL};
+use std::fmt::Debug;
+use std::io::{Error, ErrorKind, Result};
+use std::sync::Arc;
+
+/// How much taller the amplified preset makes the ground
+pub const AMPLIFIED: f32 = 2.0;
+/// The top layer of a superflat world is at this height
+pub const FLAT_TOP: i32 = -1;
+/// Half the width of the platform under the spawn of a void world
+const PLATFORM_RADIUS: i32 = 2;
+/// Height the floating islands are gathered around
+const ISLAND_Y: i32 = 48;
+/// How far above and below it islands float, they thin out towards the ends
+const ISLAND_SPREAD: f32 = 40.0;
+/// Frequency of the island noise, an island is a few dozen blocks wide
+const ISLAND_FREQ: f32 = 0.02;
+/// Land floats where the island noise is above this, higher gives fewer and smaller islands
+const ISLAND_THRESHOLD: f32 = 0.2;
+
+/// Makes the chunks of a world, the same chunk for the same coordinate every time
+/// Shared between everything generating chunks, so it has to be thread safe
+pub trait WorldGenerator: Send + Sync + Debug {
+	fn generate(&self, coord: ChunkCoord) -> Chunk;
+
+	/// The ground of a chunk column without generating its chunks
+	/// None if the generator doesn't shape its ground by columns
+	fn column_map(&self, _chunk_x: i32, _chunk_z: i32) -> Option<Arc<ColumnMap>> {
+		None
+	}
+
+	/// The highest block of the column the sky can't see through, without generating its chunks (`i32::MIN` if there is none)
+	/// None if the generator can't tell, only the loaded chunks decide where the sky reaches then
+	fn surface_height(&self, _x: i32, _z: i32) -> Option<i32> {
+		None
+	}
+}
+
+/// The kinds of worlds a new world can be made as
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum GeneratorPreset {
+	Default, // biomes, caves, ores and features
+	Superflat, // flat layers
+	Void, // nothing but a little platform to stand on
+	Amplified, // the default ground, taller
+	Islands, // floating islands in the sky
+	Heightmap, // the ground of a grayscale image
+}
+
+impl GeneratorPreset {
+	pub const ALL: [Self; 6] = [Self::Default, Self::Superflat, Self::Void, Self::Amplified, Self::Islands, Self::Heightmap];
+
+	#[inline] pub const fn name(self) -> &'static str {
+		match self {
+			Self::Default => "Default",
+			Self::Superflat => "Superflat",
+			Self::Void => "Void",
+			Self::Amplified => "Amplified",
+			Self::Islands => "Islands",
+			Self::Heightmap => "Heightmap",
+		}
+	}
+	#[inline] pub fn from_name(name: &str) -> Option<Self> {
+		Self::ALL.into_iter().find(|preset| preset.name() == name)
+	}
+	#[inline] pub fn from_id(id: u8) -> Option<Self> {
+		Self::ALL.get(id as usize).copied()
+	}
+}
+
+/// A layer of a superflat world
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FlatLayer {
+	pub material: Material,
+	pub thickness: u8,
+}
+
+/// Stone, dirt and grass on top
+pub const DEFAULT_LAYERS: &str = "stone*3,dirt*2,dirt_grass";
+
+/// Reads superflat layers from the bottom up, like "stone*3,dirt*2,dirt_grass" (None if a name is unknown)
+pub fn parse_layers(text: &str) -> Option<Vec<FlatLayer>> {
+	text.split(',')
+		.map(str::trim)
+		.filter(|part| !part.is_empty())
+		.map(|part| {
+			let (name, thickness) = match part.split_once('*') {
+				Some((name, count)) => (name.trim(), count.trim().parse().ok()?),
+				None => (part, 1),
+			};
+			Some(FlatLayer { material: props::by_name(name)?, thickness })
+		})
+		.collect()
+}
+
+/// Writes superflat layers the way `parse_layers` reads them
+pub fn layers_to_string(layers: &[FlatLayer]) -> String {
+	layers.iter()
+		.map(|layer| match layer.thickness {
+			1 => props::get(layer.material).name.to_string(),
+			thickness => format!("{}*{}", props::get(layer.material).name, thickness),
+		})
+		.collect::<Vec<_>>()
+		.join(",")
+}
+
+/// The seed typed in: a number is used as it is, other text is hashed and nothing picks a random one
+pub fn seed_from_text(text: &str) -> u32 {
+	let text = text.trim();
+	if text.is_empty() {
+		return math::next_int();
+	}
+	text.parse().unwrap_or_else(|_| text.bytes().fold(0x811C_9DC5, |hash, byte| Rand::pcg_hash(hash ^ byte as u32)))
+}
+
+/// Which generator a world uses and how, saved with the world
+#[derive(Clone, Debug, PartialEq)]
+pub struct GeneratorSettings {
+	pub preset: GeneratorPreset,
+	pub seed: u32,
+	pub layers: Vec<FlatLayer>, // bottom up, only the superflat preset uses them
+	pub sea_level: i32, // the default and amplified presets fill the ground under it with water
+	pub heightmap: Option<HeightmapSettings>, // the image the heightmap preset reads
+}
+
+impl Default for GeneratorSettings {
+	/// The default world with seed 0, what worlds were made as before the presets
+	fn default() -> Self {
+		Self::new(GeneratorPreset::Default, 0)
+	}
+}
+
+impl GeneratorSettings {
+	pub fn new(preset: GeneratorPreset, seed: u32) -> Self {
+		Self { preset, seed, layers: parse_layers(DEFAULT_LAYERS).unwrap_or_default(), sea_level: SEA_LEVEL, heightmap: None }
+	}
+	pub fn with_layers(mut self, layers: Vec<FlatLayer>) -> Self { self.layers = layers; self }
+	pub const fn with_sea_level(mut self, sea_level: i32) -> Self { self.sea_level = sea_level; self }
+	pub fn with_heightmap(mut self, heightmap: HeightmapSettings) -> Self { self.heightmap = Some(heightmap); self }
+
+	/// The generator making the chunks of the world
+	/// Fails when the heightmap image can't be read, the world can't be generated without it
+	pub fn build(&self) -> Result<Arc<dyn WorldGenerator>> {
+		Ok(match self.preset {
+			GeneratorPreset::Default => Arc::new(TerrainGen::new(self.seed).with_sea_level(self.sea_level)),
+			GeneratorPreset::Superflat => Arc::new(FlatGen::new(self.layers.clone())),
+			GeneratorPreset::Void => Arc::new(VoidGen),
+			GeneratorPreset::Amplified => Arc::new(TerrainGen::new(self.seed).amplified(AMPLIFIED).with_sea_level(self.sea_level)),
+			GeneratorPreset::Islands => Arc::new(IslandsGen::new(self.seed)),
+			GeneratorPreset::Heightmap => {
+				let heightmap = self.heightmap.clone().ok_or_else(|| Error::new(ErrorKind::NotFound, "No heightmap picked"))?;
+				Arc::new(HeightmapGen::load(heightmap)?)
+			}
+		})
+	}
+
+	pub fn to_bytes(&self) -> Vec<u8> {
+		let mut bytes = Vec::with_capacity(10 + self.layers.len() * 3);
+		bytes.push(self.preset as u8);
+		bytes.extend_from_slice(&self.seed.to_le_bytes());
+		bytes.push(self.layers.len() as u8);
+		for layer in &self.layers {
+			bytes.extend_from_slice(&layer.material.to_le_bytes());
+			bytes.push(layer.thickness);
+		}
+		bytes.extend_from_slice(&self.sea_level.to_le_bytes());
+		match &self.heightmap {
+			Some(heightmap) => {
+				bytes.push(1);
+				bytes.extend_from_slice(&heightmap.to_bytes());
+			}
+			None => bytes.push(0),
+		}
+		bytes
+	}
+
+	pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+		let invalid = || Error::new(ErrorKind::InvalidData, "Invalid generator settings");
+		if bytes.len() < 6 {
+			return Err(invalid());
+		}
+		let preset = GeneratorPreset::from_id(bytes[0]).ok_or_else(invalid)?;
+		let seed = u32::from_le_bytes([bytes[1], bytes[2], bytes[3], bytes[4]]);
+		let count = bytes[5] as usize;
+		let layers = bytes[6..].chunks_exact(3)
+			.take(count)
+			.map(|layer| FlatLayer { material: u16::from_le_bytes([layer[0], layer[1]]), thickness: layer[2] })
+			.collect::<Vec<_>>();
+		if layers.len() != count {
+			return Err(invalid());
+		}
+		let (sea_level, rest) = match &bytes[6 + count * 3..] {
+			[a, b, c, d, rest @ ..] => (i32::from_le_bytes([*a, *b, *c, *d]), rest),
+			_ => return Err(invalid()),
+		};
+		let heightmap = match rest {
+			[0, ..] => None,
+			[1, heightmap @ ..] => Some(HeightmapSettings::from_bytes(heightmap)?.0),
+			_ => return Err(invalid()),
+		};
+		Ok(Self { preset, seed, layers, sea_level, heightmap })
+	}
+}
+
+impl WorldGenerator for TerrainGen {
+	fn generate(&self, coord: ChunkCoord) -> Chunk {
+		TerrainGen::generate(self, coord)
+	}
+
+	fn column_map(&self, chunk_x: i32, chunk_z: i32) -> Option<Arc<ColumnMap>> {
+		Some(TerrainGen::column_map(self, chunk_x, chunk_z))
+	}
+
+	/// The top of the ground, trees and caves breaking through it are only found once their chunks load
+	fn surface_height(&self, x: i32, z: i32) -> Option<i32> {
+		Some(self.cached_column(x, z).height)
+	}
+}
+
+/// Flat layers everywhere, the same in every column, nothing under them
+#[derive(Clone, Debug)]
+pub struct FlatGen {
+	layers: Vec<FlatLayer>,
+}
+
+impl FlatGen {
+	pub const fn new(layers: Vec<FlatLayer>) -> Self {
+		Self { layers }
+	}
+
+	/// The material of the layer at the height (None above the top and under the bottom)
+	pub fn material_at(&self, y: i32) -> Option<Material> {
+		let mut top = FLAT_TOP;
+		for layer in self.layers.iter().rev() {
+			if y > top {
+				return None;
+			}
+			if y > top - layer.thickness as i32 {
+				return Some(layer.material);
+			}
+			top -= layer.thickness as i32;
+		}
+		None
+	}
+}
+
+impl WorldGenerator for FlatGen {
+	fn generate(&self, coord: ChunkCoord) -> Chunk {
+		let (_, world_y, _) = coord.unpack_to_worldpos();
+		let mut chunk = Chunk::empty();
+		for y in 0..Chunk::SIZE {
+			let Some(material) = self.material_at(world_y + y as i32) else { continue; };
+			for x in 0..Chunk::SIZE {
+				for z in 0..Chunk::SIZE {
+					let idx: BlockPosition = (x, y, z).into();
+					chunk.set_block(idx.into(), Block::new(material));
+				}
+			}
+		}
+		chunk
+	}
+
+	fn surface_height(&self, _x: i32, _z: i32) -> Option<i32> {
+		let mut top = FLAT_TOP;
+		for layer in self.layers.iter().rev() {
+			if layer.thickness > 0 && props::get(layer.material).is_opaque() {
+				return Some(top);
+			}
+			top -= layer.thickness as i32;
+		}
+		Some(i32::MIN)
+	}
+}
+
+/// Empty space, only a little stone platform under the spawn
+#[derive(Clone, Debug)]
+pub struct VoidGen;
+
+impl WorldGenerator for VoidGen {
+	fn generate(&self, coord: ChunkCoord) -> Chunk {
+		let (world_x, world_y, world_z) = coord.unpack_to_worldpos();
+		let mut chunk = Chunk::empty();
+		let y = -1 - world_y;
+		if !(0..Chunk::SIZE_I).contains(&y) {
+			return chunk;
+		}
+		for x in -PLATFORM_RADIUS..=PLATFORM_RADIUS {
+			for z in -PLATFORM_RADIUS..=PLATFORM_RADIUS {
+				let (x, z) = (x - world_x, z - world_z);
+				if (0..Chunk::SIZE_I).contains(&x) && (0..Chunk::SIZE_I).contains(&z) {
+					let idx: BlockPosition = (x as usize, y as usize, z as usize).into();
+					chunk.set_block(idx.into(), Block::new(mat::STONE));
+				}
+			}
+		}
+		chunk
+	}
+
+	fn surface_height(&self, x: i32, z: i32) -> Option<i32> {
+		let on_platform = x.abs() <= PLATFORM_RADIUS && z.abs() <= PLATFORM_RADIUS;
+		Some(if on_platform { -1 } else { i32::MIN })
+	}
+}
+
+/// Islands of grass, dirt and stone floating in empty space
+#[derive(Clone, Debug)]
+pub struct IslandsGen {
+	noise: Noise,
+}
+
+impl IslandsGen {
+	pub const fn new(seed: u32) -> Self {
+		Self { noise: Noise::new(seed) }
+	}
+
+	/// Whether the block is part of an island, they get thinner away from the island height
+	pub fn is_solid(&self, x: i32, y: i32, z: i32) -> bool {
+		let fade = (y - ISLAND_Y) as f32 / ISLAND_SPREAD;
+		if fade.abs() >= 1.0 {
+			return false;
+		}
+		let (x, y, z) = (x as f32 * ISLAND_FREQ, y as f32 * ISLAND_FREQ * 1.5, z as f32 * ISLAND_FREQ);
+		self.noise.fractal_noise_3d(x, y, z, 3) - fade * fade * 0.5 > ISLAND_THRESHOLD
+	}
+}
+
+impl WorldGenerator for IslandsGen {
+	fn generate(&self, coord: ChunkCoord) -> Chunk {
+		let (world_x, world_y, world_z) = coord.unpack_to_worldpos();
+		let mut chunk = Chunk::empty();
+		let spread = ISLAND_SPREAD as i32;
+		if world_y + Chunk::SIZE_I <= ISLAND_Y - spread || world_y > ISLAND_Y + spread {
+			return chunk;
+		}
+		// three more above the chunk tell how deep under the top of the island a block is
+		let mut solid = [false; Chunk::SIZE + 3];
+		for x in 0..Chunk::SIZE {
+			for z in 0..Chunk::SIZE {
+				for (y, solid) in solid.iter_mut().enumerate() {
+					*solid = self.is_solid(world_x + x as i32, world_y + y as i32, world_z + z as i32);
+				}
+				for y in 0..Chunk::SIZE {
+					if !solid[y] {
+						continue;
+					}
+					let material = if !solid[y + 1] {
+						mat::DIRT_GRASS
+					} else if !solid[y + 2] || !solid[y + 3] {
+						mat::DIRT
+					} else {
+						mat::STONE
+					};
+					let idx: BlockPosition = (x, y, z).into();
+					chunk.set_block(idx.into(), Block::new(material));
+				}
+			}
+		}
+		chunk
+	}
+
+	fn surface_height(&self, x: i32, z: i32) -> Option<i32> {
+		let spread = ISLAND_SPREAD as i32;
+		let top = ((ISLAND_Y - spread)..=(ISLAND_Y + spread)).rev().find(|&y| self.is_solid(x, y, z));
+		Some(top.unwrap_or(i32::MIN))
+	}
+}
@@ -1,5 +1,7 @@
 
-use crate::world::manager::get_save_path;
+use crate::world::manager::{get_save_path, save_world_data, WorldData};
+use crate::world::generator::GeneratorSettings;
+use crate::world::import::HEIGHTMAP_FILE;
 use crate::ui::manager::UIState;
 use crate::game::state;
 use crate::network::api;
@@ -8,7 +10,10 @@ use crate::ext::ptr;
 pub fn join_world(world_name: &str) {
 	println!("Loading world: {}", world_name);
 
-	state::start_world(&world_name);
+	if let Err(e) = state::start_world(&world_name) {
+		ptr::get_state().ui_manager.dialogs.show_error(format!("Can't open {}: {}", world_name, e));
+		return;
+	}
 	let ui_manager = &mut ptr::get_state().ui_manager;
 	ui_manager.state = UIState::Loading;
 	ui_manager.setup_ui();
@@ -17,13 +22,29 @@ pub fn join_world(world_name: &str) {
 	ui_manager.setup_ui();
 }
 
-pub fn create_world(world_name: String) {
+pub fn create_world(world_name: String, generator: GeneratorSettings) {
 	// Create the save path
 	let save_path = get_save_path()
 		.join("saves")
 		.join(world_name);
 	
-	state::make_world(save_path);
+	state::make_world(save_path.clone());
+	if save_path.join("world_data.dat").exists() {
+		return; // the world is already there, its generator stays as it was
+	}
+	let mut data = WorldData::new();
+	data.generator = generator;
+	// the world keeps its own copy of the heightmap, it still loads when the picked image is moved
+	if let Some(heightmap) = &mut data.generator.heightmap {
+		let copy = save_path.join(HEIGHTMAP_FILE);
+		match std::fs::copy(&heightmap.path, &copy) {
+			Ok(_) => heightmap.path = copy.to_string_lossy().into_owned(),
+			Err(e) => println!("Error copying the heightmap {}: {}", heightmap.path, e),
+		}
+	}
+	if let Err(e) = save_world_data(&save_path, &data) {
+		println!("Error saving world data: {}", e);
+	}
 }
 
 pub fn join_local_world(world_name: &str) {
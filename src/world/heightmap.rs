@@ -0,0 +1,99 @@
+use crate::block::main::Chunk;
+use crate::world::terrain::Column;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+/// Column maps kept at most, about 3 KB each
+pub const CACHE_CAPACITY: usize = 1024;
+
+/// The ground of every column of one chunk column, the same for every chunk stacked in it
+#[derive(Clone, Debug, PartialEq)]
+pub struct ColumnMap {
+	columns: Vec<Column>, // x * SIZE + z
+}
+
+impl ColumnMap {
+	/// Works out the columns of the chunk column from the columns in world space
+	pub fn new(chunk_x: i32, chunk_z: i32, column: impl Fn(i32, i32) -> Column) -> Self {
+		let (world_x, world_z) = (chunk_x * Chunk::SIZE_I, chunk_z * Chunk::SIZE_I);
+		let columns = (0..Chunk::SIZE_I)
+			.flat_map(|x| (0..Chunk::SIZE_I).map(move |z| (x, z)))
+			.map(|(x, z)| column(world_x + x, world_z + z))
+			.collect();
+		Self { columns }
+	}
+
+	/// The column at the position inside the chunk column
+	#[inline] pub fn get(&self, local_x: usize, local_z: usize) -> Column {
+		self.columns[local_x * Chunk::SIZE + local_z]
+	}
+
+	/// The top of the highest ground in the chunk column
+	#[inline] pub fn highest(&self) -> i32 {
+		self.columns.iter().map(|column| column.height).max().unwrap_or(i32::MIN)
+	}
+}
+
+/// The column maps of the chunk columns used lately, shared by the chunks stacked in them,
+/// the features placed across their borders and the sky heights of the columns
+/// When it is full the ones unused the longest go
+#[derive(Default)]
+pub struct ColumnCache {
+	maps: Mutex<CacheMaps>,
+}
+
+#[derive(Default)]
+struct CacheMaps {
+	maps: HashMap<(i32, i32), (Arc<ColumnMap>, u64)>, // chunk column -> map, when it was used last
+	uses: u64,
+}
+
+impl std::fmt::Debug for ColumnCache {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.debug_struct("ColumnCache")
+			.field("len", &self.len())
+			.finish()
+	}
+}
+
+#[allow(dead_code)]
+impl ColumnCache {
+	#[inline] pub fn new() -> Self {
+		Self::default()
+	}
+
+	#[inline] pub fn len(&self) -> usize {
+		self.maps.lock().unwrap().maps.len()
+	}
+	#[inline] pub fn is_empty(&self) -> bool {
+		self.len() == 0
+	}
+
+	/// The map of the chunk column, made with `make` if it isn't cached
+	/// It is made outside of the lock, two threads may both make it but only one is kept
+	pub fn get_or_make(&self, chunk_x: i32, chunk_z: i32, make: impl FnOnce() -> ColumnMap) -> Arc<ColumnMap> {
+		{
+			let mut cache = self.maps.lock().unwrap();
+			cache.uses += 1;
+			let uses = cache.uses;
+			if let Some((map, used)) = cache.maps.get_mut(&(chunk_x, chunk_z)) {
+				*used = uses;
+				return Arc::clone(map);
+			}
+		}
+		let map = Arc::new(make());
+		let mut cache = self.maps.lock().unwrap();
+		if cache.maps.len() >= CACHE_CAPACITY {
+			// a quarter at once, so the oldest aren't looked for on every new map
+			let mut used: Vec<u64> = cache.maps.values().map(|(_, used)| *used).collect();
+			let cutoff = *used.select_nth_unstable(CACHE_CAPACITY / 4).1;
+			cache.maps.retain(|_, (_, used)| *used > cutoff);
+		}
+		let uses = cache.uses;
+		Arc::clone(&cache.maps.entry((chunk_x, chunk_z)).or_insert((map, uses)).0)
+	}
+
+	pub fn clear(&self) {
+		self.maps.lock().unwrap().maps.clear();
+	}
+}
@@ -0,0 +1,263 @@
+use crate::block::main::{Block, Chunk, Material};
+use crate::block::math::{BlockPosition, ChunkCoord};
+use crate::block::props::{self, mat};
+use crate::world::generator::WorldGenerator;
+use crate::world::main::World;
+use glam::IVec3;
+use image::ImageReader;
+use std::io::{Error, ErrorKind, Result};
+use std::path::Path;
+
+/// Black pixels of an imported heightmap are the ground at this height, brighter ones stand on top of it
+pub const HEIGHTMAP_BASE: i32 = -1;
+/// The copy of the heightmap a world keeps in its folder
+pub const HEIGHTMAP_FILE: &str = "heightmap.png";
+/// Gray levels of a pixel, the vertical scale is in blocks per level
+const GRAY_LEVELS: f32 = 255.0;
+
+/// A grayscale image read as heights, 0 for black to 1 for white
+#[derive(Clone, PartialEq)]
+pub struct HeightImage {
+	width: u32,
+	height: u32,
+	pixels: Vec<f32>, // row by row
+}
+
+impl std::fmt::Debug for HeightImage {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.debug_struct("HeightImage")
+			.field("width", &self.width)
+			.field("height", &self.height)
+			.finish()
+	}
+}
+
+#[allow(dead_code)]
+impl HeightImage {
+	/// The pixels go row by row, there have to be `width * height` of them
+	pub fn new(width: u32, height: u32, pixels: Vec<f32>) -> Option<Self> {
+		(pixels.len() == width as usize * height as usize).then_some(Self { width, height, pixels })
+	}
+
+	/// Reads a PNG (or JPEG) as grayscale, 16 bit images keep their precision
+	/// The format comes from the contents, not the name (the copy a world keeps is always called .png)
+	pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+		let image = ImageReader::open(path.as_ref())?
+			.with_guessed_format()?
+			.decode()
+			.map_err(|e| Error::new(ErrorKind::InvalidData, format!("Invalid heightmap {:?}: {}", path.as_ref(), e)))?
+			.into_luma16();
+		let pixels = image.pixels().map(|pixel| pixel.0[0] as f32 / u16::MAX as f32).collect();
+		Ok(Self { width: image.width(), height: image.height(), pixels })
+	}
+
+	#[inline] pub const fn width(&self) -> u32 { self.width }
+	#[inline] pub const fn height(&self) -> u32 { self.height }
+
+	/// The pixel, black outside of the image
+	#[inline] fn pixel(&self, x: i32, y: i32) -> f32 {
+		if x < 0 || y < 0 || x >= self.width as i32 || y >= self.height as i32 {
+			return 0.0;
+		}
+		self.pixels[y as usize * self.width as usize + x as usize]
+	}
+
+	/// The value between the pixels around the point (in pixels, the center of the first one is at 0, 0)
+	pub fn sample(&self, x: f32, y: f32) -> f32 {
+		let (left, top) = (x.floor(), y.floor());
+		let (tx, ty) = (x - left, y - top);
+		let (left, top) = (left as i32, top as i32);
+		let upper = self.pixel(left, top) * (1.0 - tx) + self.pixel(left + 1, top) * tx;
+		let lower = self.pixel(left, top + 1) * (1.0 - tx) + self.pixel(left + 1, top + 1) * tx;
+		upper * (1.0 - ty) + lower * ty
+	}
+}
+
+/// How a heightmap image is turned into ground, saved with the world using it
+#[derive(Clone, Debug, PartialEq)]
+pub struct HeightmapSettings {
+	pub path: String,
+	pub horizontal_scale: f32, // blocks per pixel
+	pub vertical_scale: f32, // blocks per gray level, white is 255 levels up
+	pub material: Material, // the whole ground is made of it
+}
+
+impl HeightmapSettings {
+	/// A block per pixel and white 64 blocks up, out of stone
+	pub fn new(path: impl Into<String>) -> Self {
+		Self { path: path.into(), horizontal_scale: 1.0, vertical_scale: 0.25, material: mat::STONE }
+	}
+
+	/// Reads "path, horizontal scale, vertical scale, material", everything after the path can be left out
+	/// None if a scale isn't a positive number or the material is unknown
+	pub fn parse(text: &str) -> Option<Self> {
+		let mut parts = text.split(',').map(str::trim);
+		let mut settings = Self::new(parts.next().filter(|path| !path.is_empty())?);
+		if let Some(scale) = parts.next() {
+			settings.horizontal_scale = scale.parse().ok().filter(|scale: &f32| *scale > 0.0)?;
+		}
+		if let Some(scale) = parts.next() {
+			settings.vertical_scale = scale.parse().ok().filter(|scale: &f32| *scale > 0.0)?;
+		}
+		if let Some(name) = parts.next() {
+			settings.material = props::by_name(name)?;
+		}
+		Some(settings)
+	}
+
+	/// The height of the ground over the black level at the point of the image (in blocks from its corner)
+	pub fn height_at(&self, image: &HeightImage, x: f32, z: f32) -> i32 {
+		let value = image.sample(x / self.horizontal_scale - 0.5, z / self.horizontal_scale - 0.5);
+		(value * GRAY_LEVELS * self.vertical_scale).round() as i32
+	}
+
+	/// The height of white over the black level
+	#[inline] pub fn max_height(&self) -> i32 {
+		(GRAY_LEVELS * self.vertical_scale).round() as i32
+	}
+
+	/// The image size in blocks
+	#[inline] pub fn size(&self, image: &HeightImage) -> (i32, i32) {
+		((image.width as f32 * self.horizontal_scale).ceil() as i32, (image.height as f32 * self.horizontal_scale).ceil() as i32)
+	}
+
+	pub fn to_bytes(&self) -> Vec<u8> {
+		let mut bytes = Vec::with_capacity(12 + self.path.len());
+		bytes.extend_from_slice(&(self.path.len() as u16).to_le_bytes());
+		bytes.extend_from_slice(self.path.as_bytes());
+		bytes.extend_from_slice(&self.horizontal_scale.to_le_bytes());
+		bytes.extend_from_slice(&self.vertical_scale.to_le_bytes());
+		bytes.extend_from_slice(&self.material.to_le_bytes());
+		bytes
+	}
+
+	/// The settings and the bytes they took
+	pub fn from_bytes(bytes: &[u8]) -> Result<(Self, usize)> {
+		let invalid = || Error::new(ErrorKind::InvalidData, "Invalid heightmap settings");
+		let len = u16::from_le_bytes([*bytes.first().ok_or_else(invalid)?, *bytes.get(1).ok_or_else(invalid)?]) as usize;
+		let rest = bytes.get(2..).ok_or_else(invalid)?;
+		if rest.len() < len + 10 {
+			return Err(invalid());
+		}
+		let path = String::from_utf8(rest[..len].to_vec()).map_err(|_| invalid())?;
+		let rest = &rest[len..];
+		let settings = Self {
+			path,
+			horizontal_scale: f32::from_le_bytes([rest[0], rest[1], rest[2], rest[3]]),
+			vertical_scale: f32::from_le_bytes([rest[4], rest[5], rest[6], rest[7]]),
+			material: u16::from_le_bytes([rest[8], rest[9]]),
+		};
+		Ok((settings, 12 + len))
+	}
+}
+
+/// The ground of a heightmap image centered on the spawn, flat at the black level around it
+#[derive(Clone, Debug)]
+pub struct HeightmapGen {
+	image: HeightImage,
+	settings: HeightmapSettings,
+}
+
+impl HeightmapGen {
+	pub const fn new(image: HeightImage, settings: HeightmapSettings) -> Self {
+		Self { image, settings }
+	}
+
+	/// Loads the image of the settings
+	pub fn load(settings: HeightmapSettings) -> Result<Self> {
+		Ok(Self::new(HeightImage::load(&settings.path)?, settings))
+	}
+
+	/// The top block of the column
+	pub fn height_at(&self, x: i32, z: i32) -> i32 {
+		let (width, depth) = self.settings.size(&self.image);
+		HEIGHTMAP_BASE + self.settings.height_at(&self.image, (x + width / 2) as f32 + 0.5, (z + depth / 2) as f32 + 0.5)
+	}
+}
+
+impl WorldGenerator for HeightmapGen {
+	fn generate(&self, coord: ChunkCoord) -> Chunk {
+		let (world_x, world_y, world_z) = coord.unpack_to_worldpos();
+		if world_y > HEIGHTMAP_BASE + self.settings.max_height() {
+			return Chunk::empty();
+		}
+		if world_y + Chunk::SIZE_I - 1 <= HEIGHTMAP_BASE {
+			return Chunk::new(self.settings.material);
+		}
+		let mut chunk = Chunk::empty();
+		for x in 0..Chunk::SIZE {
+			for z in 0..Chunk::SIZE {
+				let top = self.height_at(world_x + x as i32, world_z + z as i32) - world_y;
+				for y in 0..=top.min(Chunk::SIZE_I - 1) {
+					let idx: BlockPosition = (x, y as usize, z).into();
+					chunk.set_block(idx.into(), Block::new(self.settings.material));
+				}
+			}
+		}
+		chunk
+	}
+
+	fn surface_height(&self, x: i32, z: i32) -> Option<i32> {
+		Some(if props::get(self.settings.material).is_opaque() { self.height_at(x, z) } else { i32::MIN })
+	}
+}
+
+/// Reads "x, y, z", where a heightmap gets stamped
+pub fn parse_origin(text: &str) -> Option<IVec3> {
+	let mut parts = text.split(',').map(|part| part.trim().parse::<i32>().ok());
+	let origin = IVec3::new(parts.next()??, parts.next()??, parts.next()??);
+	parts.next().is_none().then_some(origin)
+}
+
+/// Loads the image of the settings and stamps it into the world with its corner at `origin`
+pub fn stamp_file(world: &mut World, settings: &HeightmapSettings, origin: IVec3) -> Result<()> {
+	let image = HeightImage::load(&settings.path)?;
+	stamp(world, &image, settings, origin);
+	Ok(())
+}
+
+/// Stamps the heightmap into the world with its corner at `origin`: the columns it covers are filled with the material
+/// up to their height over `origin.y`, and emptied above that up to the height of white
+/// The chunks change all at once, their light is worked out again afterwards (missing ones are made empty first)
+pub fn stamp(world: &mut World, image: &HeightImage, settings: &HeightmapSettings, origin: IVec3) {
+	let (width, depth) = settings.size(image);
+	let top = origin + IVec3::new(width - 1, settings.max_height(), depth - 1);
+	let (low, high) = (ChunkCoord::from_world_pos(origin), ChunkCoord::from_world_pos(top));
+	let mut touched = Vec::new();
+	// top down, a chunk covering an already lit one would make it relight
+	for cy in (low.y()..=high.y()).rev() {
+		for cx in low.x()..=high.x() {
+			for cz in low.z()..=high.z() {
+				let coord = ChunkCoord::new(cx, cy, cz);
+				if world.get_chunk(coord).is_none() {
+					world.set_chunk(coord, Chunk::empty());
+				}
+				let Some(chunk) = world.get_chunk_mut(coord) else { continue; };
+				let (world_x, world_y, world_z) = coord.unpack_to_worldpos();
+				let chunk_min = IVec3::new(world_x, world_y, world_z);
+				let min = origin.max(chunk_min) - chunk_min;
+				let max = top.min(chunk_min + IVec3::splat(Chunk::SIZE_I - 1)) - chunk_min;
+				for x in min.x..=max.x {
+					for z in min.z..=max.z {
+						let (dx, dz) = (world_x + x - origin.x, world_z + z - origin.z);
+						let height = origin.y + settings.height_at(image, dx as f32 + 0.5, dz as f32 + 0.5);
+						for y in min.y..=max.y {
+							let block = if world_y + y <= height { Block::new(settings.material) } else { Block::None };
+							let idx: usize = BlockPosition::new(x as u8, y as u8, z as u8).into();
+							chunk.set_block(idx, block);
+						}
+					}
+				}
+				touched.push(coord);
+			}
+		}
+	}
+	for coord in touched {
+		world.light_chunk(coord);
+		for neighbor in coord.neighbors() {
+			if let Some(chunk) = world.get_chunk_mut(neighbor) {
+				chunk.final_mesh = false;
+			}
+		}
+	}
+}
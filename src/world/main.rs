@@ -1,12 +1,21 @@
 
-use crate::ext::ptr;
 use crate::block::math::{BlockPosition, ChunkCoord};
 use crate::block::main::{Block, Chunk};
+use crate::block::entity::BlockEntity;
+use crate::world::tick::TickScheduler;
+use crate::world::falling::FallingBlock;
+use crate::world::dropped::DroppedItem;
+use crate::world::explosion::Explosion;
+use crate::world::neighbor::NeighborUpdates;
+use crate::world::generator::{VoidGen, WorldGenerator};
+use crate::block::light::SkyMap;
+use crate::world::workers::{ChunkWorkers, MAX_INSERTS_PER_UPDATE};
 use ahash::AHasher;
 use glam::{IVec3, Vec3};
 use std::{
 	collections::{HashMap, HashSet},
 	hash::BuildHasherDefault,
+	sync::Arc,
 };
 
 // Type aliases for better readability
@@ -17,17 +26,33 @@ type FastMap<K, V> = HashMap<K, V, BuildHasherDefault<AHasher>>;
 pub struct World {
 	pub chunks: FastMap<ChunkCoord, Chunk>,
 	pub loaded_chunks: HashSet<ChunkCoord>,
+	pub ticks: TickScheduler,
+	pub neighbor_updates: NeighborUpdates,
+	pub falling: Vec<FallingBlock>, // blocks on their way down, placed back when they land
+	pub dropped: Vec<DroppedItem>, // item stacks lying around, waiting to be picked up
+	pub explosions: Vec<Explosion>, // blown up since the game last pushed the player away
+	pub generator: Arc<dyn WorldGenerator>, // makes the chunks that are not loaded from the save
+	pub workers: ChunkWorkers, // runs the generator in the background for the chunks coming into range
+	pub sky: SkyMap, // how far down the sky reaches in every column
 }
 
 #[allow(dead_code)]
 impl World {
-	/// Creates an empty world
+	/// Creates an empty world, nothing but the void is generated until a generator is picked
 	#[inline]
 	pub fn empty() -> Self {
 
 		Self {
 			chunks: FastMap::with_capacity_and_hasher(10_000, BuildHasherDefault::<AHasher>::default()),
 			loaded_chunks: HashSet::with_capacity(10_000),
+			ticks: TickScheduler::new(),
+			neighbor_updates: NeighborUpdates::new(),
+			falling: Vec::new(),
+			dropped: Vec::new(),
+			explosions: Vec::new(),
+			generator: Arc::new(VoidGen),
+			workers: ChunkWorkers::new(),
+			sky: SkyMap::new(),
 		}
 	}
 
@@ -68,19 +93,39 @@ impl World {
 				&mut chunk.palette[palette_idx as usize]
 			})
 	}
+	#[inline]
+	pub fn get_block_entity(&self, world_pos: IVec3) -> Option<&BlockEntity> {
+		let local_pos: BlockPosition = world_pos.into();
+		self.chunks
+			.get(&ChunkCoord::from_world_pos(world_pos))
+			.and_then(|chunk| chunk.get_entity(local_pos.into()))
+	}
+
+	#[inline]
+	pub fn get_block_entity_mut(&mut self, world_pos: IVec3) -> Option<&mut BlockEntity> {
+		let local_pos: BlockPosition = world_pos.into();
+		self.chunks
+			.get_mut(&ChunkCoord::from_world_pos(world_pos))
+			.and_then(|chunk| chunk.get_entity_mut(local_pos.into()))
+	}
+
 	#[inline]
 	pub fn set_block(&mut self, world_pos: IVec3, block: Block) {
 		let chunk_coord = ChunkCoord::from_world_pos(world_pos);
 		
 		// Get immutable access first to check conditions
 		let needs_new_chunk = !self.chunks.contains_key(&chunk_coord);
-		let is_border_block = self.get_chunk(chunk_coord)
-			.map(|chunk| chunk.is_border_block(world_pos.into()))
-			.unwrap_or(false);
 		
 		// Only get mutable access if we actually need to modify
 		let chunk = if needs_new_chunk {
-			self.set_chunk(chunk_coord, Chunk::empty());
+			if self.workers.keep_edits(chunk_coord) {
+				// still being generated, the block waits in a stand-in and goes into the generated chunk once it is done
+				self.chunks.insert(chunk_coord, Chunk::empty());
+				self.loaded_chunks.insert(chunk_coord);
+				self.light_chunk(chunk_coord);
+			} else {
+				self.set_chunk(chunk_coord, Chunk::empty());
+			}
 			self.get_chunk_mut(chunk_coord).expect("Chunk should exist after insertion")
 		} else {
 			self.get_chunk_mut(chunk_coord).expect("Chunk should exist")
@@ -90,16 +135,69 @@ impl World {
 		let index: usize = local_pos.into();
 		
 		// Only proceed if the block is actually different
-		if chunk.get_block(index) != &block {
-			chunk.set_block(index, block);
-			if is_border_block {
-				for coord in chunk_coord.get_adjacent().iter() {
-					if let Some(neighbor_chunk) = self.get_chunk_mut(*coord) {
+		let old_block = *chunk.get_block(index);
+		if old_block == block {
+			return;
+		}
+		chunk.set_block(index, block);
+		self.block_changed(world_pos, &old_block, &block);
+	}
+	/// Removes every block at the positions at once, returns the ones actually removed
+	/// The light, the meshes and the neighbors only follow once all of them are gone (nothing reacts to half an explosion)
+	pub fn remove_blocks(&mut self, positions: &[IVec3]) -> Vec<(IVec3, Block)> {
+		let mut removed = Vec::with_capacity(positions.len());
+		for &world_pos in positions {
+			let index: usize = BlockPosition::from(world_pos).into();
+			let Some(chunk) = self.get_chunk_mut(ChunkCoord::from_world_pos(world_pos)) else { continue; };
+			let old_block = *chunk.get_block(index);
+			if old_block.is_empty() {
+				continue;
+			}
+			chunk.set_block(index, Block::None);
+			removed.push((world_pos, old_block));
+		}
+		for (world_pos, old_block) in &removed {
+			self.block_changed(*world_pos, old_block, &Block::None);
+		}
+		removed
+	}
+	/// Everything that follows a block change: light, remeshing, ticks and neighbor updates
+	fn block_changed(&mut self, world_pos: IVec3, old_block: &Block, block: &Block) {
+		self.update_light(world_pos, old_block, block);
+		// Only the chunks actually touching the block have to remesh (diagonally too, for the ambient occlusion)
+		let chunk_coord = ChunkCoord::from_world_pos(world_pos);
+		let low = ChunkCoord::from_world_pos(world_pos - IVec3::ONE);
+		let high = ChunkCoord::from_world_pos(world_pos + IVec3::ONE);
+		for x in low.x()..=high.x() {
+			for y in low.y()..=high.y() {
+				for z in low.z()..=high.z() {
+					let coord = ChunkCoord::new(x, y, z);
+					if coord == chunk_coord {
+						continue;
+					}
+					if let Some(neighbor_chunk) = self.get_chunk_mut(coord) {
 						neighbor_chunk.final_mesh = false;
 					}
 				}
 			}
 		}
+		// A block reacting to its surroundings (falling, flowing ...) gets a tick to look around, the blocks next to it hear about the change
+		// The block itself gets an update too, a freshly placed wire picks up the signal around it
+		let props = block.props();
+		if props.scheduled_tick.is_some() {
+			self.schedule_tick(world_pos, props.tick_delay);
+		}
+		self.neighbor_updates.push(world_pos, world_pos);
+		self.notify_neighbors(world_pos);
+	}
+	/// Changes the state of the block at the position, like `set_block` the neighbors hear about it
+	#[inline]
+	pub fn set_block_state(&mut self, world_pos: IVec3, state: u8) {
+		let mut block = *self.get_block(world_pos);
+		if block.state() != state {
+			block.set_state(state);
+			self.set_block(world_pos, block);
+		}
 	}
 	#[inline]
 	/// Loads a new chunk
@@ -111,15 +209,24 @@ impl World {
 			chunk_coord,
 			chunk
 		);
+		self.light_chunk(chunk_coord);
 	}
 	#[inline]
-	/// Loads a chunk from storage
-	pub fn generate_chunk(&mut self, chunk_coord: ChunkCoord, seed: u32) {
-		let mut chunk = match Chunk::generate(chunk_coord, seed) {
-			Some(c) => c,
-			_ => Chunk::empty(),
-		};
-
+	/// Generates a chunk with the world's generator, right away
+	pub fn generate_chunk(&mut self, chunk_coord: ChunkCoord) {
+		let chunk = self.generator.generate(chunk_coord);
+		self.insert_generated_chunk(chunk_coord, chunk);
+	}
+	/// Puts a chunk the workers finished into the world, with the blocks set in it while it was generated
+	pub fn insert_finished_chunk(&mut self, chunk_coord: ChunkCoord, mut chunk: Chunk) {
+		let edited = self.workers.take_edits(chunk_coord);
+		if let Some(stand_in) = self.get_chunk(chunk_coord).filter(|_| edited) {
+			chunk.overlay(stand_in);
+		}
+		self.insert_generated_chunk(chunk_coord, chunk);
+	}
+	/// Puts a generated chunk into the world, replacing the one there
+	fn insert_generated_chunk(&mut self, chunk_coord: ChunkCoord, mut chunk: Chunk) {
 		self.loaded_chunks.insert(chunk_coord);
 		
 		if let Some(m_chunk) = self.get_chunk_mut(chunk_coord) {
@@ -129,8 +236,10 @@ impl World {
 		
 		// This will replace the existing entry or insert a new one
 		self.chunks.insert(chunk_coord, chunk);
+		self.light_chunk(chunk_coord);
 	}
 	/// Updates loaded chunks based on player position
+	/// The missing ones are generated in the background and put in once they are done, the nearest first
 	pub fn update_loaded_chunks(&mut self, center: Vec3, radius: f32, force: bool) {
 		let center_coord = ChunkCoord::from_world_posf(center);
 		let (center_x, center_y, center_z) = center_coord.unpack();
@@ -153,31 +262,42 @@ impl World {
 		for coord in to_unload {
 			self.unload_chunk(coord);
 		}
-		let seed = *ptr::get_gamestate().seed();
-		// Load new chunks in range
+		self.sky.retain_near(center_x, center_z, radius_sq);
+		// The chunks that went out of range are not worth finishing
+		self.workers.retarget(center_coord, radius_sq);
+		// Ask for the chunks in range
 		for dx in -radius_i32..=radius_i32 {
-			for dy in -radius_i32..=radius_i32 {
+			for dy in (-radius_i32..=radius_i32).rev() {
 				for dz in -radius_i32..=radius_i32 {
 					if dx * dx + dy * dy + dz * dz > radius_sq {
 						continue;
 					}
 
 					let coord = ChunkCoord::new(center_x + dx, center_y + dy, center_z + dz);
-					if force || !self.loaded_chunks.contains(&coord) {
-						self.generate_chunk(coord, seed);
-						self.create_bind_group(coord);
+					if (force || !self.loaded_chunks.contains(&coord)) && !self.workers.is_pending(coord) {
+						self.workers.request(coord, &self.generator);
 					}
 				}
 			}
 		}
+		// Put in the chunks generated since the last update (top down, a chunk covering an already lit one would make it relight)
+		let mut finished = self.workers.finished(MAX_INSERTS_PER_UPDATE);
+		finished.sort_by_key(|(coord, _)| -coord.y());
+		for (coord, chunk) in finished {
+			self.insert_finished_chunk(coord, chunk);
+			self.create_bind_group(coord);
+		}
 	}
 
 	#[inline] pub fn set_chunk(&mut self, chunk_coord: ChunkCoord, chunk: Chunk) {
+		self.workers.cancel(chunk_coord);
 		self.chunks.insert(chunk_coord, chunk);
 		self.loaded_chunks.insert(chunk_coord);
+		self.light_chunk(chunk_coord);
 	}
 
 	#[inline] pub fn unload_chunk(&mut self, chunk_coord: ChunkCoord) {
+		self.settle_falling_blocks_in(chunk_coord);
 		self.chunks.remove(&chunk_coord);
 		self.loaded_chunks.remove(&chunk_coord);
 	}
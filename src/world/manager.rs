@@ -1,13 +1,27 @@
 
 use crate::world::main::World;
+use crate::world::generator::GeneratorSettings;
 use crate::block::math::{BlockRotation, ChunkCoord};
 use crate::block::main::{Block, Chunk, BlockStorage};
+use crate::block::entity::BlockEntity;
+use crate::block::light::LightStorage;
+use crate::game::inventory::ItemContainer;
+use crate::game::items::{Item, ItemData, ItemStack, ToolFlags};
+use std::collections::HashMap;
 use crate::ext::ptr;
 use crate::hs::time;
 use std::path::{Path, PathBuf};
 use std::io::{self, Read, Write, Result, Error, ErrorKind};
 use std::fs::{self, File};
 use std::mem;
+use std::sync::Arc;
+
+/// The format chunks are saved in, 0 is from before block entities
+pub const CHUNK_FORMAT: u8 = 1;
+/// The first format with block entities after the blocks
+const CHUNK_FORMAT_ENTITIES: u8 = 1;
+/// Starts the world file in place of the chunk count, followed by the chunk format (no world has that many chunks)
+const WORLD_FORMAT_MARKER: u32 = u32::MAX;
 
 #[inline]
 pub fn get_save_path() -> PathBuf {
@@ -97,6 +111,7 @@ pub struct WorldData {
 	pub version: String,
 	pub creation_date: time::Time,
 	pub last_opened_date: time::Time,
+	pub generator: GeneratorSettings, // how the chunks of the world are generated
 }
 
 impl WorldData {
@@ -105,6 +120,7 @@ impl WorldData {
 			version: std::env!("CARGO_PKG_VERSION").to_string(),
 			creation_date: time::Time::now(),
 			last_opened_date: time::Time::now(),
+			generator: GeneratorSettings::default(),
 		}
 	}
 	
@@ -134,6 +150,9 @@ impl WorldData {
 		// Write time fields
 		bytes.extend_from_slice(&creation_bytes);
 		bytes.extend_from_slice(&last_opened_bytes);
+
+		// Write the generator settings
+		bytes.extend_from_slice(&self.generator.to_bytes());
 		
 		bytes
 	}
@@ -169,11 +188,20 @@ impl WorldData {
 			bytes[cursor+4], bytes[cursor+5], bytes[cursor+6], bytes[cursor+7],
 			bytes[cursor+8], bytes[cursor+9]
 		]);
+		cursor += 10;
+
+		// Worlds saved before the generator settings were made with the default generator
+		let generator = if cursor < bytes.len() {
+			GeneratorSettings::from_bytes(&bytes[cursor..])?
+		} else {
+			GeneratorSettings::default()
+		};
 		
 		Ok(WorldData {
 			version,
 			creation_date,
 			last_opened_date,
+			generator,
 		})
 	}
 }
@@ -253,11 +281,24 @@ impl Chunk {
 			}
 		}
 		
+		// 3. Write block entities (index + entity data)
+		data.extend_from_slice(&(self.entities.len() as u16).to_le_bytes());
+		for (index, entity) in &self.entities {
+			data.extend_from_slice(&index.to_le_bytes());
+			data.extend_from_slice(&entity.to_binary());
+		}
+		
 		data
 	}
 	
 	/// Deserializes the chunk from binary format
+	/// A chunk saved before block entities ends right after its blocks, it has none
 	pub fn from_binary(bytes: &[u8]) -> Option<Self> {
+		Self::read_binary(bytes, CHUNK_FORMAT).map(|(chunk, _)| chunk)
+	}
+
+	/// Deserializes a chunk saved in the given format, along with the bytes it took
+	fn read_binary(bytes: &[u8], format: u8) -> Option<(Self, usize)> {
 		let mut offset = 0;
 		// 1. Read palette (with bounds checks)
 		let palette_len = bytes.get(0)?;
@@ -309,15 +350,45 @@ impl Chunk {
 			}
 			_ => return None,
 		};
+		offset += if *storage_type == 0 { 1 } else { 4096 };
+		
+		// 3. Read block entities (with bounds checks)
+		let entity_count = if format < CHUNK_FORMAT_ENTITIES || offset == bytes.len() {
+			0
+		} else {
+			if offset + 2 > bytes.len() {
+				return None;
+			}
+			offset += 2;
+			u16::from_le_bytes([bytes[offset-2], bytes[offset-1]])
+		};
+		let mut entities = HashMap::with_capacity(entity_count as usize);
+		for _ in 0..entity_count {
+			if offset + 2 > bytes.len() {
+				return None;
+			}
+			let index = u16::from_le_bytes([bytes[offset], bytes[offset+1]]);
+			offset += 2;
+			if index as usize >= Chunk::VOLUME {
+				return None;
+			}
+			let entity = BlockEntity::from_binary(&bytes[offset..])?;
+			offset += entity.binary_size();
+			entities.insert(index, entity);
+		}
 		
-		Some(Chunk {
+		let chunk = Chunk {
 			palette,
 			storage,
+			entities,
+			light: LightStorage::Uniform(0),
 			dirty: true,
 			final_mesh: false,
 			mesh: None,
+			translucent_mesh: None,
 			bind_group: None,
-		})
+		};
+		Some((chunk, offset))
 	}
 
 	/// Returns the size of the binary representation
@@ -336,6 +407,12 @@ impl Chunk {
 			BlockStorage::Sparse(_) => 1 + 4096, // type marker + full array
 		};
 		
+		// Block entities (count + index and data for each)
+		size += 2;
+		for entity in self.entities.values() {
+			size += 2 + entity.binary_size();
+		}
+		
 		size
 	}
 }
@@ -379,6 +456,13 @@ impl Block {
 				data.push(rotation.to_byte());
 				data
 			}
+			Block::Stateful(material, rotation, state) => {
+				let mut data = vec![2];
+				data.extend_from_slice(&material.to_le_bytes());
+				data.push(rotation.to_byte());
+				data.push(*state);
+				data
+			}
 		}
 	}
 	
@@ -395,6 +479,12 @@ impl Block {
 				let rotation = BlockRotation::from_byte(bytes[3])?;
 				Some(Block::Simple(material, rotation))
 			}
+			2 => {
+				if bytes.len() < 5 { return None; }
+				let material = u16::from_le_bytes([bytes[1], bytes[2]]);
+				let rotation = BlockRotation::from_byte(bytes[3])?;
+				Some(Block::Stateful(material, rotation, bytes[4]))
+			}
 			_ => None,
 		}
 	}
@@ -404,15 +494,224 @@ impl Block {
 		match self {
 			Block::None => 1,
 			Block::Simple(_, _) => 1 + mem::size_of::<u16>() + 1,
+			Block::Stateful(_, _, _) => 1 + mem::size_of::<u16>() + 1 + 1,
 		}
 	}
 }
 
+impl BlockEntity {
+	/// Serializes the block entity to a binary format
+	pub fn to_binary(&self) -> Vec<u8> {
+		match self {
+			BlockEntity::Chest(container) => {
+				let mut data = vec![0];
+				data.extend_from_slice(&container.to_binary());
+				data
+			}
+		}
+	}
+	
+	/// Deserializes the block entity from binary format
+	pub fn from_binary(bytes: &[u8]) -> Option<Self> {
+		match bytes.get(0)? {
+			0 => Some(BlockEntity::Chest(ItemContainer::from_binary(&bytes[1..])?)),
+			_ => None,
+		}
+	}
+	
+	/// Returns the size of the binary representation
+	pub fn binary_size(&self) -> usize {
+		match self {
+			BlockEntity::Chest(container) => 1 + container.binary_size(),
+		}
+	}
+}
+
+impl ItemContainer {
+	/// Serializes the container to a binary format (dimensions + every slot)
+	pub fn to_binary(&self) -> Vec<u8> {
+		let mut data = vec![self.rows(), self.cols()];
+		for slot in self.iter() {
+			match slot {
+				None => data.push(0),
+				Some(stack) => {
+					data.push(1);
+					data.extend_from_slice(&stack.to_binary());
+				}
+			}
+		}
+		data
+	}
+	
+	/// Deserializes the container from binary format
+	pub fn from_binary(bytes: &[u8]) -> Option<Self> {
+		if bytes.len() < 2 { return None; }
+		let mut container = ItemContainer::new(bytes[0], bytes[1]);
+		let mut offset = 2;
+		for index in 0..container.capacity() {
+			match bytes.get(offset)? {
+				0 => offset += 1,
+				1 => {
+					let stack = ItemStack::from_binary(&bytes[offset+1..])?;
+					offset += 1 + stack.binary_size();
+					container.set(index, Some(stack));
+				}
+				_ => return None,
+			}
+		}
+		Some(container)
+	}
+	
+	/// Returns the size of the binary representation
+	pub fn binary_size(&self) -> usize {
+		2 + self.iter()
+			.map(|slot| 1 + slot.as_ref().map_or(0, |stack| stack.binary_size()))
+			.sum::<usize>()
+	}
+}
+
+impl ItemStack {
+	/// Serializes the stack to a binary format
+	pub fn to_binary(&self) -> Vec<u8> {
+		let (kind, id) = match self.item {
+			Item::Block(id) => (0u8, id),
+			Item::Item(id) => (1u8, id),
+		};
+		let mut data = vec![kind];
+		data.extend_from_slice(&id.to_le_bytes());
+		data.push(self.quantity);
+		match &self.data {
+			None => data.push(0),
+			Some(item_data) => {
+				data.push(1);
+				data.extend_from_slice(&item_data.to_binary());
+			}
+		}
+		data
+	}
+	
+	/// Deserializes the stack from binary format
+	pub fn from_binary(bytes: &[u8]) -> Option<Self> {
+		if bytes.len() < 5 { return None; }
+		let id = u16::from_le_bytes([bytes[1], bytes[2]]);
+		let item = match bytes[0] {
+			0 => Item::Block(id),
+			1 => Item::Item(id),
+			_ => return None,
+		};
+		let data = match bytes[4] {
+			0 => None,
+			1 => Some(Box::new(ItemData::from_binary(&bytes[5..])?)),
+			_ => return None,
+		};
+		Some(ItemStack { item, quantity: bytes[3], data })
+	}
+	
+	/// Returns the size of the binary representation
+	pub fn binary_size(&self) -> usize {
+		1 + mem::size_of::<u16>() + 1 + 1 + self.data.as_ref().map_or(0, |data| data.binary_size())
+	}
+}
+
+impl ItemData {
+	/// Serializes the item data to a binary format (every field is a presence byte + value)
+	pub fn to_binary(&self) -> Vec<u8> {
+		let mut data = Vec::new();
+		match self.durability {
+			Some(durability) => { data.push(1); data.extend_from_slice(&durability.to_le_bytes()); }
+			None => data.push(0),
+		}
+		match self.tool {
+			Some(tool) => { data.push(1); data.push(tool.0); }
+			None => data.push(0),
+		}
+		match self.hunger {
+			Some(hunger) => { data.push(1); data.push(hunger as u8); }
+			None => data.push(0),
+		}
+		match self.armor {
+			Some(armor) => { data.push(1); data.push(armor as u8); }
+			None => data.push(0),
+		}
+		match &self.effects {
+			Some(effects) => {
+				data.push(1);
+				data.extend_from_slice(&(effects.len() as u16).to_le_bytes());
+				for effect in effects {
+					data.extend_from_slice(&effect.to_le_bytes());
+				}
+			}
+			None => data.push(0),
+		}
+		data
+	}
+	
+	/// Deserializes the item data from binary format
+	pub fn from_binary(bytes: &[u8]) -> Option<Self> {
+		let mut offset = 0;
+		let durability = match bytes.get(offset)? {
+			0 => { offset += 1; None },
+			1 => {
+				let value = u16::from_le_bytes([*bytes.get(offset+1)?, *bytes.get(offset+2)?]);
+				offset += 3;
+				Some(value)
+			}
+			_ => return None,
+		};
+		let tool = match bytes.get(offset)? {
+			0 => { offset += 1; None },
+			1 => { offset += 2; Some(ToolFlags(*bytes.get(offset-1)?)) },
+			_ => return None,
+		};
+		let hunger = match bytes.get(offset)? {
+			0 => { offset += 1; None },
+			1 => { offset += 2; Some(*bytes.get(offset-1)? as i8) },
+			_ => return None,
+		};
+		let armor = match bytes.get(offset)? {
+			0 => { offset += 1; None },
+			1 => { offset += 2; Some(*bytes.get(offset-1)? as i8) },
+			_ => return None,
+		};
+		let effects = match bytes.get(offset)? {
+			0 => None,
+			1 => {
+				let count = u16::from_le_bytes([*bytes.get(offset+1)?, *bytes.get(offset+2)?]) as usize;
+				offset += 3;
+				let mut effects = Vec::with_capacity(count);
+				for _ in 0..count {
+					let effect = bytes.get(offset..offset+4)?;
+					effects.push(u32::from_le_bytes([effect[0], effect[1], effect[2], effect[3]]));
+					offset += 4;
+				}
+				Some(effects)
+			}
+			_ => return None,
+		};
+		Some(ItemData { durability, tool, hunger, armor, effects })
+	}
+	
+	/// Returns the size of the binary representation
+	pub fn binary_size(&self) -> usize {
+		let mut size = 5; // presence byte of every field
+		if self.durability.is_some() { size += 2; }
+		if self.tool.is_some() { size += 1; }
+		if self.hunger.is_some() { size += 1; }
+		if self.armor.is_some() { size += 1; }
+		if let Some(effects) = &self.effects { size += 2 + effects.len() * 4; }
+		size
+	}
+}
+
 impl World {
 	/// Serializes the world to a binary format
 	pub fn to_binary(&self) -> Vec<u8> {
 		let mut data = Vec::new();
 		
+		// 0. Write the format of the chunks (marker + format byte)
+		data.extend_from_slice(&WORLD_FORMAT_MARKER.to_le_bytes());
+		data.push(CHUNK_FORMAT);
+		
 		// 1. Write chunk count (4 bytes)
 		data.extend_from_slice(&(self.chunks.len() as u32).to_le_bytes());
 		
@@ -433,9 +732,19 @@ impl World {
 	pub fn from_binary(bytes: &[u8]) -> Option<Self> {
 		let mut offset = 0;
 		
-		// 1. Read chunk count
+		// 0. Read the format of the chunks, worlds saved before it start with the chunk count
 		if bytes.len() < 4 { return None; }
-		let chunk_count = u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as usize;
+		let format = if u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) == WORLD_FORMAT_MARKER {
+			offset += 5;
+			*bytes.get(4)?
+		} else {
+			0
+		};
+		if format > CHUNK_FORMAT { return None; }
+		
+		// 1. Read chunk count
+		if bytes.len() < offset + 4 { return None; }
+		let chunk_count = u32::from_le_bytes([bytes[offset], bytes[offset+1], bytes[offset+2], bytes[offset+3]]) as usize;
 		offset += 4;
 		
 		let mut world = World::empty();
@@ -451,13 +760,7 @@ impl World {
 			offset += 8;
 			
 			// Read chunk and get actual bytes consumed
-			let chunk = Chunk::from_binary(&bytes[offset..])?;
-			let consumed_bytes = chunk.binary_size();
-			
-			// Verify we actually consumed the expected bytes
-			if bytes.len() < offset + consumed_bytes {
-				return None;
-			}
+			let (chunk, consumed_bytes) = Chunk::read_binary(&bytes[offset..], format)?;
 			
 			// Insert into world
 			world.chunks.insert(coord.into(), chunk);
@@ -522,7 +825,9 @@ impl ChunkCoord {
 
 pub fn save_entire_world(path: &PathBuf) -> Result<()> {
 	let game_state = ptr::get_gamestate();
-	let world = game_state.world();
+	let world = game_state.world_mut();
+	// falling blocks are saved as the blocks they are
+	world.settle_falling_blocks();
 	let world_data = world.to_binary();
 	// Ensure safe writing
 	let world_dir = path.join("world");
@@ -563,6 +868,9 @@ pub fn load_entire_world(path: &PathBuf) -> Result<()> {
 		loaded_world.loaded_chunks.insert(*chunk_coord);
 		loaded_world.create_bind_group(*chunk_coord);
 	}
+	// the chunks still missing are made by the generator of the world, the sky reaches down to its ground
+	loaded_world.generator = Arc::clone(&ptr::get_gamestate().world().generator);
+	loaded_world.light_all_chunks();
 	*ptr::get_gamestate().world_mut() = loaded_world;
 		
 	Ok(())
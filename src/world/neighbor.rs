@@ -0,0 +1,69 @@
+use crate::world::main::World;
+use crate::block::math::ADJACENT;
+use glam::IVec3;
+use std::collections::{HashSet, VecDeque};
+
+/// Neighbor updates handled in a single flush, the rest waits for the next tick
+/// Keeps blocks that keep changing each other (a wire feeding itself ...) from freezing the game
+pub const MAX_NEIGHBOR_UPDATES: usize = 4096;
+
+/// What a block does when a block next to it changed (world, world position of the block, position of the changed block)
+pub type NeighborFn = fn(&mut World, IVec3, IVec3);
+
+/// Neighbor updates waiting to be handed to the blocks
+#[derive(Debug, Clone, Default)]
+pub struct NeighborUpdates {
+	queue: VecDeque<(IVec3, IVec3)>, // (block to update, block that changed)
+	queued: HashSet<(IVec3, IVec3)>, // every update in `queue`, the same one waits only once
+	flushing: bool, // a handler changing blocks only queues, the running flush gets to them
+}
+
+impl NeighborUpdates {
+	#[inline] pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Number of updates still waiting
+	#[inline] pub fn pending(&self) -> usize {
+		self.queue.len()
+	}
+
+	/// Queues an update for the block at `pos` about the block at `source`
+	#[inline] pub fn push(&mut self, pos: IVec3, source: IVec3) {
+		if self.queued.insert((pos, source)) {
+			self.queue.push_back((pos, source));
+		}
+	}
+
+	#[inline] fn pop(&mut self) -> Option<(IVec3, IVec3)> {
+		let update = self.queue.pop_front()?;
+		self.queued.remove(&update);
+		Some(update)
+	}
+}
+
+impl World {
+	/// Tells the six blocks around the position that it changed
+	pub fn notify_neighbors(&mut self, pos: IVec3) {
+		for side in ADJACENT {
+			self.neighbor_updates.push(pos + side, pos);
+		}
+		self.flush_neighbor_updates();
+	}
+
+	/// Hands the queued updates to the blocks, at most `MAX_NEIGHBOR_UPDATES` of them
+	/// Does nothing when called from inside a handler, the updates it causes join the running flush
+	pub fn flush_neighbor_updates(&mut self) {
+		if self.neighbor_updates.flushing {
+			return;
+		}
+		self.neighbor_updates.flushing = true;
+		for _ in 0..MAX_NEIGHBOR_UPDATES {
+			let Some((pos, source)) = self.neighbor_updates.pop() else { break; };
+			if let Some(on_update) = self.get_block(pos).props().neighbor_update {
+				on_update(self, pos, source);
+			}
+		}
+		self.neighbor_updates.flushing = false;
+	}
+}
@@ -0,0 +1,84 @@
+use crate::block::main::{Block, Chunk, Material};
+use crate::block::math::{BlockPosition, ChunkCoord};
+use crate::block::props::mat;
+use crate::hs::math::Rand;
+
+/// Where and how often an ore shows up
+#[derive(Clone, Copy, Debug)]
+pub struct OreRule {
+	pub ore: Material,
+	pub alt: Material, // the other look of the ore, mixed into every vein
+	pub host: Material, // the only block a vein replaces
+	pub min_y: i32, // veins start between these (world y, inclusive)
+	pub max_y: i32,
+	pub vein_size: u32, // blocks in a vein, at most
+	pub veins_per_chunk: f32, // the fraction is the chance of one more
+}
+
+impl OreRule {
+	const fn new(ore: Material, alt: Material, min_y: i32, max_y: i32) -> Self {
+		Self { ore, alt, host: mat::STONE, min_y, max_y, vein_size: 8, veins_per_chunk: 1.0 }
+	}
+	const fn vein(mut self, vein_size: u32, veins_per_chunk: f32) -> Self {
+		self.vein_size = vein_size;
+		self.veins_per_chunk = veins_per_chunk;
+		self
+	}
+	const fn host(mut self, host: Material) -> Self { self.host = host; self }
+}
+
+/// Common ores spread wide and in big veins, precious ones deep down and few
+pub static ORES: [OreRule; 7] = [
+	OreRule::new(mat::STONE_COAL, mat::STONE_COAL_ALT, -32, 128).vein(14, 6.0),
+	OreRule::new(mat::STONE_BROWNIRON, mat::STONE_BROWNIRON_ALT, -16, 64).vein(10, 2.0).host(mat::STONE_SAND),
+	OreRule::new(mat::STONE_IRON, mat::STONE_IRON_ALT, -64, 48).vein(8, 4.0),
+	OreRule::new(mat::STONE_SILVER, mat::STONE_SILVER_ALT, -80, 0).vein(7, 2.0),
+	OreRule::new(mat::STONE_GOLD, mat::STONE_GOLD_ALT, -112, -24).vein(6, 1.0),
+	OreRule::new(mat::STONE_DIAMOND, mat::STONE_DIAMOND_ALT, -160, -64).vein(4, 0.5),
+	OreRule::new(mat::ROCK_RUBY, mat::ROCK_RUBY_ALT, 32, 160).vein(3, 0.75), // up in the mountains
+];
+
+/// The random numbers of one ore in one chunk, the same for the same world
+fn vein_rand(seed: u32, coord: ChunkCoord, rule: usize) -> Rand {
+	let mut hash = Rand::pcg_hash(seed ^ rule as u32);
+	for value in [coord.x(), coord.y(), coord.z()] {
+		hash = Rand::pcg_hash(hash ^ value as u32);
+	}
+	Rand::new(hash.max(1)) // xorshift never leaves 0
+}
+
+/// Places the ore veins starting in the chunk, a vein is a random walk from its start
+/// Veins only replace their host rock and are cut off at the chunk border
+pub fn place_ores(chunk: &mut Chunk, coord: ChunkCoord, seed: u32) {
+	let (_, world_y, _) = coord.unpack_to_worldpos();
+	for (index, rule) in ORES.iter().enumerate() {
+		let (min_y, max_y) = (rule.min_y.max(world_y), rule.max_y.min(world_y + Chunk::SIZE_I - 1));
+		if min_y > max_y {
+			continue;
+		}
+		let mut rand = vein_rand(seed, coord, index);
+		let mut veins = rule.veins_per_chunk as u32;
+		if rand.next_f32() < rule.veins_per_chunk.fract() {
+			veins += 1;
+		}
+		for _ in 0..veins {
+			let mut pos = [
+				(rand.next_u32() % Chunk::SIZE as u32) as i32,
+				min_y - world_y + (rand.next_u32() % (max_y - min_y + 1) as u32) as i32,
+				(rand.next_u32() % Chunk::SIZE as u32) as i32,
+			];
+			let size = rule.vein_size / 2 + rand.next_u32() % (rule.vein_size / 2 + 1);
+			for _ in 0..size {
+				if pos.iter().all(|value| (0..Chunk::SIZE_I).contains(value)) {
+					let idx: usize = BlockPosition::new(pos[0] as u8, pos[1] as u8, pos[2] as u8).into();
+					if chunk.get_block(idx).material() == rule.host {
+						let material = if rand.next_u32() & 1 == 0 { rule.ore } else { rule.alt };
+						chunk.set_block(idx, Block::new(material));
+					}
+				}
+				let axis = (rand.next_u32() % 3) as usize;
+				pos[axis] += if rand.next_u32() & 1 == 0 { 1 } else { -1 };
+			}
+		}
+	}
+}
@@ -0,0 +1,145 @@
+use crate::block::main::{Block, Chunk, Material};
+use crate::block::math::{BlockPosition, ChunkCoord};
+use crate::block::props::mat;
+use crate::hs::math::Rand;
+use crate::world::terrain::{TerrainGen, BEACH_HEIGHT};
+use glam::IVec3;
+
+/// Features stand on a lattice this many blocks wide, at most one in every cell
+pub const FEATURE_CELL: i32 = 8;
+/// How far sideways a feature reaches from its origin, the cells this close to a chunk are looked at
+const FEATURE_REACH: i32 = 3;
+
+/// The kinds of things standing on the generated ground
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum FeatureKind {
+	Tree,
+	WhiteTree, // of the white wood
+	Boulder,
+	Ruin,
+}
+
+/// How a feature block goes into the ground that is already there
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Placing {
+	Replace, // whatever was there
+	IntoAir, // only empty space (leaves don't eat into the ground or the trunks)
+}
+
+/// One feature standing at a place, its blocks are worked out from its own seed
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Feature {
+	pub kind: FeatureKind,
+	pub origin: IVec3, // the block right above the ground
+	pub seed: u32,
+}
+
+impl Feature {
+	/// The blocks of the feature in world space, later ones over earlier ones
+	pub fn blocks(&self) -> Vec<(IVec3, Material, Placing)> {
+		let mut rand = Rand::new(self.seed.max(1));
+		let mut blocks = Vec::new();
+		match self.kind {
+			FeatureKind::Tree | FeatureKind::WhiteTree => {
+				let trunk = if self.kind == FeatureKind::Tree { mat::WOOD_SIDE } else { mat::WOOD_WHITE_SIDE };
+				let height = 4 + (rand.next_u32() % 3) as i32;
+				// two wide layers around the top of the trunk and a narrow crown
+				for y in height - 2..=height + 1 {
+					let radius: i32 = if y >= height { 1 } else { 2 };
+					for x in -radius..=radius {
+						for z in -radius..=radius {
+							let corner = x.abs() == radius && z.abs() == radius;
+							if corner && (y == height + 1 || rand.next_u32() & 1 == 0) {
+								continue; // ragged edges
+							}
+							blocks.push((self.origin + IVec3::new(x, y, z), mat::LEAVES, Placing::IntoAir));
+						}
+					}
+				}
+				for y in 0..height {
+					blocks.push((self.origin + IVec3::new(0, y, 0), trunk, Placing::Replace));
+				}
+			}
+			FeatureKind::Boulder => {
+				// a lump sunk half into the ground
+				let radius = 1.0 + rand.next_f32() * 1.2;
+				let reach = radius.ceil() as i32;
+				for x in -reach..=reach {
+					for y in -reach..=reach {
+						for z in -reach..=reach {
+							let offset = IVec3::new(x, y, z);
+							if offset.as_vec3().length() <= radius + rand.next_f32() * 0.4 {
+								let material = if rand.next_u32() % 4 == 0 { mat::STONE_DIRT } else { mat::ROCK };
+								blocks.push((self.origin + offset - IVec3::Y, material, Placing::Replace));
+							}
+						}
+					}
+				}
+			}
+			FeatureKind::Ruin => {
+				// a worn down 5x5 room with a floor and a doorway
+				for x in -2i32..=2 {
+					for z in -2i32..=2 {
+						blocks.push((self.origin + IVec3::new(x, -1, z), mat::BRICK_GREY, Placing::Replace));
+						if (x.abs() < 2 && z.abs() < 2) || (x == 0 && z == -2) {
+							continue;
+						}
+						let height = (rand.next_u32() % 4) as i32;
+						for y in 0..height {
+							let material = if rand.next_u32() % 3 == 0 { mat::STONE_DIRT } else { mat::BRICK_GREY };
+							blocks.push((self.origin + IVec3::new(x, y, z), material, Placing::Replace));
+						}
+					}
+				}
+			}
+		}
+		blocks
+	}
+}
+
+/// The random numbers of a lattice cell, the same for the same world
+fn cell_rand(seed: u32, cell_x: i32, cell_z: i32) -> Rand {
+	let hash = Rand::pcg_hash(seed ^ 0x57C7_0000 ^ Rand::pcg_hash(cell_x as u32 ^ Rand::pcg_hash(cell_z as u32)));
+	Rand::new(hash.max(1))
+}
+
+/// The feature of a lattice cell, if its biome rolls one and the ground there can hold it
+pub fn feature_in_cell(terrain: &TerrainGen, cell_x: i32, cell_z: i32) -> Option<Feature> {
+	let mut rand = cell_rand(terrain.seed(), cell_x, cell_z);
+	let x = cell_x * FEATURE_CELL + (rand.next_u32() % FEATURE_CELL as u32) as i32;
+	let z = cell_z * FEATURE_CELL + (rand.next_u32() % FEATURE_CELL as u32) as i32;
+	let column = terrain.cached_column(x, z);
+	let roll = rand.next_f32();
+	let mut chance = 0.0;
+	let kind = column.biome.props().features.iter()
+		.find(|(_, weight)| { chance += weight; roll < chance })
+		.map(|(kind, _)| *kind)?;
+	// not in the water, on the beaches, the snow caps or over a cave mouth
+	if column.height <= column.water + BEACH_HEIGHT || column.height >= terrain.snow_line() || terrain.is_cave(x, column.height, z, 0) {
+		return None;
+	}
+	Some(Feature { kind, origin: IVec3::new(x, column.height + 1, z), seed: rand.next_u32() })
+}
+
+/// Places the parts of every feature near the chunk that fall inside it
+/// Each chunk works them out on its own, so it doesn't matter which chunk is generated first
+pub fn place_features(chunk: &mut Chunk, coord: ChunkCoord, terrain: &TerrainGen) {
+	let (world_x, world_y, world_z) = coord.unpack_to_worldpos();
+	let min = IVec3::new(world_x, world_y, world_z);
+	let cells = |start: i32| (start - FEATURE_REACH).div_euclid(FEATURE_CELL)..=(start + Chunk::SIZE_I - 1 + FEATURE_REACH).div_euclid(FEATURE_CELL);
+	for cell_x in cells(world_x) {
+		for cell_z in cells(world_z) {
+			let Some(feature) = feature_in_cell(terrain, cell_x, cell_z) else { continue; };
+			for (pos, material, placing) in feature.blocks() {
+				let local = pos - min;
+				if local.min_element() < 0 || local.max_element() >= Chunk::SIZE_I {
+					continue;
+				}
+				let idx: usize = BlockPosition::new(local.x as u8, local.y as u8, local.z as u8).into();
+				if placing == Placing::Replace || chunk.get_block(idx).is_empty() {
+					chunk.set_block(idx, Block::new(material));
+				}
+			}
+		}
+	}
+}
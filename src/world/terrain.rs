@@ -0,0 +1,208 @@
+use crate::block::main::{Block, Chunk, Material};
+use crate::block::math::{BlockPosition, ChunkCoord};
+use crate::block::props::mat;
+use crate::hs::math::Noise;
+use crate::world::biome::{Biome, BiomeBlend, ClimateNoise};
+use crate::world::heightmap::{ColumnCache, ColumnMap};
+use crate::world::water::{self, WaterNoise};
+use crate::world::{ore, structure};
+use std::sync::Arc;
+
+/// Height of the sea surface unless the world picks another one
+pub const SEA_LEVEL: i32 = 0;
+/// Ground this many blocks above the sea or higher is topped with snowy stone, whatever the biome
+pub const SNOW_HEIGHT: i32 = 80;
+/// Ground this many blocks above the water or lower is a sandy beach
+pub const BEACH_HEIGHT: i32 = 2;
+/// Ground under more water than this is covered with gravel instead of sand
+pub const GRAVEL_DEPTH: i32 = 6;
+/// Caves stay this many blocks under ground covered with water, so the seas don't drain into them
+const CAVE_SEAL: i32 = 4;
+/// Chunks above this one are empty (the highest mountain stays under it), amplified ground goes higher
+pub const MAX_CHUNK_Y: i32 = 8;
+/// Frequency of the cheese cave noise, the caverns are a few dozen blocks wide (and flatter than wide)
+const CHEESE_FREQ: f32 = 0.015;
+/// The stone is hollowed out where the cheese noise is above this, higher gives fewer caverns
+const CHEESE_THRESHOLD: f32 = 0.4;
+/// Caverns stay this many blocks under the top so they don't open up whole hillsides
+const CHEESE_MIN_DEPTH: i32 = 12;
+/// Frequency of the two tunnel noises, lower gives longer and straighter tunnels
+const TUNNEL_FREQ: f32 = 0.012;
+/// Tunnels run where both tunnel noises are this close to zero, higher gives wider tunnels
+const TUNNEL_WIDTH: f32 = 0.06;
+/// Seed offsets keeping the cave noises apart from each other and from the ground
+const CHEESE_SALT: u32 = 0xCA7E_C0DE;
+const TUNNEL_SALT_A: u32 = 0x7E11_0A0A;
+const TUNNEL_SALT_B: u32 = 0x7E11_0B0B;
+
+/// The ground of one column: how high it goes, the biome covering it and the water over it
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Column {
+	pub height: i32, // the top block
+	pub biome: Biome, // the dominant one, it picks the blocks
+	pub water: i32, // the water surface (the sea or a lake), it only fills the column if the ground is lower
+}
+
+impl Column {
+	#[inline] pub const fn is_underwater(&self) -> bool {
+		self.height < self.water
+	}
+
+	/// The material at the height (None above the ground and the water)
+	/// The biome's surface block on top, its subsurface under that, stone deeper down
+	/// Snow caps the peaks from the snow line up, sand covers the beaches and the shallow water and gravel the deep water
+	pub fn material_at(&self, y: i32, snow_line: i32) -> Option<Material> {
+		if y > self.height {
+			return (y <= self.water).then_some(mat::WATER);
+		}
+		let props = self.biome.props();
+		let depth = self.height - y;
+		let shore = if self.is_underwater() {
+			Some(if self.water - self.height > GRAVEL_DEPTH { mat::GRAVEL } else { mat::SAND })
+		} else {
+			(self.height <= self.water + BEACH_HEIGHT).then_some(mat::SAND)
+		};
+		Some(if depth == 0 {
+			if self.height >= snow_line { mat::STONE_SNOW } else { shore.unwrap_or(props.surface) }
+		} else if depth <= props.subsurface_depth {
+			shore.unwrap_or(props.subsurface)
+		} else {
+			mat::STONE
+		})
+	}
+}
+
+/// Shapes the ground from the terrain noise, every biome lifting and covering it its own way
+/// The heights blend between biomes so their borders have no cliffs, caves are carved out of it with 3D noise
+/// Oceans sink it under the sea, rivers and lakes are dug into it
+/// The columns are worked out once for a chunk column and cached, the chunks stacked in it share them
+#[derive(Clone, Debug)]
+pub struct TerrainGen {
+	seed: u32,
+	amplify: f32, // how much taller than usual the ground is
+	sea_level: i32,
+	ground: Noise,
+	climate: ClimateNoise,
+	water: WaterNoise,
+	cheese: Noise,
+	tunnels: (Noise, Noise),
+	columns: Arc<ColumnCache>, // shared by the copies, they make the same ground
+}
+
+impl TerrainGen {
+	pub fn new(seed: u32) -> Self {
+		Self {
+			seed,
+			amplify: 1.0,
+			sea_level: SEA_LEVEL,
+			ground: Noise::new(seed),
+			climate: ClimateNoise::new(seed),
+			water: WaterNoise::new(seed),
+			cheese: Noise::new(seed ^ CHEESE_SALT),
+			tunnels: (Noise::new(seed ^ TUNNEL_SALT_A), Noise::new(seed ^ TUNNEL_SALT_B)),
+			columns: Arc::new(ColumnCache::new()),
+		}
+	}
+
+	/// The same ground, `amplify` times as far above and below the sea
+	pub fn amplified(mut self, amplify: f32) -> Self { self.amplify = amplify; self.columns = Arc::default(); self }
+	/// The sea surface at another height, the oceans and rivers go with it
+	pub fn with_sea_level(mut self, sea_level: i32) -> Self { self.sea_level = sea_level; self.columns = Arc::default(); self }
+	#[inline] pub const fn seed(&self) -> u32 { self.seed }
+	#[inline] pub const fn sea_level(&self) -> i32 { self.sea_level }
+	/// The height the snow caps start at, it rises and sinks with the sea
+	#[inline] pub const fn snow_line(&self) -> i32 { self.sea_level + SNOW_HEIGHT }
+
+	/// The ground height of the column with the oceans and rivers, but without the lakes
+	fn shaped_height(&self, x: i32, z: i32, blend: &BiomeBlend) -> f32 {
+		let terrain = self.ground.terrain_noise_2d(x, z);
+		// the ridges only matter where a ridged biome weighs in
+		let ridged = if blend.mix(|props| props.ridges) > 0.01 { self.ground.ridged_noise_2d(x, z).max(0.0) } else { 0.0 };
+		let height = blend.mix(|props| props.base_height + props.amplitude * terrain + props.ridges * ridged);
+		let sea = self.sea_level as f32;
+		let mut height = sea + (height - sea) * self.amplify;
+		// oceans sink the ground down to their floor
+		height += (sea - water::OCEAN_DEPTH - height).min(0.0) * self.water.ocean(x, z);
+		// a river valley slopes down to just above the sea, the channel in it goes under
+		let (valley, channel) = self.water.river(x, z, height - sea);
+		height += (sea + 1.0 - height).min(0.0) * valley;
+		height += (sea - water::RIVER_DEPTH - height).min(0.0) * channel;
+		height
+	}
+
+	/// The ground height, biome and water surface of the column
+	pub fn column(&self, x: i32, z: i32) -> Column {
+		let blend = self.climate.blend(x, z);
+		let mut height = self.shaped_height(x, z, &blend);
+		let mut water = self.sea_level;
+		// lakes are dug into the ground, their level is only worked out for the columns they could reach
+		if let Some(lake) = self.water.lake(x, z) {
+			let distance = lake.distance(x, z);
+			if distance < lake.radius {
+				let height_at = |x: i32, z: i32| self.shaped_height(x, z, &self.climate.blend(x, z));
+				if let Some(level) = lake.level(height_at).filter(|level| *level > self.sea_level + BEACH_HEIGHT && *level < self.snow_line()) {
+					height = height.min(lake.floor(level, distance));
+					water = level;
+				}
+			}
+		}
+		Column { height: height.floor() as i32, biome: blend.dominant(), water }
+	}
+
+	/// The columns of the chunk column, from the cache if a chunk in it was generated lately
+	pub fn column_map(&self, chunk_x: i32, chunk_z: i32) -> Arc<ColumnMap> {
+		self.columns.get_or_make(chunk_x, chunk_z, || ColumnMap::new(chunk_x, chunk_z, |x, z| self.column(x, z)))
+	}
+
+	/// The same as `column`, through the cache
+	pub fn cached_column(&self, x: i32, z: i32) -> Column {
+		let map = self.column_map(x.div_euclid(Chunk::SIZE_I), z.div_euclid(Chunk::SIZE_I));
+		map.get(x.rem_euclid(Chunk::SIZE_I) as usize, z.rem_euclid(Chunk::SIZE_I) as usize)
+	}
+
+	/// Whether a cave hollows out the block, `depth` blocks under the top of its column
+	/// Cheese caves are big caverns where the slow noise is high, spaghetti tunnels run along the lines
+	/// where two noises both cross zero (they can break through the top, that's how caves are found)
+	pub fn is_cave(&self, x: i32, y: i32, z: i32, depth: i32) -> bool {
+		let (x, y, z) = (x as f32, y as f32, z as f32);
+		if depth >= CHEESE_MIN_DEPTH {
+			let cheese = self.cheese.fractal_noise_3d(x * CHEESE_FREQ, y * CHEESE_FREQ * 2.0, z * CHEESE_FREQ, 2);
+			if cheese > CHEESE_THRESHOLD {
+				return true;
+			}
+		}
+		let (x, y, z) = (x * TUNNEL_FREQ, y * TUNNEL_FREQ, z * TUNNEL_FREQ);
+		self.tunnels.0.noise_3d(x, y, z).abs() < TUNNEL_WIDTH && self.tunnels.1.noise_3d(x, y, z).abs() < TUNNEL_WIDTH
+	}
+
+	/// Fills the chunk with the ground of its columns, carves the caves out of it and places the ore veins
+	/// and the parts of the features (trees, boulders, ruins) reaching into it
+	pub fn generate(&self, coord: ChunkCoord) -> Chunk {
+		if coord.y() > ((MAX_CHUNK_Y as f32 * self.amplify).ceil() as i32).max(self.sea_level.div_euclid(Chunk::SIZE_I)) {
+			return Chunk::empty();
+		}
+		let (world_x, world_y, world_z) = coord.unpack_to_worldpos();
+		let columns = self.column_map(coord.x(), coord.z());
+		let snow_line = self.snow_line();
+		let mut chunk = Chunk::empty();
+		for x in 0..Chunk::SIZE {
+			for z in 0..Chunk::SIZE {
+				let column = columns.get(x, z);
+				for y in 0..Chunk::SIZE {
+					let world_y = world_y + y as i32;
+					let Some(material) = column.material_at(world_y, snow_line) else { break; };
+					let depth = column.height - world_y;
+					let sealed = column.is_underwater() && depth < CAVE_SEAL;
+					if material != mat::WATER && !sealed && self.is_cave(world_x + x as i32, world_y, world_z + z as i32, depth) {
+						continue;
+					}
+					let idx: BlockPosition = (x, y, z).into();
+					chunk.set_block(idx.into(), Block::new(material));
+				}
+			}
+		}
+		ore::place_ores(&mut chunk, coord, self.seed);
+		structure::place_features(&mut chunk, coord, self);
+		chunk
+	}
+}
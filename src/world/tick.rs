@@ -0,0 +1,170 @@
+use crate::block::main::{Block, Chunk};
+use crate::block::math::{ADJACENT, BlockPosition, ChunkCoord};
+use crate::block::props::mat;
+use crate::hs::math::Rand;
+use crate::world::main::World;
+use glam::IVec3;
+use std::collections::{BTreeMap, HashSet};
+
+/// Game ticks per second, every timed block behavior counts in these
+pub const TICKS_PER_SECOND: f32 = 20.0;
+/// Blocks picked in every loaded chunk on every tick
+pub const RANDOM_TICKS_PER_CHUNK: u32 = 3;
+/// Caps the catch-up after a long frame so a lag spike does not freeze the game even longer
+const MAX_TICKS_PER_UPDATE: u32 = 10;
+/// Scheduled ticks run in a single tick, the rest waits for the next one (a big flood stays smooth)
+pub const MAX_SCHEDULED_PER_TICK: usize = 1024;
+
+/// What a block does when it gets ticked (world, world position of the block, rng)
+pub type TickFn = fn(&mut World, IVec3, &mut Rand);
+
+/// Keeps the game tick count and the positions waiting for a scheduled tick
+#[derive(Debug, Clone, Default)]
+pub struct TickScheduler {
+	tick: u64,
+	accumulator: f32, // fraction of a tick left over from previous frames
+	scheduled: BTreeMap<u64, Vec<IVec3>>, // due tick -> positions
+	queued: HashSet<IVec3>, // every position in `scheduled`, a position waits for one tick at a time
+}
+
+impl TickScheduler {
+	#[inline] pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// The number of ticks since the world was loaded
+	#[inline] pub const fn current(&self) -> u64 {
+		self.tick
+	}
+
+	/// Number of scheduled ticks still waiting
+	#[inline] pub fn pending(&self) -> usize {
+		self.queued.len()
+	}
+
+	/// Schedules a tick for the position `delay` ticks from now (at least 1)
+	/// Does nothing if the position is already waiting for one
+	pub fn schedule(&mut self, pos: IVec3, delay: u32) {
+		if !self.queued.insert(pos) {
+			return;
+		}
+		let due = self.tick + delay.max(1) as u64;
+		self.scheduled.entry(due).or_default().push(pos);
+	}
+
+	/// Steps to the next tick and takes out the positions due by then (at most `MAX_SCHEDULED_PER_TICK`)
+	pub(crate) fn advance(&mut self) -> Vec<IVec3> {
+		self.tick += 1;
+		let later = self.scheduled.split_off(&(self.tick + 1));
+		let due = std::mem::replace(&mut self.scheduled, later);
+		let mut due: Vec<IVec3> = due.into_values().flatten().collect();
+		if due.len() > MAX_SCHEDULED_PER_TICK {
+			let delayed = due.split_off(MAX_SCHEDULED_PER_TICK);
+			self.scheduled.entry(self.tick + 1).or_default().splice(0..0, delayed);
+		}
+		for pos in &due {
+			self.queued.remove(pos);
+		}
+		due
+	}
+}
+
+/// Mixes the world seed, a chunk and a tick into a non zero rng seed
+#[inline]
+fn tick_seed(seed: u32, coord: ChunkCoord, tick: u64) -> u32 {
+	let coord = coord.into_u64();
+	let hash = Rand::pcg_hash(seed ^ Rand::pcg_hash(coord as u32 ^ Rand::pcg_hash((coord >> 32) as u32)));
+	Rand::pcg_hash(hash ^ tick as u32 ^ Rand::pcg_hash((tick >> 32) as u32)).max(1)
+}
+
+impl World {
+	/// Schedules a tick for the block at the position
+	#[inline] pub fn schedule_tick(&mut self, pos: IVec3, delay: u32) {
+		self.ticks.schedule(pos, delay);
+	}
+
+	/// Advances the tick clock by the frame time, running every tick that became due
+	pub fn update_ticks(&mut self, delta_seconds: f32, seed: u32) {
+		self.ticks.accumulator += delta_seconds * TICKS_PER_SECOND;
+		let mut ran = 0;
+		while self.ticks.accumulator >= 1.0 {
+			if ran == MAX_TICKS_PER_UPDATE {
+				self.ticks.accumulator = 0.0;
+				break;
+			}
+			self.ticks.accumulator -= 1.0;
+			self.tick(seed);
+			ran += 1;
+		}
+	}
+
+	/// Runs a single game tick: neighbor updates left over from the last one, scheduled ticks, then the random ones
+	pub fn tick(&mut self, seed: u32) {
+		self.flush_neighbor_updates();
+		let due = self.ticks.advance();
+		let tick = self.ticks.current();
+
+		for pos in due {
+			let Some(on_tick) = self.get_block(pos).props().scheduled_tick else { continue; };
+			let local = u16::from(BlockPosition::from(pos)) as u64;
+			let mut rand = Rand::new(tick_seed(seed, ChunkCoord::from_world_pos(pos), tick ^ local << 32));
+			on_tick(self, pos, &mut rand);
+		}
+
+		let coords: Vec<ChunkCoord> = self.loaded_chunks.iter()
+			.copied()
+			.filter(|coord| self.get_chunk(*coord).is_some_and(|chunk| !chunk.is_empty()))
+			.collect();
+		for coord in coords {
+			let mut rand = Rand::new(tick_seed(seed, coord, tick));
+			let (x, y, z) = coord.unpack_to_worldpos();
+			for _ in 0..RANDOM_TICKS_PER_CHUNK {
+				let index = rand.next_u32() as usize % Chunk::VOLUME;
+				let Some(chunk) = self.get_chunk(coord) else { break; };
+				let Some(on_tick) = chunk.get_block(index).props().random_tick else { continue; };
+				let pos = IVec3::new(x, y, z) + IVec3::from(BlockPosition::from(index));
+				on_tick(self, pos, &mut rand);
+			}
+		}
+	}
+}
+
+//
+// Block behaviors used by the props table
+//
+
+/// Neighbor update of the blocks with a scheduled tick: they get one to look around
+pub fn schedule_on_update(world: &mut World, pos: IVec3, _source: IVec3) {
+	let delay = world.get_block(pos).props().tick_delay;
+	world.schedule_tick(pos, delay);
+}
+
+/// Grass dies under opaque blocks, otherwise it spreads to a nearby uncovered dirt block
+pub fn grass_spread(world: &mut World, pos: IVec3, rand: &mut Rand) {
+	if world.get_block(pos + IVec3::Y).props().is_opaque() {
+		world.set_block(pos, Block::new(mat::DIRT));
+		return;
+	}
+	let target = pos + IVec3::new(
+		(rand.next_u32() % 3) as i32 - 1,
+		(rand.next_u32() % 5) as i32 - 3,
+		(rand.next_u32() % 3) as i32 - 1,
+	);
+	if world.get_block(target).material() == mat::DIRT && !world.get_block(target + IVec3::Y).props().is_opaque() {
+		world.set_block(target, Block::new(mat::DIRT_GRASS));
+	}
+}
+
+/// Snow touching water melts into water
+pub fn snow_melt(world: &mut World, pos: IVec3, _rand: &mut Rand) {
+	if ADJACENT.iter().any(|side| world.get_block(pos + *side).material() == mat::WATER) {
+		world.set_block(pos, Block::new(mat::WATER));
+	}
+}
+
+/// Snowy dirt covered by an opaque block loses its snow
+pub fn snow_thaw(world: &mut World, pos: IVec3, _rand: &mut Rand) {
+	if world.get_block(pos + IVec3::Y).props().is_opaque() {
+		world.set_block(pos, Block::new(mat::DIRT));
+	}
+}
@@ -0,0 +1,146 @@
+use crate::hs::math::{Noise, Rand};
+
+/// Frequency of the continent noise, an ocean is several hundred blocks across
+const CONTINENT_FREQ: f32 = 0.0012;
+/// The ground starts sinking where the continent noise goes under this
+const OCEAN_EDGE: f32 = -0.8;
+/// ... and is down on the ocean floor this much further down the continent noise
+const OCEAN_SLOPE: f32 = 1.0;
+/// Depth of the ocean floor under the sea
+pub const OCEAN_DEPTH: f32 = 24.0;
+/// Frequency of the river noise, rivers run along the lines where it crosses zero
+const RIVER_FREQ: f32 = 0.004;
+/// Half width of a river valley and of the channel in it, in river noise
+const VALLEY_WIDTH: f32 = 1.2;
+const CHANNEL_WIDTH: f32 = 0.15;
+/// Depth of a river bed under the sea, rivers flow at sea level into the oceans
+pub const RIVER_DEPTH: f32 = 3.0;
+/// Rivers fade out over ground this high above the sea (in the 16 blocks under it), they don't cut canyons into mountains
+const RIVER_MAX_HEIGHT: f32 = 24.0;
+/// The continent and river noise are sampled on lattices this wide and interpolated in between, it smooths out the jumps of the noise
+const CONTINENT_CELL: i32 = 16;
+const RIVER_CELL: i32 = 4;
+/// At most one lake in every cell of a lattice this wide, a lake stays inside its cell
+const LAKE_CELL: i32 = 64;
+/// Chance of a cell having a lake (if the ground there can hold one)
+const LAKE_CHANCE: f32 = 0.4;
+const LAKE_MIN_RADIUS: f32 = 6.0;
+const LAKE_MAX_RADIUS: f32 = 14.0;
+/// Depth of the bowl a lake is dug into, under its water level
+pub const LAKE_DEPTH: f32 = 4.0;
+/// The ground around a lake may be at most this much higher on one side than on the other, lakes sit in basins and on flats
+const LAKE_MAX_RIM_SPREAD: f32 = 8.0;
+/// Points around the rim the water level is worked out from
+const LAKE_RIM_SAMPLES: usize = 16;
+/// Seed offsets keeping the water noises apart from each other and from the ground
+const CONTINENT_SALT: u32 = 0x0CEA_0C0A;
+const RIVER_SALT: u32 = 0x217E_2000;
+const LAKE_SALT: u32 = 0x1A4E_0000;
+
+#[inline] fn smoothstep(t: f32) -> f32 {
+	let t = t.clamp(0.0, 1.0);
+	t * t * (3.0 - 2.0 * t)
+}
+
+/// A sample of `value` interpolated between the corners of the lattice cell the column is in
+fn lattice(x: i32, z: i32, cell: i32, value: impl Fn(i32, i32) -> f32) -> f32 {
+	let (cell_x, cell_z) = (x.div_euclid(cell) * cell, z.div_euclid(cell) * cell);
+	let (tx, tz) = ((x - cell_x) as f32 / cell as f32, (z - cell_z) as f32 / cell as f32);
+	let near = value(cell_x, cell_z) * (1.0 - tx) + value(cell_x + cell, cell_z) * tx;
+	let far = value(cell_x, cell_z + cell) * (1.0 - tx) + value(cell_x + cell, cell_z + cell) * tx;
+	near * (1.0 - tz) + far * tz
+}
+
+/// A lake of a lattice cell, round around its center
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Lake {
+	pub x: i32,
+	pub z: i32,
+	pub radius: f32,
+}
+
+impl Lake {
+	#[inline] pub fn distance(&self, x: i32, z: i32) -> f32 {
+		(((x - self.x) * (x - self.x) + (z - self.z) * (z - self.z)) as f32).sqrt()
+	}
+
+	/// The water level of the lake from the ground height around it: just under the lowest point of its rim
+	/// None if the ground can't hold a lake (it is on a slope or on a knoll higher than the rim)
+	pub fn level(&self, height: impl Fn(i32, i32) -> f32) -> Option<i32> {
+		let reach = self.radius + 1.0;
+		let rim: Vec<f32> = (0..LAKE_RIM_SAMPLES).map(|i| {
+			let angle = i as f32 / LAKE_RIM_SAMPLES as f32 * std::f32::consts::TAU;
+			height(self.x + (angle.cos() * reach).round() as i32, self.z + (angle.sin() * reach).round() as i32)
+		}).collect();
+		let lowest = rim.iter().copied().fold(f32::INFINITY, f32::min);
+		let highest = rim.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+		let level = lowest.floor() as i32 - 1;
+		if highest - lowest > LAKE_MAX_RIM_SPREAD || height(self.x, self.z) > highest {
+			return None;
+		}
+		Some(level)
+	}
+
+	/// The bottom of the bowl the lake is dug into, the deepest in the middle and up to the water level at the edge
+	#[inline] pub fn floor(&self, level: i32, distance: f32) -> f32 {
+		let t = distance / self.radius;
+		level as f32 - LAKE_DEPTH * (1.0 - t * t)
+	}
+}
+
+/// The noise of the oceans, rivers and lakes of a world
+#[derive(Clone, Copy, Debug)]
+pub struct WaterNoise {
+	seed: u32,
+	continent: Noise,
+	rivers: Noise,
+}
+
+impl WaterNoise {
+	pub const fn new(seed: u32) -> Self {
+		Self {
+			seed,
+			continent: Noise::new(seed ^ CONTINENT_SALT),
+			rivers: Noise::new(seed ^ RIVER_SALT),
+		}
+	}
+
+	/// How far the column is into an ocean, 0 on land to 1 where the ground is down on the ocean floor
+	pub fn ocean(&self, x: i32, z: i32) -> f32 {
+		let continent = lattice(x, z, CONTINENT_CELL, |x, z| {
+			let (x, z) = (x as f32 * CONTINENT_FREQ, z as f32 * CONTINENT_FREQ);
+			(self.continent.noise_2d(x, z) + 0.5 * self.continent.noise_2d(x * 2.0 - 7.1, z * 2.0 + 3.9)) / 1.5
+		});
+		smoothstep((OCEAN_EDGE - continent) / OCEAN_SLOPE)
+	}
+
+	/// How far the column is into a river valley and into its channel (0 - 1 each)
+	/// `above_sea` is the ground height over the sea without the river, high ground has no rivers
+	pub fn river(&self, x: i32, z: i32, above_sea: f32) -> (f32, f32) {
+		let fade = smoothstep((RIVER_MAX_HEIGHT - above_sea) / 16.0);
+		if fade <= 0.0 {
+			return (0.0, 0.0);
+		}
+		let river = lattice(x, z, RIVER_CELL, |x, z| self.rivers.noise_2d(x as f32 * RIVER_FREQ, z as f32 * RIVER_FREQ)).abs();
+		(smoothstep(1.0 - river / VALLEY_WIDTH) * fade, smoothstep(1.0 - river / CHANNEL_WIDTH) * fade)
+	}
+
+	/// The lake of the lattice cell the column is in, if the cell rolled one
+	pub fn lake(&self, x: i32, z: i32) -> Option<Lake> {
+		let (cell_x, cell_z) = (x.div_euclid(LAKE_CELL), z.div_euclid(LAKE_CELL));
+		let hash = Rand::pcg_hash(self.seed ^ LAKE_SALT ^ Rand::pcg_hash(cell_x as u32 ^ Rand::pcg_hash(cell_z as u32)));
+		let mut rand = Rand::new(hash.max(1));
+		if rand.next_f32() >= LAKE_CHANCE {
+			return None;
+		}
+		let radius = rand.range_f32(LAKE_MIN_RADIUS, LAKE_MAX_RADIUS);
+		// the rim stays inside the cell
+		let margin = radius.ceil() as i32 + 2;
+		let span = (LAKE_CELL - 2 * margin) as u32;
+		Some(Lake {
+			x: cell_x * LAKE_CELL + margin + (rand.next_u32() % span) as i32,
+			z: cell_z * LAKE_CELL + margin + (rand.next_u32() % span) as i32,
+			radius,
+		})
+	}
+}
@@ -0,0 +1,234 @@
+use crate::block::main::Chunk;
+use crate::block::math::ChunkCoord;
+use crate::world::generator::WorldGenerator;
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap, HashSet};
+use std::sync::{mpsc, Arc, Condvar, Mutex};
+use std::thread;
+
+/// At most this many threads generate chunks (one core is left to the game)
+const MAX_WORKERS: usize = 8;
+/// Generated chunks put into the world in one update at most, lighting them is done on the main thread
+pub const MAX_INSERTS_PER_UPDATE: usize = 16;
+
+/// The squared distance between two chunks, in chunks
+#[inline] fn distance_sq(a: ChunkCoord, b: ChunkCoord) -> i32 {
+	let ((ax, ay, az), (bx, by, bz)) = (a.unpack(), b.unpack());
+	(ax - bx).pow(2) + (ay - by).pow(2) + (az - bz).pow(2)
+}
+
+/// A chunk waiting for a worker, the nearest one is taken first (the higher one of equally near ones)
+#[derive(Debug)]
+struct Job {
+	distance: i32,
+	coord: ChunkCoord,
+	request: u32, // which request for the chunk it is, the result of a cancelled one is not taken for a later one
+	generator: Arc<dyn WorldGenerator>,
+}
+
+impl Job {
+	#[inline] fn priority(&self) -> (i32, i32) {
+		(-self.distance, self.coord.y())
+	}
+}
+
+impl PartialEq for Job {
+	fn eq(&self, other: &Self) -> bool { self.priority() == other.priority() }
+}
+impl Eq for Job {}
+impl PartialOrd for Job {
+	fn partial_cmp(&self, other: &Self) -> Option<Ordering> { Some(self.cmp(other)) }
+}
+impl Ord for Job {
+	fn cmp(&self, other: &Self) -> Ordering { self.priority().cmp(&other.priority()) }
+}
+
+/// The jobs shared with the threads
+#[derive(Debug, Default)]
+struct Queue {
+	jobs: BinaryHeap<Job>,
+	shutdown: bool,
+}
+
+#[derive(Debug, Default)]
+struct Shared {
+	queue: Mutex<Queue>,
+	ready: Condvar, // a job came in or the threads have to stop
+}
+
+/// Generates chunks on background threads, the ones nearest to the player first
+/// The threads only start with the first chunk asked for, a world that never generates anything has none
+#[derive(Debug)]
+pub struct ChunkWorkers {
+	shared: Arc<Shared>,
+	sender: mpsc::Sender<(ChunkCoord, u32, Chunk)>,
+	receiver: mpsc::Receiver<(ChunkCoord, u32, Chunk)>,
+	threads: Vec<thread::JoinHandle<()>>,
+	pending: HashMap<ChunkCoord, u32>, // asked for and not put into the world yet, with the request waited for
+	next_request: u32,
+	edited: HashSet<ChunkCoord>, // pending ones blocks were set in meanwhile, the generated chunk takes them in
+	done: Vec<(ChunkCoord, u32, Chunk)>, // finished, waiting for a later update
+	center: ChunkCoord, // the jobs are ordered by the distance to it
+}
+
+impl Default for ChunkWorkers {
+	fn default() -> Self {
+		let (sender, receiver) = mpsc::channel();
+		Self {
+			shared: Arc::new(Shared::default()),
+			sender,
+			receiver,
+			threads: Vec::new(),
+			pending: HashMap::new(),
+			next_request: 0,
+			edited: HashSet::new(),
+			done: Vec::new(),
+			center: ChunkCoord::new(0, 0, 0),
+		}
+	}
+}
+
+impl Clone for ChunkWorkers {
+	/// A copy of a world doesn't share the chunks being generated, it starts with no workers
+	fn clone(&self) -> Self {
+		Self::default()
+	}
+}
+
+#[allow(dead_code)]
+impl ChunkWorkers {
+	#[inline] pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Whether the chunk is being generated (or waits to be put into the world)
+	#[inline] pub fn is_pending(&self, coord: ChunkCoord) -> bool {
+		self.pending.contains_key(&coord)
+	}
+	#[inline] pub fn pending_count(&self) -> usize {
+		self.pending.len()
+	}
+
+	fn spawn_threads(&mut self) {
+		let count = thread::available_parallelism().map_or(1, |cores| cores.get().saturating_sub(1)).clamp(1, MAX_WORKERS);
+		for index in 0..count {
+			let (shared, sender) = (Arc::clone(&self.shared), self.sender.clone());
+			let handle = thread::Builder::new()
+				.name(format!("chunk-worker-{index}"))
+				.spawn(move || work(&shared, &sender));
+			match handle {
+				Ok(handle) => self.threads.push(handle),
+				Err(e) => eprintln!("Failed to start a chunk worker: {}", e),
+			}
+		}
+	}
+
+	/// Queues the chunk to be generated with the generator, unless it already is
+	pub fn request(&mut self, coord: ChunkCoord, generator: &Arc<dyn WorldGenerator>) {
+		if self.is_pending(coord) {
+			return;
+		}
+		let request = self.next_request;
+		self.next_request = self.next_request.wrapping_add(1);
+		self.pending.insert(coord, request);
+		if self.threads.is_empty() {
+			self.spawn_threads();
+		}
+		let job = Job { distance: distance_sq(coord, self.center), coord, request, generator: Arc::clone(generator) };
+		self.shared.queue.lock().unwrap().jobs.push(job);
+		self.shared.ready.notify_one();
+	}
+
+	/// Forgets the chunk (it was put into the world some other way), a worker already on it finishes it but it is thrown away
+	pub fn cancel(&mut self, coord: ChunkCoord) {
+		if self.pending.remove(&coord).is_none() {
+			return;
+		}
+		self.edited.remove(&coord);
+		self.shared.queue.lock().unwrap().jobs.retain(|job| job.coord != coord);
+	}
+
+	/// Marks a pending chunk as having blocks set in it before it is done, false if it is not pending
+	pub fn keep_edits(&mut self, coord: ChunkCoord) -> bool {
+		if !self.is_pending(coord) {
+			return false;
+		}
+		self.edited.insert(coord);
+		true
+	}
+
+	/// Whether blocks were set in the chunk while it was generated, they have to go into it (forgets it)
+	#[inline] pub fn take_edits(&mut self, coord: ChunkCoord) -> bool {
+		self.edited.remove(&coord)
+	}
+
+	/// Drops the chunks out of range (a worker already on one finishes it, but it is thrown away)
+	/// and orders the rest by the distance to the new center
+	pub fn retarget(&mut self, center: ChunkCoord, radius_sq: i32) {
+		if center == self.center {
+			return;
+		}
+		self.center = center;
+		self.pending.retain(|coord, _| distance_sq(*coord, center) <= radius_sq);
+		let pending = &self.pending;
+		self.edited.retain(|coord| pending.contains_key(coord));
+		let mut queue = self.shared.queue.lock().unwrap();
+		let jobs = std::mem::take(&mut queue.jobs).into_vec();
+		queue.jobs = jobs.into_iter()
+			.filter(|job| pending.get(&job.coord) == Some(&job.request))
+			.map(|job| Job { distance: distance_sq(job.coord, center), ..job })
+			.collect();
+	}
+
+	/// The nearest generated chunks still wanted, at most `limit` of them, the rest stay for later
+	pub fn finished(&mut self, limit: usize) -> Vec<(ChunkCoord, Chunk)> {
+		self.done.extend(self.receiver.try_iter());
+		let pending = &self.pending;
+		self.done.retain(|(coord, request, _)| pending.get(coord) == Some(request));
+		// the farthest go to the front, the nearest are split off the end
+		let center = self.center;
+		self.done.sort_by_key(|(coord, _, _)| (-distance_sq(*coord, center), coord.y()));
+		let finished = self.done.split_off(self.done.len().saturating_sub(limit));
+		finished.into_iter()
+			.map(|(coord, _, chunk)| {
+				self.pending.remove(&coord);
+				(coord, chunk)
+			})
+			.collect()
+	}
+}
+
+impl Drop for ChunkWorkers {
+	fn drop(&mut self) {
+		if let Ok(mut queue) = self.shared.queue.lock() {
+			queue.shutdown = true;
+			queue.jobs.clear();
+		}
+		self.shared.ready.notify_all();
+		for handle in self.threads.drain(..) {
+			let _ = handle.join();
+		}
+	}
+}
+
+/// What a worker thread does: takes the nearest job, generates it and sends it back, until the pool goes away
+fn work(shared: &Shared, sender: &mpsc::Sender<(ChunkCoord, u32, Chunk)>) {
+	loop {
+		let job = {
+			let mut queue = shared.queue.lock().unwrap();
+			loop {
+				if queue.shutdown {
+					return;
+				}
+				if let Some(job) = queue.jobs.pop() {
+					break job;
+				}
+				queue = shared.ready.wait(queue).unwrap();
+			}
+		};
+		let chunk = job.generator.generate(job.coord);
+		if sender.send((job.coord, job.request, chunk)).is_err() {
+			return;
+		}
+	}
+}